@@ -0,0 +1,186 @@
+// Remote agent
+// Polls glazewm and serves snapshots and diffs to viewers over TCP, so the
+// TUI can run on another machine or inside WSL (`--connect`)
+
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::watch;
+use tokio::time::interval;
+use tracing::{debug, info, warn};
+
+use crate::app::UpdateLoop;
+use crate::cli::{AgentMessage, OutcomeChange, QueryOutcome};
+
+/// Outcome of one poll of glazewm
+#[derive(Debug, Clone, PartialEq)]
+struct Poll {
+    seq: u64,
+    monitors: QueryOutcome,
+    windows: QueryOutcome,
+}
+
+/// Serves the raw results of the update loop's glazewm queries
+pub struct Agent {
+    update_loop: UpdateLoop,
+    listener: TcpListener,
+}
+
+impl Agent {
+    /// Listen on `address`; viewers connect with `--connect`
+    pub async fn bind(
+        address: impl ToSocketAddrs,
+        update_loop: UpdateLoop,
+    ) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(address).await?;
+        Ok(Self {
+            update_loop,
+            listener,
+        })
+    }
+
+    pub fn local_addr(&self) -> std::io::Result<std::net::SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Poll at the refresh interval and publish changes until the
+    /// application stops
+    pub async fn run(self) -> std::io::Result<()> {
+        let Agent {
+            update_loop,
+            listener,
+        } = self;
+        info!("Agent listening on {}", listener.local_addr()?);
+        let (sender, receiver) = watch::channel(Arc::new(poll(&update_loop, 0).await));
+
+        let accept = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        info!("Viewer connected from {}", peer);
+                        tokio::spawn(serve(stream, receiver.clone()));
+                    }
+                    Err(e) => warn!("Failed to accept viewer: {}", e),
+                }
+            }
+        });
+
+        let state = update_loop.state().clone();
+        let mut timer = interval(update_loop.config().refresh_interval);
+        let mut seq = 0;
+        while state.is_running().await {
+            timer.tick().await;
+            seq += 1;
+
+            let latest = poll(&update_loop, seq).await;
+            let changed = {
+                let last = sender.borrow();
+                last.monitors != latest.monitors || last.windows != latest.windows
+            };
+            if changed {
+                debug!("Publishing poll {}", seq);
+                sender.send_replace(Arc::new(latest));
+            }
+        }
+
+        accept.abort();
+        Ok(())
+    }
+}
+
+/// Query glazewm through the update loop's client
+async fn poll(update_loop: &UpdateLoop, seq: u64) -> Poll {
+    let (monitors, windows) = update_loop.query_raw().await;
+    Poll {
+        seq,
+        monitors: QueryOutcome::from_result(monitors),
+        windows: QueryOutcome::from_result(windows),
+    }
+}
+
+/// Send one viewer a snapshot, then a diff for every published poll
+async fn serve(mut stream: TcpStream, mut polls: watch::Receiver<Arc<Poll>>) {
+    let mut sent: Option<Arc<Poll>> = None;
+
+    loop {
+        let poll = polls.borrow_and_update().clone();
+        let message = AgentMessage {
+            seq: poll.seq,
+            monitors: OutcomeChange::between(sent.as_ref().map(|p| &p.monitors), &poll.monitors),
+            windows: OutcomeChange::between(sent.as_ref().map(|p| &p.windows), &poll.windows),
+        };
+
+        let mut line = match serde_json::to_string(&message) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Failed to encode agent message: {}", e);
+                return;
+            }
+        };
+        line.push('\n');
+        if let Err(e) = stream.write_all(line.as_bytes()).await {
+            debug!("Viewer disconnected: {}", e);
+            return;
+        }
+        sent = Some(poll);
+
+        if polls.changed().await.is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::update::UpdateConfig;
+    use crate::app::AppState;
+    use crate::cli::{GlazewmClient, GlazewmParser, RemoteGlazewmClient};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn viewer_should_follow_agent_on_localhost() {
+        let config = UpdateConfig {
+            refresh_interval: Duration::from_millis(20),
+            ..Default::default()
+        };
+        let agent_state = AppState::new();
+        let agent = Agent::bind(
+            "127.0.0.1:0",
+            UpdateLoop::new_demo(config.clone(), agent_state.clone()),
+        )
+        .await
+        .unwrap();
+        let address = agent.local_addr().unwrap();
+        let agent = tokio::spawn(agent.run());
+
+        let viewer = RemoteGlazewmClient::connect(address.to_string());
+        let monitors = viewer.query_monitors().await.unwrap();
+        let windows = viewer.query_windows().await.unwrap();
+        assert_eq!(
+            GlazewmParser::parse_monitors(&monitors)
+                .unwrap()
+                .monitors
+                .len(),
+            2
+        );
+        assert_eq!(windows["data"]["windows"].as_array().unwrap().len(), 5);
+
+        agent_state.stop().await;
+        agent.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn viewer_should_report_unreachable_agent() {
+        // Bind and drop a listener to get a port nobody listens on
+        let address = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let viewer = RemoteGlazewmClient::connect(address.to_string());
+        let error = viewer.query_monitors().await.unwrap_err();
+        assert!(error.to_string().contains("cannot connect to agent"));
+    }
+}
@@ -0,0 +1,557 @@
+// Layout assertions
+// Expectations about the monitor tree declared in a JSON spec file, e.g. to
+// check in CI that a shared glazewm config produces the intended layout
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Write};
+use std::path::Path;
+use thiserror::Error;
+
+use crate::domain::{Monitor, Window, WindowState, Workspace};
+
+/// Errors raised while loading or validating a spec
+#[derive(Error, Debug)]
+pub enum SpecError {
+    #[error("cannot read spec {path}: {message}")]
+    Io { path: String, message: String },
+
+    #[error("invalid spec: {0}")]
+    Format(#[from] serde_json::Error),
+
+    #[error("assertion {index}: {message}")]
+    Invalid { index: usize, message: String },
+}
+
+/// Expectations checked against one snapshot, loaded from JSON
+///
+/// ```json
+/// {
+///   "name": "Office docking station",
+///   "assertions": [
+///     { "expect": "workspace_on_monitor", "workspace": "1", "monitor": { "hardware_id": "DEL4321" } },
+///     { "expect": "window_state", "window": { "process": "Code" }, "state": "tiling", "on_displayed_workspace": true },
+///     { "expect": "window_on_workspace", "window": { "title_contains": "Slack" }, "workspace": "3" },
+///     { "expect": "window_count", "workspace": "1", "min": 1, "max": 4 },
+///     { "expect": "no_offscreen_windows" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LayoutSpec {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub assertions: Vec<Assertion>,
+}
+
+/// One expectation; workspaces are named by name or display name
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "expect", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Assertion {
+    WorkspaceExists {
+        workspace: String,
+    },
+    WorkspaceOnMonitor {
+        workspace: String,
+        monitor: MonitorSelector,
+    },
+    /// Every matching window is in `state`, and there is at least one
+    WindowState {
+        window: WindowSelector,
+        /// `tiling`, `floating`, `minimized` or `fullscreen`
+        state: String,
+        #[serde(default)]
+        on_displayed_workspace: bool,
+    },
+    /// Every matching window is on the workspace, and there is at least one
+    WindowOnWorkspace {
+        window: WindowSelector,
+        workspace: String,
+    },
+    /// Windows on a workspace, or on all of them
+    WindowCount {
+        #[serde(default)]
+        workspace: Option<String>,
+        #[serde(default)]
+        min: Option<usize>,
+        #[serde(default)]
+        max: Option<usize>,
+    },
+    /// Every shown, non-minimized window overlaps a monitor
+    NoOffscreenWindows,
+}
+
+/// Monitor by any combination of id, hardware id and device name
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MonitorSelector {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub hardware_id: Option<String>,
+    #[serde(default)]
+    pub device_name: Option<String>,
+}
+
+/// Windows by process name (ignoring case) and part of their title
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WindowSelector {
+    #[serde(default)]
+    pub process: Option<String>,
+    #[serde(default)]
+    pub title_contains: Option<String>,
+}
+
+/// Result of checking one assertion
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Outcome {
+    pub assertion: String,
+    pub passed: bool,
+    /// What did not match
+    pub failures: Vec<String>,
+}
+
+impl MonitorSelector {
+    fn is_empty(&self) -> bool {
+        self.id.is_none() && self.hardware_id.is_none() && self.device_name.is_none()
+    }
+
+    fn matches(&self, monitor: &Monitor) -> bool {
+        self.id
+            .as_deref()
+            .is_none_or(|id| monitor.id().as_str() == id)
+            && self
+                .hardware_id
+                .as_deref()
+                .is_none_or(|id| monitor.hardware_id() == Some(id))
+            && self
+                .device_name
+                .as_deref()
+                .is_none_or(|name| monitor.device_name() == name)
+    }
+}
+
+impl fmt::Display for MonitorSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = [
+            self.id.as_ref().map(|id| format!("id {}", id)),
+            self.hardware_id
+                .as_ref()
+                .map(|id| format!("hardware id {}", id)),
+            self.device_name
+                .as_ref()
+                .map(|name| format!("device name {}", name)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        write!(f, "monitor with {}", parts.join(" and "))
+    }
+}
+
+impl WindowSelector {
+    fn is_empty(&self) -> bool {
+        self.process.is_none() && self.title_contains.is_none()
+    }
+
+    fn matches(&self, window: &Window) -> bool {
+        self.process
+            .as_deref()
+            .is_none_or(|process| window.process_name().eq_ignore_ascii_case(process))
+            && self
+                .title_contains
+                .as_deref()
+                .is_none_or(|part| window.title().contains(part))
+    }
+}
+
+impl fmt::Display for WindowSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.process, &self.title_contains) {
+            (Some(process), Some(part)) => {
+                write!(f, "process {} titled \"*{}*\"", process, part)
+            }
+            (Some(process), None) => write!(f, "process {}", process),
+            (None, Some(part)) => write!(f, "window titled \"*{}*\"", part),
+            (None, None) => write!(f, "any window"),
+        }
+    }
+}
+
+impl fmt::Display for Assertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Assertion::WorkspaceExists { workspace } => write!(f, "workspace {} exists", workspace),
+            Assertion::WorkspaceOnMonitor { workspace, monitor } => {
+                write!(f, "workspace {} is on {}", workspace, monitor)
+            }
+            Assertion::WindowState {
+                window,
+                state,
+                on_displayed_workspace,
+            } => {
+                write!(f, "{} is {}", window, state)?;
+                if *on_displayed_workspace {
+                    write!(f, " on a displayed workspace")?;
+                }
+                Ok(())
+            }
+            Assertion::WindowOnWorkspace { window, workspace } => {
+                write!(f, "{} is on workspace {}", window, workspace)
+            }
+            Assertion::WindowCount {
+                workspace,
+                min,
+                max,
+            } => {
+                match workspace {
+                    Some(workspace) => write!(f, "workspace {} has ", workspace)?,
+                    None => write!(f, "there are ")?,
+                }
+                match (min, max) {
+                    (Some(min), Some(max)) if min == max => write!(f, "{}", min)?,
+                    (Some(min), Some(max)) => write!(f, "{} to {}", min, max)?,
+                    (Some(min), None) => write!(f, "at least {}", min)?,
+                    (None, Some(max)) => write!(f, "at most {}", max)?,
+                    (None, None) => write!(f, "any number of")?,
+                }
+                write!(f, " window(s)")
+            }
+            Assertion::NoOffscreenWindows => write!(f, "no window is off-screen"),
+        }
+    }
+}
+
+/// A window with the workspace and monitor it is on
+struct Placed<'a> {
+    window: &'a Window,
+    workspace: &'a Workspace,
+}
+
+fn placed_windows(monitors: &[Monitor]) -> Vec<Placed<'_>> {
+    monitors
+        .iter()
+        .flat_map(Monitor::workspaces)
+        .flat_map(|workspace| {
+            workspace
+                .windows()
+                .iter()
+                .map(move |window| Placed { window, workspace })
+        })
+        .collect()
+}
+
+fn matching_windows<'a>(monitors: &'a [Monitor], selector: &WindowSelector) -> Vec<Placed<'a>> {
+    placed_windows(monitors)
+        .into_iter()
+        .filter(|placed| selector.matches(placed.window))
+        .collect()
+}
+
+fn is_named(workspace: &Workspace, name: &str) -> bool {
+    workspace.name() == name || workspace.label() == name
+}
+
+fn find_workspace<'a>(monitors: &'a [Monitor], name: &str) -> Option<(&'a Workspace, &'a Monitor)> {
+    monitors.iter().find_map(|monitor| {
+        monitor
+            .workspaces()
+            .iter()
+            .find(|workspace| is_named(workspace, name))
+            .map(|workspace| (workspace, monitor))
+    })
+}
+
+fn describe_monitor(monitor: &Monitor) -> String {
+    match monitor.hardware_id() {
+        Some(hardware_id) => format!("monitor {} (hardware id {})", monitor.id(), hardware_id),
+        None => format!("monitor {}", monitor.id()),
+    }
+}
+
+impl Assertion {
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Assertion::WorkspaceOnMonitor { monitor, .. } if monitor.is_empty() => {
+                Err("monitor needs an id, hardware_id or device_name".to_string())
+            }
+            Assertion::WindowState { window, .. } | Assertion::WindowOnWorkspace { window, .. }
+                if window.is_empty() =>
+            {
+                Err("window needs a process or title_contains".to_string())
+            }
+            Assertion::WindowState { state, .. } => {
+                let known = [
+                    WindowState::Tiling,
+                    WindowState::Floating,
+                    WindowState::Minimized,
+                    WindowState::Fullscreen,
+                ];
+                if known.iter().any(|known| known.to_string() == *state) {
+                    Ok(())
+                } else {
+                    Err(format!("unknown window state '{}'", state))
+                }
+            }
+            Assertion::WindowCount {
+                min: None,
+                max: None,
+                ..
+            } => Err("window_count needs min, max or both".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check the assertion, returning what did not match
+    pub fn check(&self, monitors: &[Monitor]) -> Vec<String> {
+        match self {
+            Assertion::WorkspaceExists { workspace } => match find_workspace(monitors, workspace) {
+                Some(_) => Vec::new(),
+                None => vec![format!("workspace {} not found", workspace)],
+            },
+            Assertion::WorkspaceOnMonitor { workspace, monitor } => {
+                let Some((_, actual)) = find_workspace(monitors, workspace) else {
+                    return vec![format!("workspace {} not found", workspace)];
+                };
+                if monitor.matches(actual) {
+                    return Vec::new();
+                }
+                let mut failures = vec![format!(
+                    "workspace {} is on {}",
+                    workspace,
+                    describe_monitor(actual)
+                )];
+                if !monitors.iter().any(|m| monitor.matches(m)) {
+                    failures.push(format!("no {} is connected", monitor));
+                }
+                failures
+            }
+            Assertion::WindowState {
+                window,
+                state,
+                on_displayed_workspace,
+            } => {
+                let matching = matching_windows(monitors, window);
+                if matching.is_empty() {
+                    return vec![format!("no {} found", window)];
+                }
+                let mut failures = Vec::new();
+                for placed in matching {
+                    let actual = placed.window.state().to_string();
+                    if actual != *state {
+                        failures.push(format!("{} is {}", placed.window.display_name(), actual));
+                    }
+                    if *on_displayed_workspace && !placed.workspace.is_visible() {
+                        failures.push(format!(
+                            "{} is on hidden workspace {}",
+                            placed.window.display_name(),
+                            placed.workspace.label()
+                        ));
+                    }
+                }
+                failures
+            }
+            Assertion::WindowOnWorkspace { window, workspace } => {
+                let matching = matching_windows(monitors, window);
+                if matching.is_empty() {
+                    return vec![format!("no {} found", window)];
+                }
+                matching
+                    .iter()
+                    .filter(|placed| !is_named(placed.workspace, workspace))
+                    .map(|placed| {
+                        format!(
+                            "{} is on workspace {}",
+                            placed.window.display_name(),
+                            placed.workspace.label()
+                        )
+                    })
+                    .collect()
+            }
+            Assertion::WindowCount {
+                workspace,
+                min,
+                max,
+            } => {
+                let count = match workspace {
+                    Some(name) => match find_workspace(monitors, name) {
+                        Some((workspace, _)) => workspace.window_count(),
+                        None => return vec![format!("workspace {} not found", name)],
+                    },
+                    None => monitors.iter().map(Monitor::total_window_count).sum(),
+                };
+                let too_few = min.is_some_and(|min| count < min);
+                let too_many = max.is_some_and(|max| count > max);
+                if too_few || too_many {
+                    vec![format!("found {} window(s)", count)]
+                } else {
+                    Vec::new()
+                }
+            }
+            Assertion::NoOffscreenWindows => placed_windows(monitors)
+                .iter()
+                .map(|placed| placed.window)
+                .filter(|window| window.is_visible() && *window.state() != WindowState::Minimized)
+                .filter(|window| {
+                    !monitors
+                        .iter()
+                        .any(|monitor| monitor.geometry().intersects(window.geometry()))
+                })
+                .map(|window| format!("{} is at {}", window.display_name(), window.geometry()))
+                .collect(),
+        }
+    }
+}
+
+impl LayoutSpec {
+    /// Parse and validate a spec from JSON text
+    pub fn from_json(text: &str) -> Result<Self, SpecError> {
+        let spec: Self = serde_json::from_str(text)?;
+        for (index, assertion) in spec.assertions.iter().enumerate() {
+            assertion
+                .validate()
+                .map_err(|message| SpecError::Invalid { index, message })?;
+        }
+        Ok(spec)
+    }
+
+    /// Load a spec file
+    pub fn load(path: &Path) -> Result<Self, SpecError> {
+        let text = std::fs::read_to_string(path).map_err(|e| SpecError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Self::from_json(&text)
+    }
+
+    /// Check every assertion, in order
+    pub fn evaluate(&self, monitors: &[Monitor]) -> Vec<Outcome> {
+        self.assertions
+            .iter()
+            .map(|assertion| {
+                let failures = assertion.check(monitors);
+                Outcome {
+                    assertion: assertion.to_string(),
+                    passed: failures.is_empty(),
+                    failures,
+                }
+            })
+            .collect()
+    }
+}
+
+/// One `PASS`/`FAIL` line per assertion, failures indented below, and a count
+pub fn format_outcomes(outcomes: &[Outcome]) -> String {
+    let mut out = String::new();
+    for outcome in outcomes {
+        let status = if outcome.passed { "PASS" } else { "FAIL" };
+        let _ = writeln!(out, "{} {}", status, outcome.assertion);
+        for failure in &outcome.failures {
+            let _ = writeln!(out, "     {}", failure);
+        }
+    }
+    let passed = outcomes.iter().filter(|outcome| outcome.passed).count();
+    let _ = writeln!(out, "{} passed, {} failed", passed, outcomes.len() - passed);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{GlazewmParser, Simulator};
+
+    fn demo_monitors(simulator: &Simulator) -> Vec<Monitor> {
+        GlazewmParser::parse_monitors(&simulator.monitors_json())
+            .unwrap()
+            .monitors
+    }
+
+    #[test]
+    fn should_pass_and_fail_assertions_against_the_tree() {
+        let mut simulator = Simulator::demo();
+        simulator.execute("toggle-floating").unwrap();
+        let monitors = demo_monitors(&simulator);
+        let hardware_id = monitors[0].hardware_id().unwrap().to_string();
+        let spec = LayoutSpec::from_json(
+            &serde_json::json!({
+                "assertions": [
+                    { "expect": "workspace_on_monitor", "workspace": "Development", "monitor": { "hardware_id": hardware_id } },
+                    { "expect": "window_state", "window": { "process": "code" }, "state": "tiling", "on_displayed_workspace": true },
+                    { "expect": "window_state", "window": { "process": "firefox" }, "state": "tiling" },
+                    { "expect": "window_count", "min": 5, "max": 5 },
+                    { "expect": "no_offscreen_windows" }
+                ]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let outcomes = spec.evaluate(&monitors);
+        let passed: Vec<bool> = outcomes.iter().map(|outcome| outcome.passed).collect();
+        assert_eq!(passed, [true, true, false, true, true]);
+        assert_eq!(
+            outcomes[1].assertion,
+            "process code is tiling on a displayed workspace"
+        );
+        assert_eq!(
+            outcomes[2].failures,
+            ["firefox: Firefox - Documentation is floating"]
+        );
+
+        let text = format_outcomes(&outcomes);
+        assert!(text.contains(
+            "FAIL process firefox is tiling\n     firefox: Firefox - Documentation is floating\n"
+        ));
+        assert!(text.ends_with("4 passed, 1 failed\n"));
+    }
+
+    #[test]
+    fn should_report_missing_workspaces_and_offscreen_windows() {
+        let mut simulator = Simulator::demo();
+        simulator.remove_monitor(1).unwrap();
+        let mut json = simulator.monitors_json();
+        let window = &mut json["data"]["monitors"][0]["children"][0]["children"][0];
+        window["x"] = serde_json::json!(-32000);
+        window["y"] = serde_json::json!(-32000);
+        let monitors = GlazewmParser::parse_monitors(&json).unwrap().monitors;
+
+        let missing = Assertion::WorkspaceOnMonitor {
+            workspace: "9".to_string(),
+            monitor: MonitorSelector {
+                id: Some("m".to_string()),
+                ..Default::default()
+            },
+        };
+        assert_eq!(missing.check(&monitors), ["workspace 9 not found"]);
+
+        let offscreen = Assertion::NoOffscreenWindows.check(&monitors);
+        assert_eq!(offscreen.len(), 1);
+        assert!(offscreen[0].ends_with("is at (-32000, -32000) 960x1040"));
+    }
+
+    #[test]
+    fn should_reject_invalid_specs() {
+        let invalid = |json: serde_json::Value| LayoutSpec::from_json(&json.to_string());
+
+        assert!(matches!(
+            invalid(
+                serde_json::json!({ "assertions": [{ "expect": "window_state", "window": {}, "state": "tiling" }] })
+            ),
+            Err(SpecError::Invalid { index: 0, .. })
+        ));
+        assert!(matches!(
+            invalid(serde_json::json!({ "assertions": [
+                { "expect": "no_offscreen_windows" },
+                { "expect": "window_state", "window": { "process": "a" }, "state": "sideways" }
+            ] })),
+            Err(SpecError::Invalid { index: 1, .. })
+        ));
+        assert!(matches!(
+            invalid(
+                serde_json::json!({ "assertions": [{ "expect": "workspace_exists", "workspce": "1" }] })
+            ),
+            Err(SpecError::Format(_))
+        ));
+    }
+}
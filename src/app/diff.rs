@@ -0,0 +1,166 @@
+// Snapshot diff output
+// Renders a `SnapshotDiff` for the `diff` command, one line per node and one
+// indented line per changed property
+
+use crossterm::style::{Color, Stylize};
+use std::fmt::Write;
+
+use crate::domain::snapshot_diff::{DiffKind, FieldChange, MatchedBy};
+use crate::domain::SnapshotDiff;
+
+/// Marker and color of each kind of entry
+fn marker(kind: DiffKind) -> (&'static str, Color) {
+    match kind {
+        DiffKind::Added => ("+", Color::Green),
+        DiffKind::Removed => ("-", Color::Red),
+        DiffKind::Moved => (">", Color::Yellow),
+        DiffKind::Changed => ("~", Color::Cyan),
+    }
+}
+
+struct Printer {
+    out: String,
+    color: bool,
+}
+
+impl Printer {
+    fn section(&mut self, title: &str) {
+        if self.color {
+            let _ = writeln!(self.out, "{}", title.bold());
+        } else {
+            let _ = writeln!(self.out, "{}", title);
+        }
+    }
+
+    fn entry(
+        &mut self,
+        kind: DiffKind,
+        text: String,
+        matched_by: Option<MatchedBy>,
+        changes: &[FieldChange],
+    ) {
+        let (marker, color) = marker(kind);
+        let mut line = format!("{} {}", marker, text);
+        // Matching by id is the norm; anything else is worth double-checking
+        if let Some(matched_by) = matched_by.filter(|m| *m != MatchedBy::Id) {
+            let _ = write!(line, " (matched by {})", matched_by);
+        }
+        if self.color {
+            let _ = writeln!(self.out, "  {}", line.with(color));
+        } else {
+            let _ = writeln!(self.out, "  {}", line);
+        }
+        for change in changes {
+            let _ = writeln!(
+                self.out,
+                "      {}: {} -> {}",
+                change.field, change.from, change.to
+            );
+        }
+    }
+}
+
+/// Render the diff as text, with ANSI colors if `color` is set
+pub fn format_diff(diff: &SnapshotDiff, color: bool) -> String {
+    if diff.is_empty() {
+        return "No changes\n".to_string();
+    }
+    let mut printer = Printer {
+        out: String::new(),
+        color,
+    };
+
+    if !diff.monitors.is_empty() {
+        printer.section("Monitors");
+        for entry in &diff.monitors {
+            printer.entry(
+                entry.kind,
+                entry.monitor.to_string(),
+                entry.matched_by,
+                &entry.changes,
+            );
+        }
+    }
+
+    if !diff.workspaces.is_empty() {
+        printer.section("Workspaces");
+        for entry in &diff.workspaces {
+            let text = match &entry.from_monitor {
+                Some(from) => format!(
+                    "{} moved from monitor {} to {}",
+                    entry.workspace, from, entry.monitor
+                ),
+                None => format!("{} on monitor {}", entry.workspace, entry.monitor),
+            };
+            printer.entry(entry.kind, text, entry.matched_by, &entry.changes);
+        }
+    }
+
+    if !diff.windows.is_empty() {
+        printer.section("Windows");
+        for entry in &diff.windows {
+            let text = match &entry.from_workspace {
+                Some(from) => format!(
+                    "{} moved from workspace {} to {}",
+                    entry.window, from, entry.workspace
+                ),
+                None => format!("{} on workspace {}", entry.window, entry.workspace),
+            };
+            printer.entry(entry.kind, text, entry.matched_by, &entry.changes);
+        }
+    }
+
+    let _ = writeln!(
+        printer.out,
+        "{} added, {} removed, {} moved, {} changed",
+        diff.count(DiffKind::Added),
+        diff.count(DiffKind::Removed),
+        diff.count(DiffKind::Moved),
+        diff.count(DiffKind::Changed)
+    );
+    printer.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{GlazewmParser, Simulator};
+    use crate::domain::snapshot_diff;
+    use crate::domain::Monitor;
+
+    fn monitors(simulator: &Simulator) -> Vec<Monitor> {
+        GlazewmParser::parse_monitors(&simulator.monitors_json())
+            .unwrap()
+            .monitors
+    }
+
+    #[test]
+    fn should_print_entries_with_their_changes() {
+        let mut simulator = Simulator::demo();
+        let before = monitors(&simulator);
+        simulator
+            .execute("shell-exec notepad Untitled - Notepad")
+            .unwrap();
+        let after = monitors(&simulator);
+
+        let text = format_diff(&snapshot_diff::diff(&before, &after), false);
+
+        assert!(text.starts_with("Windows\n"));
+        assert!(text.contains("  + notepad: Untitled - Notepad on workspace Development\n"));
+        assert!(text.contains("      focus: focused -> unfocused\n"));
+        assert!(text.ends_with("\n1 added, 0 removed, 0 moved, 2 changed\n"));
+        assert!(!text.contains('\x1b'));
+    }
+
+    #[test]
+    fn should_color_entries_on_request() {
+        let mut simulator = Simulator::demo();
+        let before = monitors(&simulator);
+        simulator.remove_monitor(1).unwrap();
+        let after = monitors(&simulator);
+
+        let text = format_diff(&snapshot_diff::diff(&before, &after), true);
+        assert!(text.contains('\x1b'));
+        assert_eq!(format_diff(&SnapshotDiff::default(), true), "No changes\n");
+    }
+}
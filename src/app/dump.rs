@@ -0,0 +1,164 @@
+// Plain-text state dump
+// Prints the parsed glazewm state without a terminal, for scripts and tests
+
+use serde::Serialize;
+use std::fmt::Write;
+
+use crate::app::AppState;
+use crate::cli::ParseWarning;
+use crate::domain::{Inconsistency, LayoutDeviation, Monitor};
+
+/// Outcome of the last update: its error and what parsing and
+/// reconciliation found. Also the body of the HTTP API's `/validate`.
+#[derive(Debug, Serialize)]
+pub struct Validation {
+    pub valid: bool,
+    pub last_error: Option<String>,
+    pub warnings: Vec<ParseWarning>,
+    pub inconsistencies: Vec<Inconsistency>,
+    /// Reported, but apps enforcing a minimum size cause them legitimately,
+    /// so they do not make the state invalid
+    pub layout_deviations: Vec<LayoutDeviation>,
+}
+
+impl Validation {
+    pub async fn from_state(state: &AppState) -> Self {
+        let last_error = state.get_last_error().await;
+        let warnings = state.get_parse_warnings().await;
+        let inconsistencies = state.get_inconsistencies().await;
+        Self {
+            valid: last_error.is_none() && warnings.is_empty() && inconsistencies.is_empty(),
+            last_error,
+            warnings,
+            inconsistencies,
+            layout_deviations: state.get_layout_deviations().await,
+        }
+    }
+
+    /// The findings as text, or a single line saying there are none
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        if let Some(error) = &self.last_error {
+            let _ = writeln!(out, "Update failed: {}", error);
+        }
+        write_findings(&mut out, &self.warnings, &self.inconsistencies);
+        if !self.layout_deviations.is_empty() {
+            let _ = writeln!(out, "Layout deviations ({}):", self.layout_deviations.len());
+            for deviation in &self.layout_deviations {
+                let _ = writeln!(out, "  {}", deviation);
+            }
+        }
+        if self.valid {
+            out.push_str("No problems found\n");
+        }
+        out
+    }
+}
+
+/// Render the monitor tree, parse warnings and inconsistencies as indented text
+pub fn format_state(
+    monitors: &[Monitor],
+    warnings: &[ParseWarning],
+    inconsistencies: &[Inconsistency],
+) -> String {
+    let mut out = String::new();
+
+    if monitors.is_empty() {
+        out.push_str("No monitors\n");
+    }
+
+    for monitor in monitors {
+        let _ = writeln!(
+            out,
+            "Monitor {} ({}){}",
+            monitor.id(),
+            monitor.geometry().size,
+            focus_marker(monitor.is_focused())
+        );
+
+        for workspace in monitor.workspaces() {
+            let _ = writeln!(
+                out,
+                "  Workspace {} [{} window(s)]{}",
+                workspace.label(),
+                workspace.window_count(),
+                focus_marker(workspace.is_focused())
+            );
+
+            for window in workspace.windows() {
+                let _ = writeln!(
+                    out,
+                    "    {} {} ({}){}",
+                    window.state_indicator(),
+                    window.display_name(),
+                    window.geometry().size,
+                    focus_marker(window.is_focused())
+                );
+            }
+        }
+    }
+
+    write_findings(&mut out, warnings, inconsistencies);
+    out
+}
+
+fn write_findings(out: &mut String, warnings: &[ParseWarning], inconsistencies: &[Inconsistency]) {
+    if !warnings.is_empty() {
+        let _ = writeln!(out, "Warnings ({}):", warnings.len());
+        for warning in warnings {
+            let _ = writeln!(out, "  {}", warning);
+        }
+    }
+
+    if !inconsistencies.is_empty() {
+        let _ = writeln!(out, "Inconsistencies ({}):", inconsistencies.len());
+        for inconsistency in inconsistencies {
+            let _ = writeln!(out, "  {}", inconsistency);
+        }
+    }
+}
+
+fn focus_marker(focused: bool) -> &'static str {
+    if focused {
+        " *"
+    } else {
+        ""
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{GlazewmParser, Simulator};
+
+    #[test]
+    fn should_dump_demo_tree_with_focus_markers() {
+        let parsed = GlazewmParser::parse_monitors(&Simulator::demo().monitors_json()).unwrap();
+
+        let text = format_state(&parsed.monitors, &[], &[]);
+
+        assert!(text.starts_with("Monitor "));
+        assert!(text.contains("  Workspace "));
+        assert_eq!(text.lines().filter(|line| line.ends_with(" *")).count(), 3);
+        assert!(!text.contains("Warnings"));
+    }
+
+    #[test]
+    fn should_report_missing_monitors() {
+        assert_eq!(format_state(&[], &[], &[]), "No monitors\n");
+    }
+
+    #[tokio::test]
+    async fn should_validate_last_update() {
+        let state = AppState::new();
+        assert_eq!(
+            Validation::from_state(&state).await.to_text(),
+            "No problems found\n"
+        );
+
+        state.set_last_error(Some("timed out".to_string())).await;
+        let validation = Validation::from_state(&state).await;
+        assert!(!validation.valid);
+        assert_eq!(validation.to_text(), "Update failed: timed out\n");
+    }
+}
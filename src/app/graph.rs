@@ -0,0 +1,375 @@
+// Container tree export
+// Renders the monitor → workspace → container → window hierarchy as
+// Graphviz DOT or a Mermaid flowchart, for pasting into docs and issues
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::domain::{LayoutNode, Monitor, Window, Workspace};
+use crate::utils::stamped_files;
+
+/// Text format of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+impl GraphFormat {
+    /// File extension of exported files
+    pub fn extension(self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "dot",
+            GraphFormat::Mermaid => "mmd",
+        }
+    }
+}
+
+/// Kind of container a node stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Monitor,
+    Workspace,
+    Split,
+    Window,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Monitor => "monitor",
+            Kind::Workspace => "workspace",
+            Kind::Split => "split",
+            Kind::Window => "window",
+        }
+    }
+}
+
+/// One container, in tree order
+struct Node {
+    parent: Option<usize>,
+    kind: Kind,
+    /// Label, one entry per line
+    lines: Vec<String>,
+    attributes: Vec<(&'static str, String)>,
+    focused: bool,
+    hidden: bool,
+}
+
+fn focus(focused: bool) -> String {
+    if focused { "focused" } else { "unfocused" }.to_string()
+}
+
+/// Flatten the tree; split containers come from each workspace's layout and
+/// windows outside it (e.g. floating ones) hang off the workspace
+fn build(monitors: &[Monitor]) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    for monitor in monitors {
+        let monitor_index = nodes.len();
+        nodes.push(Node {
+            parent: None,
+            kind: Kind::Monitor,
+            lines: vec![
+                format!("Monitor {}", monitor.id()),
+                monitor.geometry().size.to_string(),
+            ],
+            attributes: vec![
+                ("id", monitor.id().to_string()),
+                ("focus", focus(monitor.is_focused())),
+            ],
+            focused: monitor.is_focused(),
+            hidden: false,
+        });
+
+        for workspace in monitor.workspaces() {
+            let workspace_index = nodes.len();
+            nodes.push(Node {
+                parent: Some(monitor_index),
+                kind: Kind::Workspace,
+                lines: vec![
+                    format!("Workspace {}", workspace.label()),
+                    format!(
+                        "{}, {}",
+                        workspace.tiling_direction(),
+                        workspace.display_state()
+                    ),
+                ],
+                attributes: vec![
+                    ("name", workspace.name().to_string()),
+                    ("focus", focus(workspace.is_focused())),
+                    ("display_state", workspace.display_state().to_string()),
+                    ("tiling_direction", workspace.tiling_direction().to_string()),
+                ],
+                focused: workspace.is_focused(),
+                hidden: !workspace.is_visible(),
+            });
+
+            let mut placed = HashSet::new();
+            add_layout(
+                &mut nodes,
+                workspace_index,
+                workspace,
+                workspace.layout(),
+                &mut placed,
+            );
+            for window in workspace.windows() {
+                if !placed.contains(window.id().as_str()) {
+                    nodes.push(window_node(workspace_index, window));
+                }
+            }
+        }
+    }
+    nodes
+}
+
+fn add_layout<'a>(
+    nodes: &mut Vec<Node>,
+    parent: usize,
+    workspace: &'a Workspace,
+    layout: &'a [LayoutNode],
+    placed: &mut HashSet<&'a str>,
+) {
+    for node in layout {
+        match node {
+            LayoutNode::Window(id) => {
+                if let Some(window) = workspace.windows().iter().find(|w| w.id() == id) {
+                    placed.insert(window.id().as_str());
+                    nodes.push(window_node(parent, window));
+                }
+            }
+            LayoutNode::Split(container) => {
+                let index = nodes.len();
+                nodes.push(Node {
+                    parent: Some(parent),
+                    kind: Kind::Split,
+                    lines: vec![format!("Split {}", container.tiling_direction())],
+                    attributes: vec![
+                        ("id", container.id().to_string()),
+                        ("tiling_direction", container.tiling_direction().to_string()),
+                    ],
+                    focused: false,
+                    hidden: false,
+                });
+                add_layout(nodes, index, workspace, container.children(), placed);
+            }
+        }
+    }
+}
+
+fn window_node(parent: usize, window: &Window) -> Node {
+    Node {
+        parent: Some(parent),
+        kind: Kind::Window,
+        lines: vec![
+            window.display_name(),
+            format!("{}, {}", window.state(), window.display_state()),
+        ],
+        attributes: vec![
+            ("process", window.process_name().to_string()),
+            ("title", window.title().to_string()),
+            ("focus", focus(window.is_focused())),
+            ("state", window.state().to_string()),
+            ("display_state", window.display_state().to_string()),
+        ],
+        focused: window.is_focused(),
+        hidden: !window.is_visible(),
+    }
+}
+
+/// Render the tree in `format`
+pub fn render(monitors: &[Monitor], format: GraphFormat) -> String {
+    match format {
+        GraphFormat::Dot => to_dot(monitors),
+        GraphFormat::Mermaid => to_mermaid(monitors),
+    }
+}
+
+/// Graphviz digraph; every property is also a node attribute
+pub fn to_dot(monitors: &[Monitor]) -> String {
+    let nodes = build(monitors);
+    let mut out = String::from(
+        "digraph glazewm {\n    rankdir=LR;\n    node [shape=box, fontname=\"monospace\"];\n",
+    );
+
+    for (index, node) in nodes.iter().enumerate() {
+        let label: Vec<String> = node.lines.iter().map(|line| dot_escape(line)).collect();
+        let shape = match node.kind {
+            Kind::Monitor => "box3d",
+            Kind::Workspace => "folder",
+            Kind::Split => "ellipse",
+            Kind::Window => "box",
+        };
+        let mut attributes = vec![
+            format!("label=\"{}\"", label.join("\\n")),
+            format!("shape={}", shape),
+            format!("kind=\"{}\"", node.kind.name()),
+        ];
+        attributes.extend(
+            node.attributes
+                .iter()
+                .map(|(key, value)| format!("{}=\"{}\"", key, dot_escape(value))),
+        );
+        let style: Vec<&str> = [(node.focused, "bold"), (node.hidden, "dashed")]
+            .into_iter()
+            .filter_map(|(on, style)| on.then_some(style))
+            .collect();
+        if !style.is_empty() {
+            attributes.push(format!("style=\"{}\"", style.join(",")));
+        }
+        let _ = writeln!(out, "    n{} [{}];", index, attributes.join(", "));
+        if let Some(parent) = node.parent {
+            let _ = writeln!(out, "    n{} -> n{};", parent, index);
+        }
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// Mermaid flowchart; focus and hidden containers are styled with classes
+pub fn to_mermaid(monitors: &[Monitor]) -> String {
+    let nodes = build(monitors);
+    let mut out = String::from("flowchart LR\n");
+
+    for (index, node) in nodes.iter().enumerate() {
+        let label: Vec<String> = node.lines.iter().map(|line| mermaid_escape(line)).collect();
+        let label = label.join("<br/>");
+        let shape = match node.kind {
+            Kind::Monitor => format!("[[\"{}\"]]", label),
+            Kind::Workspace => format!("[\"{}\"]", label),
+            Kind::Split => format!("([\"{}\"])", label),
+            Kind::Window => format!("(\"{}\")", label),
+        };
+        let _ = writeln!(out, "    n{}{}", index, shape);
+        if let Some(parent) = node.parent {
+            let _ = writeln!(out, "    n{} --> n{}", parent, index);
+        }
+    }
+
+    for (class, style, selected) in [
+        (
+            "focused",
+            "stroke-width:3px",
+            nodes.iter().map(|node| node.focused).collect::<Vec<_>>(),
+        ),
+        (
+            "hidden",
+            "stroke-dasharray:4 4",
+            nodes.iter().map(|node| node.hidden).collect(),
+        ),
+    ] {
+        let ids: Vec<String> = selected
+            .iter()
+            .enumerate()
+            .filter(|(_, selected)| **selected)
+            .map(|(index, _)| format!("n{}", index))
+            .collect();
+        if !ids.is_empty() {
+            let _ = writeln!(out, "    classDef {} {}", class, style);
+            let _ = writeln!(out, "    class {} {}", ids.join(","), class);
+        }
+    }
+    out
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Mermaid entity codes, so titles cannot break out of the label
+fn mermaid_escape(text: &str) -> String {
+    text.replace('#', "#35;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+/// Write `glazewm-tree-<time>.dot` and `.mmd` into `dir`
+pub fn write_files(monitors: &[Monitor], dir: &Path, time: SystemTime) -> io::Result<Vec<PathBuf>> {
+    let files = [GraphFormat::Dot, GraphFormat::Mermaid]
+        .map(|format| (format.extension(), render(monitors, format)));
+    stamped_files::write(dir, "glazewm-tree", time, files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{GlazewmParser, Simulator};
+
+    fn demo_monitors(simulator: &Simulator) -> Vec<Monitor> {
+        GlazewmParser::parse_monitors(&simulator.monitors_json())
+            .unwrap()
+            .monitors
+    }
+
+    #[test]
+    fn should_render_dot_with_attributes() {
+        let dot = to_dot(&demo_monitors(&Simulator::demo()));
+
+        assert!(dot.starts_with("digraph glazewm {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("n0 [label=\"Monitor sim-monitor-1\\n1920x1080\", shape=box3d"));
+        assert!(dot.contains("tiling_direction=\"horizontal\""));
+        assert!(dot.contains(
+            "label=\"firefox: Firefox - Documentation\\ntiling, shown\", shape=box, kind=\"window\""
+        ));
+        assert!(dot.contains(
+            "focus=\"focused\", state=\"tiling\", display_state=\"shown\", style=\"bold\""
+        ));
+        assert!(dot.contains("    n0 -> n1;\n"));
+        // Five windows, each with an edge from its parent
+        assert_eq!(dot.matches("kind=\"window\"").count(), 5);
+    }
+
+    #[test]
+    fn should_render_split_containers_and_hidden_workspaces() {
+        let mut simulator = Simulator::demo();
+        simulator
+            .execute("shell-exec notepad \"quoted\" <b>")
+            .unwrap();
+        // Moving across the axis wraps the other windows in a split
+        simulator.execute("move --direction up").unwrap();
+        let monitors = demo_monitors(&simulator);
+
+        let dot = to_dot(&monitors);
+        assert!(dot.contains("kind=\"split\""));
+        assert!(dot.contains("\\\"quoted\\\""));
+
+        let mermaid = to_mermaid(&monitors);
+        assert!(mermaid
+            .starts_with("flowchart LR\n    n0[[\"Monitor sim-monitor-1<br/>1920x1080\"]]\n"));
+        assert!(mermaid.contains("#quot;quoted#quot; #lt;b#gt;"));
+        assert!(mermaid.contains("([\"Split "));
+        assert!(mermaid.contains("    classDef hidden stroke-dasharray:4 4\n"));
+        assert!(mermaid.contains("    classDef focused stroke-width:3px\n    class n0,"));
+    }
+
+    #[test]
+    fn should_write_both_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = write_files(
+            &demo_monitors(&Simulator::demo()),
+            dir.path(),
+            std::time::UNIX_EPOCH,
+        )
+        .unwrap();
+
+        let names: Vec<_> = paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "glazewm-tree-1970-01-01T00-00-00Z.dot",
+                "glazewm-tree-1970-01-01T00-00-00Z.mmd"
+            ]
+        );
+        assert!(std::fs::read_to_string(&paths[1])
+            .unwrap()
+            .starts_with("flowchart LR"));
+    }
+}
@@ -0,0 +1,439 @@
+// HTTP API
+// Serves the state kept in AppState as JSON, plus a server-sent event stream
+// of diffs, Prometheus metrics and DOT/Mermaid graphs, so dashboards and
+// scripts can reuse the parsed tree
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info, warn};
+
+use crate::app::dump::Validation;
+use crate::app::graph::{self, GraphFormat};
+use crate::app::AppState;
+use crate::domain::{MonitorId, Window, WorkspaceId};
+use crate::utils::json_diff;
+
+/// Requests are a line and a few headers; anything longer is cut off
+const MAX_REQUEST_BYTES: u64 = 8 * 1024;
+
+/// Window from the flat `/windows` list, with its place in the tree
+#[derive(Serialize)]
+struct WindowEntry<'a> {
+    monitor: &'a MonitorId,
+    workspace: &'a WorkspaceId,
+    #[serde(flatten)]
+    window: &'a Window,
+}
+
+/// Read-only HTTP view of the application state, bound to localhost
+pub struct HttpServer {
+    state: AppState,
+    listener: TcpListener,
+}
+
+impl HttpServer {
+    /// Listen on 127.0.0.1; the API has no authentication, so it is never
+    /// exposed beyond this machine
+    pub async fn bind(port: u16, state: AppState) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await?;
+        Ok(Self { state, listener })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Answer requests until the task is dropped
+    pub async fn run(self) {
+        let port = match self.listener.local_addr() {
+            Ok(address) => {
+                info!("HTTP API listening on http://{}", address);
+                address.port()
+            }
+            Err(_) => 0,
+        };
+
+        loop {
+            match self.listener.accept().await {
+                Ok((stream, _)) => {
+                    let state = self.state.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle(stream, state, port).await {
+                            debug!("HTTP connection ended: {}", e);
+                        }
+                    });
+                }
+                Err(e) => warn!("Failed to accept HTTP connection: {}", e),
+            }
+        }
+    }
+}
+
+/// Whether a `Host` header names this machine's loopback address on `port`.
+/// Binding to 127.0.0.1 does not stop a DNS-rebinding page in a browser from
+/// reaching the API under its own host name; checking the header does.
+fn is_local_host(host: &str, port: u16) -> bool {
+    let (name, host_port) = match host.rsplit_once(':') {
+        Some((name, host_port)) if !host_port.ends_with(']') => (name, Some(host_port)),
+        _ => (host, None),
+    };
+    let local_name = ["localhost", "127.0.0.1", "[::1]"]
+        .iter()
+        .any(|local| name.eq_ignore_ascii_case(local));
+    local_name && host_port.is_none_or(|host_port| host_port == port.to_string())
+}
+
+/// Serve one request; the connection is closed afterwards
+async fn handle(mut stream: TcpStream, state: AppState, port: u16) -> io::Result<()> {
+    let (reader, mut writer) = stream.split();
+    let mut reader = BufReader::new(reader.take(MAX_REQUEST_BYTES));
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    // Only `Host` is needed, but every header must be read before answering
+    let mut host = None;
+    let mut header = String::new();
+    while reader.read_line(&mut header).await? > 0 && !header.trim().is_empty() {
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("host") {
+                host = Some(value.trim().to_string());
+            }
+        }
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = target.split('?').next().unwrap_or("");
+    debug!("HTTP {} {}", method, target);
+
+    if !host
+        .as_deref()
+        .is_some_and(|host| is_local_host(host, port))
+    {
+        let body = json!({ "error": "only localhost may use this API" });
+        return respond(&mut writer, "403 Forbidden", &body).await;
+    }
+
+    if method != "GET" {
+        let body = json!({ "error": format!("method {} not allowed", method) });
+        return respond(&mut writer, "405 Method Not Allowed", &body).await;
+    }
+
+    match path {
+        "/state" => {
+            let monitors = state.get_monitors().await;
+            respond(&mut writer, "200 OK", &monitors).await
+        }
+        "/windows" => {
+            let monitors = state.get_monitors().await;
+            let windows: Vec<WindowEntry> = monitors
+                .iter()
+                .flat_map(|monitor| {
+                    monitor.workspaces().iter().flat_map(move |workspace| {
+                        workspace.windows().iter().map(move |window| WindowEntry {
+                            monitor: monitor.id(),
+                            workspace: workspace.id(),
+                            window,
+                        })
+                    })
+                })
+                .collect();
+            respond(&mut writer, "200 OK", &windows).await
+        }
+        "/validate" => {
+            let validation = Validation::from_state(&state).await;
+            respond(&mut writer, "200 OK", &validation).await
+        }
+        "/geometry" => {
+            let geometry = state.get_geometry().await;
+            respond(&mut writer, "200 OK", &geometry).await
+        }
+        "/metrics" => {
+            let monitors = state.get_monitors().await;
+            let text = state.get_metrics().await.to_prometheus(&monitors);
+            let content_type = "text/plain; version=0.0.4; charset=utf-8";
+            respond_with(&mut writer, "200 OK", content_type, &text).await
+        }
+        "/graph.dot" | "/graph.mmd" => {
+            let monitors = state.get_monitors().await;
+            let (format, content_type) = if path == "/graph.dot" {
+                (GraphFormat::Dot, "text/vnd.graphviz; charset=utf-8")
+            } else {
+                (GraphFormat::Mermaid, "text/plain; charset=utf-8")
+            };
+            let text = graph::render(&monitors, format);
+            respond_with(&mut writer, "200 OK", content_type, &text).await
+        }
+        "/events" => stream_events(&mut writer, &state).await,
+        _ => {
+            let body = json!({ "error": format!("no such endpoint: {}", path) });
+            respond(&mut writer, "404 Not Found", &body).await
+        }
+    }
+}
+
+async fn respond(
+    writer: &mut (impl AsyncWrite + Unpin),
+    status: &str,
+    body: &impl Serialize,
+) -> io::Result<()> {
+    let mut body = serde_json::to_string(body)?;
+    body.push('\n');
+    respond_with(writer, status, "application/json", &body).await
+}
+
+async fn respond_with(
+    writer: &mut (impl AsyncWrite + Unpin),
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {}\r\n\
+         Content-Type: {}\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    writer.write_all(head.as_bytes()).await?;
+    writer.write_all(body.as_bytes()).await?;
+    writer.shutdown().await
+}
+
+/// Send the monitors as a `snapshot` event, then a `patch` event (see
+/// `utils::json_diff`) after every update that changed them
+async fn stream_events(writer: &mut (impl AsyncWrite + Unpin), state: &AppState) -> io::Result<()> {
+    let head = "HTTP/1.1 200 OK\r\n\
+                Content-Type: text/event-stream\r\n\
+                Cache-Control: no-cache\r\n\
+                Connection: close\r\n\r\n";
+    writer.write_all(head.as_bytes()).await?;
+
+    let mut updates = state.subscribe_updates();
+    let mut sent: Option<Value> = None;
+    loop {
+        let update = *updates.borrow_and_update();
+        let current = serde_json::to_value(state.get_monitors().await)?;
+
+        let event = match &sent {
+            None => Some(("snapshot", current.to_string())),
+            Some(previous) => {
+                let patches = json_diff::diff(previous, &current);
+                (!patches.is_empty())
+                    .then(|| serde_json::to_string(&patches))
+                    .transpose()?
+                    .map(|data| ("patch", data))
+            }
+        };
+        if let Some((name, data)) = event {
+            let message = format!("id: {}\nevent: {}\ndata: {}\n\n", update, name, data);
+            writer.write_all(message.as_bytes()).await?;
+            writer.flush().await?;
+            sent = Some(current);
+        }
+
+        if updates.changed().await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::update::UpdateConfig;
+    use crate::app::UpdateLoop;
+    use crate::utils::json_diff::JsonPatch;
+
+    async fn demo_server() -> (AppState, SocketAddr) {
+        let state = AppState::new();
+        UpdateLoop::new_demo(UpdateConfig::default(), state.clone())
+            .update_now()
+            .await
+            .unwrap();
+
+        let server = HttpServer::bind(0, state.clone()).await.unwrap();
+        let address = server.local_addr().unwrap();
+        tokio::spawn(server.run());
+        (state, address)
+    }
+
+    async fn request(address: SocketAddr, request_line: &str) -> (String, Value) {
+        request_from(address, request_line, "localhost").await
+    }
+
+    async fn request_from(address: SocketAddr, request_line: &str, host: &str) -> (String, Value) {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(format!("{}\r\nHost: {}\r\n\r\n", request_line, host).as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.lines().next().unwrap().to_string();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn should_serve_state_windows_validation_and_geometry() {
+        let (_, address) = demo_server().await;
+
+        let (status, state) = request(address, "GET /state HTTP/1.1").await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(state.as_array().unwrap().len(), 2);
+        assert_eq!(state[0]["workspaces"][0]["name"], "Development");
+
+        let (_, windows) = request(address, "GET /windows?pretty HTTP/1.1").await;
+        let windows = windows.as_array().unwrap();
+        assert_eq!(windows.len(), 5);
+        assert!(windows[0]["monitor"].is_string());
+        assert!(windows[0]["title"].is_string());
+
+        let (_, validation) = request(address, "GET /validate HTTP/1.1").await;
+        assert_eq!(validation["valid"], true);
+        assert_eq!(validation["inconsistencies"], json!([]));
+
+        // The demo's floating Spotify sits over the tiled Discord
+        let (_, geometry) = request(address, "GET /geometry HTTP/1.1").await;
+        assert_eq!(geometry["issues"][0]["kind"], "overlap");
+        assert_eq!(geometry["issues"][0]["other_id"], "sim-window-9");
+        assert_eq!(geometry["visibility"].as_array().unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn should_serve_prometheus_metrics() {
+        let (_, address) = demo_server().await;
+
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+        assert!(response.contains("glazewm_monitors 2\n"));
+        assert!(response.contains("glazewm_debug_updates_total{result=\"success\"} 1\n"));
+        assert!(response.contains("glazewm_query_duration_seconds_count{query=\"windows\"} 1\n"));
+    }
+
+    #[tokio::test]
+    async fn should_serve_graphs() {
+        let (_, address) = demo_server().await;
+
+        for (path, content_type, start) in [
+            ("/graph.dot", "text/vnd.graphviz", "digraph glazewm {"),
+            ("/graph.mmd", "text/plain", "flowchart LR"),
+        ] {
+            let mut stream = TcpStream::connect(address).await.unwrap();
+            stream
+                .write_all(format!("GET {} HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n", path).as_bytes())
+                .await
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+
+            assert!(response.contains(&format!("Content-Type: {}", content_type)));
+            assert!(response.contains(&format!("\r\n\r\n{}\n", start)));
+        }
+    }
+
+    #[tokio::test]
+    async fn should_reject_unknown_requests() {
+        let (_, address) = demo_server().await;
+
+        let (status, body) = request(address, "GET /nope HTTP/1.1").await;
+        assert_eq!(status, "HTTP/1.1 404 Not Found");
+        assert_eq!(body["error"], "no such endpoint: /nope");
+
+        let (status, _) = request(address, "POST /state HTTP/1.1").await;
+        assert_eq!(status, "HTTP/1.1 405 Method Not Allowed");
+    }
+
+    #[tokio::test]
+    async fn should_reject_requests_for_other_hosts() {
+        let (_, address) = demo_server().await;
+        let port = address.port();
+
+        for host in [
+            "localhost".to_string(),
+            format!("localhost:{}", port),
+            format!("127.0.0.1:{}", port),
+            format!("[::1]:{}", port),
+        ] {
+            let (status, _) = request_from(address, "GET /state HTTP/1.1", &host).await;
+            assert_eq!(status, "HTTP/1.1 200 OK", "Host: {}", host);
+        }
+
+        // A DNS-rebinding page sends its own host name; a missing header
+        // cannot be trusted either
+        for host in [
+            format!("evil.example:{}", port),
+            format!("localhost:{}", port.wrapping_add(1)),
+            "localhost.evil.example".to_string(),
+        ] {
+            let (status, body) = request_from(address, "GET /state HTTP/1.1", &host).await;
+            assert_eq!(status, "HTTP/1.1 403 Forbidden", "Host: {}", host);
+            assert_eq!(body["error"], "only localhost may use this API");
+        }
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(b"GET /state HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 403 Forbidden"));
+    }
+
+    /// Lines of the next server-sent event
+    async fn next_event(
+        lines: &mut tokio::io::Lines<BufReader<tokio::net::tcp::OwnedReadHalf>>,
+    ) -> Vec<String> {
+        let mut event = Vec::new();
+        while let Some(line) = lines.next_line().await.unwrap() {
+            if line.is_empty() && event.iter().any(|l: &String| l.starts_with("data:")) {
+                break;
+            }
+            event.push(line);
+        }
+        event
+    }
+
+    #[tokio::test]
+    async fn should_stream_snapshot_then_patches() {
+        let (state, address) = demo_server().await;
+
+        let stream = TcpStream::connect(address).await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        writer
+            .write_all(b"GET /events HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut lines = BufReader::new(reader).lines();
+
+        let snapshot = next_event(&mut lines).await;
+        assert!(snapshot.contains(&"event: snapshot".to_string()));
+
+        let mut monitors = state.get_monitors().await;
+        monitors.truncate(1);
+        state.update_monitors(monitors).await;
+        state.mark_updated();
+
+        let patch = next_event(&mut lines).await;
+        assert!(patch.contains(&"event: patch".to_string()));
+        let data = patch.iter().find_map(|l| l.strip_prefix("data: ")).unwrap();
+        let patches: Vec<JsonPatch> = serde_json::from_str(data).unwrap();
+        assert_eq!(patches.len(), 1);
+    }
+}
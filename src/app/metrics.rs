@@ -0,0 +1,332 @@
+// Update loop metrics
+// Counters and histograms recorded by the update loop, rendered in the
+// Prometheus text format for the HTTP API's `/metrics`
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::domain::changes::{self, Change};
+use crate::domain::{Monitor, WindowState};
+
+/// Upper bounds of the duration buckets, in seconds
+const DURATION_BUCKETS: [f64; 12] = [
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Cumulative histogram of durations
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    /// Observations at or below each bound of `DURATION_BUCKETS`
+    buckets: [u64; DURATION_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self {
+            buckets: [0; DURATION_BUCKETS.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    pub fn observe(&mut self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        for (bound, bucket) in DURATION_BUCKETS.iter().zip(&mut self.buckets) {
+            if seconds <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Append the bucket, sum and count samples; `labels` is empty or ends
+    /// with a comma
+    fn write_samples(&self, out: &mut String, name: &str, labels: &str) {
+        for (bound, bucket) in DURATION_BUCKETS.iter().zip(&self.buckets) {
+            let _ = writeln!(
+                out,
+                "{}_bucket{{{}le=\"{}\"}} {}",
+                name, labels, bound, bucket
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{}_bucket{{{}le=\"+Inf\"}} {}",
+            name, labels, self.count
+        );
+        let labels = labels.trim_end_matches(',');
+        let braces = |labels: &str| {
+            if labels.is_empty() {
+                String::new()
+            } else {
+                format!("{{{}}}", labels)
+            }
+        };
+        let _ = writeln!(out, "{}_sum{} {}", name, braces(labels), self.sum);
+        let _ = writeln!(out, "{}_count{} {}", name, braces(labels), self.count);
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Everything the update loop counts across updates
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    /// Latency of each glazewm query, by query name
+    query_latency: BTreeMap<&'static str, Histogram>,
+    /// Time spent turning glazewm JSON into domain models
+    parse_time: Histogram,
+    updates_succeeded: u64,
+    updates_failed: u64,
+    focus_changes: u64,
+    windows_created: u64,
+    windows_destroyed: u64,
+    /// Previous snapshot; `None` before the first one
+    previous: Option<Vec<Monitor>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record how long a glazewm query took, whether it succeeded or not
+    pub fn record_query(&mut self, query: &'static str, elapsed: Duration) {
+        self.query_latency
+            .entry(query)
+            .or_default()
+            .observe(elapsed);
+    }
+
+    pub fn record_parse(&mut self, elapsed: Duration) {
+        self.parse_time.observe(elapsed);
+    }
+
+    pub fn record_update(&mut self, succeeded: bool) {
+        if succeeded {
+            self.updates_succeeded += 1;
+        } else {
+            self.updates_failed += 1;
+        }
+    }
+
+    /// Count windows that appeared or went away and focus changes since the
+    /// previous snapshot; the first snapshot only sets the baseline
+    pub fn record_snapshot(&mut self, monitors: &[Monitor]) {
+        if let Some(previous) = &self.previous {
+            for change in changes::compare(previous, monitors) {
+                match change {
+                    Change::WindowOpened { .. } => self.windows_created += 1,
+                    Change::WindowClosed { .. } => self.windows_destroyed += 1,
+                    Change::FocusChanged { .. } => self.focus_changes += 1,
+                    _ => {}
+                }
+            }
+        }
+        self.previous = Some(monitors.to_vec());
+    }
+
+    pub fn focus_changes(&self) -> u64 {
+        self.focus_changes
+    }
+
+    pub fn windows_created(&self) -> u64 {
+        self.windows_created
+    }
+
+    pub fn windows_destroyed(&self) -> u64 {
+        self.windows_destroyed
+    }
+
+    /// Render the counters, plus gauges for the current `monitors`, in the
+    /// Prometheus text exposition format
+    pub fn to_prometheus(&self, monitors: &[Monitor]) -> String {
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "glazewm_monitors",
+            "gauge",
+            "Monitors reported by glazewm",
+        );
+        let _ = writeln!(out, "glazewm_monitors {}", monitors.len());
+
+        header(
+            &mut out,
+            "glazewm_windows",
+            "gauge",
+            "Windows by monitor, workspace and state",
+        );
+        for monitor in monitors {
+            for workspace in monitor.workspaces() {
+                let mut by_state: BTreeMap<&str, usize> = BTreeMap::new();
+                for window in workspace.windows() {
+                    *by_state.entry(state_label(window.state())).or_default() += 1;
+                }
+                for (state, count) in by_state {
+                    let _ = writeln!(
+                        out,
+                        "glazewm_windows{{monitor=\"{}\",workspace=\"{}\",state=\"{}\"}} {}",
+                        escape(monitor.id().as_str()),
+                        escape(workspace.label()),
+                        state,
+                        count
+                    );
+                }
+            }
+        }
+
+        header(
+            &mut out,
+            "glazewm_debug_updates_total",
+            "counter",
+            "Update cycles by result",
+        );
+        let _ = writeln!(
+            out,
+            "glazewm_debug_updates_total{{result=\"success\"}} {}",
+            self.updates_succeeded
+        );
+        let _ = writeln!(
+            out,
+            "glazewm_debug_updates_total{{result=\"failure\"}} {}",
+            self.updates_failed
+        );
+
+        for (name, help, value) in [
+            (
+                "glazewm_focus_changes_total",
+                "Changes of the focused window between updates",
+                self.focus_changes,
+            ),
+            (
+                "glazewm_windows_created_total",
+                "Windows that appeared between updates",
+                self.windows_created,
+            ),
+            (
+                "glazewm_windows_destroyed_total",
+                "Windows that went away between updates",
+                self.windows_destroyed,
+            ),
+        ] {
+            header(&mut out, name, "counter", help);
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+
+        header(
+            &mut out,
+            "glazewm_query_duration_seconds",
+            "histogram",
+            "Latency of glazewm queries",
+        );
+        for (query, histogram) in &self.query_latency {
+            let labels = format!("query=\"{}\",", escape(query));
+            histogram.write_samples(&mut out, "glazewm_query_duration_seconds", &labels);
+        }
+
+        header(
+            &mut out,
+            "glazewm_parse_duration_seconds",
+            "histogram",
+            "Time spent parsing glazewm responses",
+        );
+        self.parse_time
+            .write_samples(&mut out, "glazewm_parse_duration_seconds", "");
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn state_label(state: &WindowState) -> &'static str {
+    match state {
+        WindowState::Tiling => "tiling",
+        WindowState::Floating => "floating",
+        WindowState::Minimized => "minimized",
+        WindowState::Fullscreen => "fullscreen",
+        WindowState::Unknown(_) => "unknown",
+    }
+}
+
+/// Escape a label value
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{GlazewmParser, Simulator};
+
+    fn monitors(simulator: &Simulator) -> Vec<Monitor> {
+        GlazewmParser::parse_monitors(&simulator.monitors_json())
+            .unwrap()
+            .monitors
+    }
+
+    #[test]
+    fn should_count_window_and_focus_changes_after_the_first_snapshot() {
+        let mut simulator = Simulator::demo();
+        let mut metrics = Metrics::new();
+
+        metrics.record_snapshot(&monitors(&simulator));
+        assert_eq!(metrics.windows_created(), 0);
+
+        simulator
+            .execute("shell-exec notepad Untitled - Notepad")
+            .unwrap();
+        metrics.record_snapshot(&monitors(&simulator));
+        assert_eq!(metrics.windows_created(), 1);
+        assert_eq!(metrics.focus_changes(), 1);
+
+        simulator.execute("close").unwrap();
+        metrics.record_snapshot(&monitors(&simulator));
+        assert_eq!(metrics.windows_destroyed(), 1);
+        assert_eq!(metrics.focus_changes(), 2);
+    }
+
+    #[test]
+    fn should_render_prometheus_text() {
+        let simulator = Simulator::demo();
+        let mut metrics = Metrics::new();
+        metrics.record_query("monitors", Duration::from_millis(20));
+        metrics.record_parse(Duration::from_micros(300));
+        metrics.record_update(true);
+
+        let text = metrics.to_prometheus(&monitors(&simulator));
+        assert!(text.contains("# TYPE glazewm_windows gauge\n"));
+        assert!(text.contains("state=\"tiling\"}"));
+        assert!(text.contains("glazewm_debug_updates_total{result=\"success\"} 1\n"));
+        assert!(text
+            .contains("glazewm_query_duration_seconds_bucket{query=\"monitors\",le=\"0.01\"} 0\n"));
+        assert!(text.contains(
+            "glazewm_query_duration_seconds_bucket{query=\"monitors\",le=\"0.025\"} 1\n"
+        ));
+        assert!(text.contains("glazewm_parse_duration_seconds_count 1\n"));
+    }
+
+    #[test]
+    fn should_escape_label_values() {
+        assert_eq!(escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
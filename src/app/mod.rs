@@ -1,8 +1,23 @@
 // Application layer module
 // Manages state and coordinates between CLI and TUI layers
 
+pub mod agent;
+pub mod assertions;
+pub mod diff;
+pub mod dump;
+pub mod graph;
+pub mod http;
+pub mod metrics;
+pub mod perf;
+pub mod record;
+pub mod report;
 pub mod state;
 pub mod update;
+pub mod watch;
 
+pub use agent::Agent;
+pub use http::HttpServer;
+pub use metrics::Metrics;
+pub use perf::PerfStats;
 pub use state::AppState;
-pub use update::UpdateLoop;
\ No newline at end of file
+pub use update::UpdateLoop;
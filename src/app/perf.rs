@@ -0,0 +1,117 @@
+// Performance statistics
+// Rolling timings of the update and render paths, shown in the TUI's
+// performance HUD
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Samples kept per statistic
+const WINDOW: usize = 120;
+
+/// Summary of the samples in a rolling window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StatsSummary {
+    pub last: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+/// The most recent samples of one measurement
+#[derive(Debug, Clone, Default)]
+pub struct RollingStats {
+    samples: VecDeque<f64>,
+}
+
+impl RollingStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a sample, dropping the oldest one once the window is full
+    pub fn record(&mut self, sample: f64) {
+        if self.samples.len() == WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn record_duration(&mut self, elapsed: Duration) {
+        self.record(elapsed.as_secs_f64() * 1000.0);
+    }
+
+    /// Last, median, 95th percentile and maximum; `None` without samples
+    pub fn summary(&self) -> Option<StatsSummary> {
+        let last = *self.samples.back()?;
+        let mut sorted: Vec<f64> = self.samples.iter().copied().collect();
+        sorted.sort_by(f64::total_cmp);
+
+        // Nearest-rank percentile
+        let percentile = |p: f64| {
+            let rank = (p * sorted.len() as f64).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        };
+        Some(StatsSummary {
+            last,
+            p50: percentile(0.5),
+            p95: percentile(0.95),
+            max: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+/// Timings in milliseconds and payload sizes in bytes
+#[derive(Debug, Clone, Default)]
+pub struct PerfStats {
+    /// glazewm queries of one update, run concurrently
+    pub poll_latency: RollingStats,
+    /// Parsing both responses into domain models
+    pub parse_time: RollingStats,
+    /// Drawing one TUI frame
+    pub frame_time: RollingStats,
+    /// JSON returned by both queries, as glazewm wrote it
+    pub payload_size: RollingStats,
+}
+
+impl PerfStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_summarize_samples() {
+        let mut stats = RollingStats::new();
+        assert_eq!(stats.summary(), None);
+
+        for sample in (1..=100).rev() {
+            stats.record(sample as f64);
+        }
+        assert_eq!(
+            stats.summary(),
+            Some(StatsSummary {
+                last: 1.0,
+                p50: 50.0,
+                p95: 95.0,
+                max: 100.0,
+            })
+        );
+    }
+
+    #[test]
+    fn should_keep_only_recent_samples() {
+        let mut stats = RollingStats::new();
+        stats.record(1000.0);
+        for _ in 0..WINDOW {
+            stats.record_duration(Duration::from_millis(2));
+        }
+
+        let summary = stats.summary().unwrap();
+        assert_eq!(summary.max, 2.0);
+        assert_eq!(summary.p95, 2.0);
+    }
+}
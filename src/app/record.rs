@@ -0,0 +1,64 @@
+// Recording
+// Saves the raw `glazewm query monitors` response of every poll as one line
+// of newline-delimited JSON, to be viewed later with `replay` or `--input -`
+
+use std::io::{self, Write};
+use tokio::time::interval;
+use tracing::warn;
+
+use crate::app::UpdateLoop;
+
+/// Poll at the refresh interval and write each monitors response until the
+/// application stops or `limit` documents were written. Failed queries are
+/// logged and skipped. Returns the number of documents written.
+pub async fn run<W: Write>(
+    update_loop: &UpdateLoop,
+    mut out: W,
+    limit: Option<usize>,
+) -> io::Result<usize> {
+    let state = update_loop.state();
+    let mut timer = interval(update_loop.config().refresh_interval);
+    let mut written = 0;
+
+    while state.is_running().await && limit.is_none_or(|limit| written < limit) {
+        timer.tick().await;
+        let (monitors, _) = update_loop.query_raw().await;
+        match monitors {
+            Ok(document) => {
+                writeln!(out, "{}", document)?;
+                out.flush()?;
+                written += 1;
+            }
+            Err(e) => warn!("Skipping failed poll: {}", e),
+        }
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::update::UpdateConfig;
+    use crate::app::AppState;
+    use crate::cli::read_recording;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_write_one_document_per_poll() {
+        let config = UpdateConfig {
+            refresh_interval: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let update_loop = UpdateLoop::new_demo(config, AppState::new());
+        let file = tempfile::NamedTempFile::new().unwrap();
+
+        let written = run(&update_loop, file.reopen().unwrap(), Some(3))
+            .await
+            .unwrap();
+
+        assert_eq!(written, 3);
+        let documents = read_recording(file.path()).unwrap();
+        assert_eq!(documents.len(), 3);
+        assert_eq!(documents[0]["success"], true);
+    }
+}
@@ -0,0 +1,275 @@
+// Bug report bundles
+// Collects what we usually have to ask for in a glazewm bug report into one
+// tar archive: raw query responses, recent snapshots, findings, log lines and
+// versions, with a Markdown summary to paste into the issue
+
+use serde_json::{json, Value};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::app::dump::{self, Validation};
+use crate::app::state::Snapshot;
+use crate::app::UpdateLoop;
+use crate::cli::{CliError, RedactionMode};
+use crate::domain::Monitor;
+use crate::utils::log_buffer::LogBuffer;
+use crate::utils::tar::TarWriter;
+use crate::utils::timestamp;
+
+/// Directory the files are unpacked into
+const ROOT: &str = "glazewm-report";
+
+/// The files of a report, in archive order
+#[derive(Debug)]
+pub struct Report {
+    created: SystemTime,
+    files: Vec<(&'static str, Vec<u8>)>,
+}
+
+impl Report {
+    /// Query glazewm and gather the state the update loop has collected
+    pub async fn collect(update_loop: &UpdateLoop) -> Self {
+        let created = SystemTime::now();
+        let (monitors_json, windows_json) = update_loop.query_raw().await;
+        let metadata_json = update_loop.query_app_metadata().await;
+
+        let state = update_loop.state();
+        let monitors = state.get_monitors().await;
+        let history = state.get_history().await;
+        let validation = Validation::from_state(state).await;
+        let logs = LogBuffer::global().lines();
+
+        let glazewm_version = metadata_json
+            .as_ref()
+            .ok()
+            .and_then(|metadata| metadata["data"]["version"].as_str())
+            .map(str::to_string);
+
+        let summary = summary(
+            created,
+            glazewm_version.as_deref(),
+            &monitors,
+            &history,
+            &validation,
+            update_loop.redactor().mode(),
+        );
+
+        let mut files = vec![
+            ("summary.md", summary.into_bytes()),
+            (
+                "versions.json",
+                to_json(json!({
+                    "glazewm_debug": env!("CARGO_PKG_VERSION"),
+                    "glazewm": glazewm_version,
+                })),
+            ),
+        ];
+        for (name, response) in [
+            ("queries/monitors.json", monitors_json),
+            ("queries/windows.json", windows_json),
+            ("queries/app-metadata.json", metadata_json),
+        ] {
+            files.push((name, to_json(response_or_error(response))));
+        }
+        files.push((
+            "history.json",
+            to_json(serde_json::to_value(&history).unwrap_or_default()),
+        ));
+        files.push((
+            "validation.json",
+            to_json(serde_json::to_value(&validation).unwrap_or_default()),
+        ));
+        let mut log = String::new();
+        for line in &logs {
+            let _ = writeln!(log, "{}", line);
+        }
+        // Log lines may quote titles from before they were redacted
+        let log = update_loop.redactor().scrub_text(&log);
+        files.push(("log.txt", log.into_bytes()));
+
+        Self { created, files }
+    }
+
+    /// Names of the files in the archive, relative to its root directory
+    pub fn file_names(&self) -> impl Iterator<Item = &str> {
+        self.files.iter().map(|(name, _)| *name)
+    }
+
+    /// Contents of one file
+    pub fn file(&self, name: &str) -> Option<&[u8]> {
+        self.files
+            .iter()
+            .find(|(file, _)| *file == name)
+            .map(|(_, contents)| contents.as_slice())
+    }
+
+    /// Write the files as a tar archive under `glazewm-report/`
+    pub fn write_archive<W: Write>(&self, out: W) -> io::Result<()> {
+        let mtime = self
+            .created
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut tar = TarWriter::new(out, mtime);
+        for (name, contents) in &self.files {
+            tar.append(&format!("{}/{}", ROOT, name), contents)?;
+        }
+        tar.finish()?;
+        Ok(())
+    }
+}
+
+/// `glazewm-report-2024-05-01T12-34-56Z.tar`, safe on every file system
+pub fn default_file_name(time: SystemTime) -> String {
+    format!("glazewm-report-{}.tar", timestamp::file_stamp(time))
+}
+
+/// Collect a report and write it to `path`
+pub async fn write(update_loop: &UpdateLoop, path: &Path) -> io::Result<()> {
+    let report = Report::collect(update_loop).await;
+    report.write_archive(BufWriter::new(File::create(path)?))
+}
+
+fn summary(
+    created: SystemTime,
+    glazewm_version: Option<&str>,
+    monitors: &[Monitor],
+    history: &[Snapshot],
+    validation: &Validation,
+    redaction: &RedactionMode,
+) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# glazewm bug report\n");
+    let _ = writeln!(
+        out,
+        "- Created: {}\n- glazewm: {}\n- glazewm-debug: {}",
+        timestamp::rfc3339(created),
+        glazewm_version.unwrap_or("unknown"),
+        env!("CARGO_PKG_VERSION")
+    );
+    if !matches!(redaction, RedactionMode::Off) {
+        let _ = writeln!(out, "- Redaction: {}", redaction.name());
+    }
+
+    let workspaces: usize = monitors.iter().map(|m| m.workspaces().len()).sum();
+    let windows: usize = monitors.iter().map(Monitor::total_window_count).sum();
+    let _ = writeln!(
+        out,
+        "\n## State\n\n{} monitor(s), {} workspace(s), {} window(s)\n",
+        monitors.len(),
+        workspaces,
+        windows
+    );
+    let _ = writeln!(
+        out,
+        "```text\n{}```\n",
+        dump::format_state(monitors, &[], &[])
+    );
+
+    let _ = writeln!(out, "## Findings\n\n```text\n{}```\n", validation.to_text());
+
+    let _ = writeln!(
+        out,
+        "## Files\n\n\
+         - `queries/*.json`: raw `glazewm query` responses\n\
+         - `history.json`: the last {} distinct snapshot(s), oldest first\n\
+         - `validation.json`: update error, parse warnings and inconsistencies\n\
+         - `log.txt`: recent log lines\n\
+         - `versions.json`: glazewm and glazewm-debug versions",
+        history.len()
+    );
+    out
+}
+
+fn response_or_error(response: Result<Value, CliError>) -> Value {
+    response.unwrap_or_else(|e| json!({ "error": e.to_string() }))
+}
+
+fn to_json(value: Value) -> Vec<u8> {
+    let mut bytes = serde_json::to_vec_pretty(&value).unwrap_or_default();
+    bytes.push(b'\n');
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::update::UpdateConfig;
+    use crate::app::AppState;
+
+    async fn demo_report(redaction: RedactionMode) -> Report {
+        let update_loop = UpdateLoop::new_demo(UpdateConfig::default(), AppState::new())
+            .with_redaction(redaction);
+        update_loop.update_now().await.unwrap();
+        Report::collect(&update_loop).await
+    }
+
+    fn text(report: &Report, name: &str) -> String {
+        String::from_utf8(report.file(name).unwrap().to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_collect_queries_history_and_summary() {
+        let report = demo_report(RedactionMode::Off).await;
+
+        assert_eq!(
+            report.file_names().collect::<Vec<_>>(),
+            [
+                "summary.md",
+                "versions.json",
+                "queries/monitors.json",
+                "queries/windows.json",
+                "queries/app-metadata.json",
+                "history.json",
+                "validation.json",
+                "log.txt"
+            ]
+        );
+        let summary = text(&report, "summary.md");
+        assert!(summary.starts_with("# glazewm bug report\n"));
+        assert!(summary.contains("- glazewm: simulator\n"));
+        assert!(summary.contains("2 monitor(s)"));
+        assert!(summary.contains("Firefox - Documentation"));
+
+        let history: Value = serde_json::from_slice(report.file("history.json").unwrap()).unwrap();
+        assert_eq!(history.as_array().unwrap().len(), 1);
+        let monitors: Value =
+            serde_json::from_slice(report.file("queries/monitors.json").unwrap()).unwrap();
+        assert_eq!(monitors["success"], true);
+    }
+
+    #[tokio::test]
+    async fn should_carry_redaction_into_every_file() {
+        let report = demo_report(RedactionMode::Process).await;
+
+        for name in report.file_names() {
+            let contents = text(&report, name);
+            assert!(
+                !contents.contains("Firefox - Documentation"),
+                "{} leaks a title",
+                name
+            );
+        }
+        let summary = text(&report, "summary.md");
+        assert!(summary.contains("- Redaction: process\n"));
+        assert!(summary.contains("firefox: firefox #1"));
+        assert!(text(&report, "queries/windows.json").contains("\"title\": \"firefox #1\""));
+    }
+
+    #[tokio::test]
+    async fn should_write_a_tar_archive() {
+        let report = demo_report(RedactionMode::Off).await;
+        let mut archive = Vec::new();
+        report.write_archive(&mut archive).unwrap();
+
+        assert!(archive.starts_with(b"glazewm-report/summary.md\0"));
+        assert_eq!(archive.len() % 512, 0);
+        assert_eq!(
+            default_file_name(UNIX_EPOCH),
+            "glazewm-report-1970-01-01T00-00-00Z.tar"
+        );
+    }
+}
@@ -1,11 +1,32 @@
 // Application state management
 // Central state for the entire application
 
+use serde::Serialize;
+use std::collections::VecDeque;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-
-use crate::domain::Monitor;
-use crate::tui::DisplayMode;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{watch, Notify, RwLock};
+
+use crate::app::metrics::Metrics;
+use crate::app::perf::PerfStats;
+use crate::cli::ParseWarning;
+use crate::domain::{GeometryReport, Inconsistency, LayoutDeviation, Monitor, Placeholder};
+use crate::tui::{DisplayMode, SortOrder};
+use crate::utils::timestamp;
+
+/// Number of distinct snapshots kept for bug reports
+pub const HISTORY_CAPACITY: usize = 20;
+
+/// How long a notice stays in the TUI footer
+const NOTICE_DURATION: Duration = Duration::from_secs(5);
+
+/// The monitor tree as it was at one point in time
+#[derive(Debug, Clone, Serialize)]
+pub struct Snapshot {
+    /// RFC 3339 wall-clock time of the update
+    pub taken_at: String,
+    pub monitors: Vec<Monitor>,
+}
 
 /// Central application state
 #[derive(Debug, Clone)]
@@ -18,6 +39,40 @@ pub struct AppState {
     last_update: Arc<RwLock<Option<std::time::Instant>>>,
     /// Current display mode for the TUI
     display_mode: Arc<RwLock<DisplayMode>>,
+    /// Order of workspaces and windows in the TUI
+    sort_order: Arc<RwLock<SortOrder>>,
+    /// Monitors that failed to parse or validate in the most recent parse
+    monitor_placeholders: Arc<RwLock<Vec<Placeholder>>>,
+    /// Warnings from the most recent parse
+    parse_warnings: Arc<RwLock<Vec<ParseWarning>>>,
+    /// Disagreements between the monitor tree and the window list
+    inconsistencies: Arc<RwLock<Vec<Inconsistency>>>,
+    /// Tiling windows away from where the expected layout puts them
+    layout_deviations: Arc<RwLock<Vec<LayoutDeviation>>>,
+    /// Overlapping, off-screen and straddling windows, and what is visible
+    geometry: Arc<RwLock<GeometryReport>>,
+    /// Window manager commands requested from the TUI, not yet sent
+    pending_commands: Arc<RwLock<Vec<String>>>,
+    /// Whether the client can run window manager commands at all
+    commands_enabled: Arc<RwLock<bool>>,
+    /// Wakes the update loop when a command is queued
+    commands_queued: Arc<Notify>,
+    /// Error from the most recent update, cleared by the next success
+    last_error: Arc<RwLock<Option<String>>>,
+    /// Number of finished update cycles, for readers that follow changes
+    updates: Arc<watch::Sender<u64>>,
+    /// Counters and timings recorded by the update loop
+    metrics: Arc<RwLock<Metrics>>,
+    /// Rolling timings of the update and render paths
+    perf: Arc<RwLock<PerfStats>>,
+    /// Whether the TUI shows the performance HUD
+    perf_hud: Arc<RwLock<bool>>,
+    /// Recent distinct monitor trees, oldest first
+    history: Arc<RwLock<VecDeque<Snapshot>>>,
+    /// Whether the TUI asked the update loop for a bug report
+    report_requested: Arc<RwLock<bool>>,
+    /// Short message for the TUI footer and when it was set
+    notice: Arc<RwLock<Option<(String, Instant)>>>,
 }
 
 impl AppState {
@@ -28,11 +83,40 @@ impl AppState {
             running: Arc::new(RwLock::new(true)),
             last_update: Arc::new(RwLock::new(None)),
             display_mode: Arc::new(RwLock::new(DisplayMode::Detailed)),
+            sort_order: Arc::new(RwLock::new(SortOrder::Natural)),
+            monitor_placeholders: Arc::new(RwLock::new(Vec::new())),
+            parse_warnings: Arc::new(RwLock::new(Vec::new())),
+            inconsistencies: Arc::new(RwLock::new(Vec::new())),
+            layout_deviations: Arc::new(RwLock::new(Vec::new())),
+            geometry: Arc::new(RwLock::new(GeometryReport::default())),
+            pending_commands: Arc::new(RwLock::new(Vec::new())),
+            commands_enabled: Arc::new(RwLock::new(false)),
+            commands_queued: Arc::new(Notify::new()),
+            last_error: Arc::new(RwLock::new(None)),
+            updates: Arc::new(watch::Sender::new(0)),
+            metrics: Arc::new(RwLock::new(Metrics::new())),
+            perf: Arc::new(RwLock::new(PerfStats::new())),
+            perf_hud: Arc::new(RwLock::new(false)),
+            history: Arc::new(RwLock::new(VecDeque::new())),
+            report_requested: Arc::new(RwLock::new(false)),
+            notice: Arc::new(RwLock::new(None)),
         }
     }
 
-    /// Update monitors from CLI data
+    /// Update monitors from CLI data, keeping the tree in the history if it
+    /// changed
     pub async fn update_monitors(&self, monitors: Vec<Monitor>) {
+        let mut history = self.history.write().await;
+        if history.back().is_none_or(|last| last.monitors != monitors) {
+            if history.len() == HISTORY_CAPACITY {
+                history.pop_front();
+            }
+            history.push_back(Snapshot {
+                taken_at: timestamp::rfc3339(SystemTime::now()),
+                monitors: monitors.clone(),
+            });
+        }
+
         let mut current_monitors = self.monitors.write().await;
         *current_monitors = monitors;
 
@@ -45,6 +129,160 @@ impl AppState {
         self.monitors.read().await.clone()
     }
 
+    /// Get the recent distinct monitor trees, oldest first
+    pub async fn get_history(&self) -> Vec<Snapshot> {
+        self.history.read().await.iter().cloned().collect()
+    }
+
+    /// Replace the monitors that failed to parse or validate
+    pub async fn update_monitor_placeholders(&self, placeholders: Vec<Placeholder>) {
+        let mut current = self.monitor_placeholders.write().await;
+        *current = placeholders;
+    }
+
+    /// Get the monitors that failed to parse or validate in the most recent
+    /// parse; workspace and window placeholders live in the tree
+    pub async fn get_monitor_placeholders(&self) -> Vec<Placeholder> {
+        self.monitor_placeholders.read().await.clone()
+    }
+
+    /// Replace the warnings from the most recent parse
+    pub async fn update_parse_warnings(&self, warnings: Vec<ParseWarning>) {
+        let mut current_warnings = self.parse_warnings.write().await;
+        *current_warnings = warnings;
+    }
+
+    /// Get warnings from the most recent parse
+    pub async fn get_parse_warnings(&self) -> Vec<ParseWarning> {
+        self.parse_warnings.read().await.clone()
+    }
+
+    /// Replace the inconsistencies found by the most recent reconciliation
+    pub async fn update_inconsistencies(&self, inconsistencies: Vec<Inconsistency>) {
+        let mut current = self.inconsistencies.write().await;
+        *current = inconsistencies;
+    }
+
+    /// Get inconsistencies found by the most recent reconciliation
+    pub async fn get_inconsistencies(&self) -> Vec<Inconsistency> {
+        self.inconsistencies.read().await.clone()
+    }
+
+    /// Replace the layout deviations found in the most recent update
+    pub async fn update_layout_deviations(&self, deviations: Vec<LayoutDeviation>) {
+        let mut current = self.layout_deviations.write().await;
+        *current = deviations;
+    }
+
+    /// Get layout deviations found in the most recent update
+    pub async fn get_layout_deviations(&self) -> Vec<LayoutDeviation> {
+        self.layout_deviations.read().await.clone()
+    }
+
+    /// Replace the geometry analysis of the most recent update
+    pub async fn update_geometry(&self, report: GeometryReport) {
+        let mut current = self.geometry.write().await;
+        *current = report;
+    }
+
+    /// Get the geometry analysis of the most recent update
+    pub async fn get_geometry(&self) -> GeometryReport {
+        self.geometry.read().await.clone()
+    }
+
+    /// Record the outcome of an update; `None` means it succeeded
+    pub async fn set_last_error(&self, error: Option<String>) {
+        let mut last_error = self.last_error.write().await;
+        *last_error = error;
+    }
+
+    /// Get the error from the most recent update, if it failed
+    pub async fn get_last_error(&self) -> Option<String> {
+        self.last_error.read().await.clone()
+    }
+
+    /// Signal that an update cycle finished, successfully or not
+    pub fn mark_updated(&self) {
+        self.updates.send_modify(|count| *count += 1);
+    }
+
+    /// Follow finished update cycles; the value counts them
+    pub fn subscribe_updates(&self) -> watch::Receiver<u64> {
+        self.updates.subscribe()
+    }
+
+    /// Record into the update loop metrics
+    pub async fn update_metrics(&self, record: impl FnOnce(&mut Metrics)) {
+        record(&mut *self.metrics.write().await);
+    }
+
+    /// Get a copy of the update loop metrics
+    pub async fn get_metrics(&self) -> Metrics {
+        self.metrics.read().await.clone()
+    }
+
+    /// Record into the rolling performance statistics
+    pub async fn update_perf(&self, record: impl FnOnce(&mut PerfStats)) {
+        record(&mut *self.perf.write().await);
+    }
+
+    /// Get a copy of the rolling performance statistics
+    pub async fn get_perf(&self) -> PerfStats {
+        self.perf.read().await.clone()
+    }
+
+    /// Record whether the client accepts window manager commands
+    pub async fn set_commands_enabled(&self, enabled: bool) {
+        *self.commands_enabled.write().await = enabled;
+    }
+
+    /// Whether queued window manager commands can be run
+    pub async fn commands_enabled(&self) -> bool {
+        *self.commands_enabled.read().await
+    }
+
+    /// Queue a window manager command for the update loop to send
+    pub async fn queue_command(&self, command: String) {
+        self.pending_commands.write().await.push(command);
+        self.commands_queued.notify_one();
+    }
+
+    /// Take every queued command, oldest first
+    pub async fn take_pending_commands(&self) -> Vec<String> {
+        std::mem::take(&mut *self.pending_commands.write().await)
+    }
+
+    /// Wait until a command is queued
+    pub async fn wait_for_commands(&self) {
+        self.commands_queued.notified().await;
+    }
+
+    /// Ask the update loop to write a bug report on its next cycle
+    pub async fn request_report(&self) {
+        *self.report_requested.write().await = true;
+        self.commands_queued.notify_one();
+    }
+
+    /// Whether a bug report was requested since the last call
+    pub async fn take_report_request(&self) -> bool {
+        std::mem::take(&mut *self.report_requested.write().await)
+    }
+
+    /// Show a message in the TUI footer for a few seconds
+    pub async fn set_notice(&self, notice: String) {
+        *self.notice.write().await = Some((notice, Instant::now()));
+    }
+
+    /// Get the footer message, unless it has expired
+    pub async fn get_notice(&self) -> Option<String> {
+        self.notice
+            .read()
+            .await
+            .as_ref()
+            .filter(|(_, set_at)| set_at.elapsed() < NOTICE_DURATION)
+            .map(|(notice, _)| notice.clone())
+    }
+
     /// Check if the application should continue running
     pub async fn is_running(&self) -> bool {
         *self.running.read().await
@@ -91,11 +329,47 @@ impl AppState {
     }
 
     /// Toggle display mode between Detailed and Compact
+    ///
+    /// Leaving the focus stack view returns to Detailed.
     pub async fn toggle_display_mode(&self) {
         let mut current_mode = self.display_mode.write().await;
         *current_mode = match *current_mode {
             DisplayMode::Detailed => DisplayMode::Compact,
-            DisplayMode::Compact => DisplayMode::Detailed,
+            DisplayMode::Compact | DisplayMode::FocusStack => DisplayMode::Detailed,
+        };
+    }
+
+    /// Switch to the focus stack view, or back to Detailed
+    pub async fn toggle_focus_stack(&self) {
+        let mut current_mode = self.display_mode.write().await;
+        *current_mode = match *current_mode {
+            DisplayMode::FocusStack => DisplayMode::Detailed,
+            _ => DisplayMode::FocusStack,
+        };
+    }
+
+    /// Whether the performance HUD is shown
+    pub async fn is_perf_hud_visible(&self) -> bool {
+        *self.perf_hud.read().await
+    }
+
+    /// Show or hide the performance HUD
+    pub async fn toggle_perf_hud(&self) {
+        let mut visible = self.perf_hud.write().await;
+        *visible = !*visible;
+    }
+
+    /// Get current sort order
+    pub async fn get_sort_order(&self) -> SortOrder {
+        *self.sort_order.read().await
+    }
+
+    /// Toggle between natural and most-recently-focused ordering
+    pub async fn toggle_sort_order(&self) {
+        let mut current_order = self.sort_order.write().await;
+        *current_order = match *current_order {
+            SortOrder::Natural => SortOrder::MostRecent,
+            SortOrder::MostRecent => SortOrder::Natural,
         };
     }
 }
@@ -147,6 +421,45 @@ mod tests {
         assert_eq!(monitors[0].id(), monitor.id());
     }
 
+    #[tokio::test]
+    async fn should_keep_distinct_snapshots_in_history() {
+        let state = AppState::new();
+        let monitor = |id: &str| {
+            Monitor::new(
+                MonitorId::new(id.to_string()),
+                Rectangle::new(Position::new(0, 0), Size::new(1920, 1080)),
+                Vec::new(),
+                FocusState::Focused,
+                96,
+                1.0,
+            )
+        };
+
+        state.update_monitors(vec![monitor("a")]).await;
+        state.update_monitors(vec![monitor("a")]).await;
+        assert_eq!(state.get_history().await.len(), 1);
+
+        for i in 0..HISTORY_CAPACITY {
+            state.update_monitors(vec![monitor(&i.to_string())]).await;
+        }
+        let history = state.get_history().await;
+        assert_eq!(history.len(), HISTORY_CAPACITY);
+        assert_eq!(history[0].monitors[0].id().as_str(), "0");
+    }
+
+    #[tokio::test]
+    async fn should_replace_parse_warnings() {
+        let state = AppState::new();
+        assert!(state.get_parse_warnings().await.is_empty());
+
+        let warning = ParseWarning::unknown_value("window-1", "displayState", "showing");
+        state.update_parse_warnings(vec![warning.clone()]).await;
+        assert_eq!(state.get_parse_warnings().await, vec![warning]);
+
+        state.update_parse_warnings(Vec::new()).await;
+        assert!(state.get_parse_warnings().await.is_empty());
+    }
+
     #[tokio::test]
     async fn should_stop_application() {
         let state = AppState::new();
@@ -217,4 +530,38 @@ mod tests {
         state.set_display_mode(DisplayMode::Detailed).await;
         assert_eq!(state.get_display_mode().await, DisplayMode::Detailed);
     }
+
+    #[tokio::test]
+    async fn should_toggle_focus_stack_and_sort_order() {
+        let state = AppState::new();
+
+        state.toggle_focus_stack().await;
+        assert_eq!(state.get_display_mode().await, DisplayMode::FocusStack);
+
+        // Toggling the mode leaves the focus stack view
+        state.toggle_display_mode().await;
+        assert_eq!(state.get_display_mode().await, DisplayMode::Detailed);
+
+        assert_eq!(state.get_sort_order().await, SortOrder::Natural);
+        state.toggle_sort_order().await;
+        assert_eq!(state.get_sort_order().await, SortOrder::MostRecent);
+    }
+
+    #[tokio::test]
+    async fn should_queue_and_take_commands() {
+        let state = AppState::new();
+
+        state
+            .queue_command("focus --direction left".to_string())
+            .await;
+        state.queue_command("close".to_string()).await;
+
+        // The queued notification lets a later waiter return immediately
+        state.wait_for_commands().await;
+        assert_eq!(
+            state.take_pending_commands().await,
+            vec!["focus --direction left", "close"]
+        );
+        assert!(state.take_pending_commands().await.is_empty());
+    }
 }
@@ -2,12 +2,21 @@
 // Manages the 1-second interval updates from glazewm
 
 use std::path::PathBuf;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::select;
 use tokio::time::{interval, timeout};
-use tracing::{debug, error};
+use tracing::{debug, error, info};
 
+use serde_json::Value;
+
+use crate::app::report;
 use crate::app::AppState;
-use crate::cli::{CliError, DemoGlazewmClient, GlazewmClient, GlazewmParser, RealGlazewmClient};
+use crate::cli::{
+    CliError, DemoGlazewmClient, FaultConfig, FaultyGlazewmClient, GlazewmClient, GlazewmParser,
+    InputGlazewmClient, InputSource, RealGlazewmClient, RedactionMode, Redactor,
+    RemoteGlazewmClient, Scenario, ScenarioError,
+};
+use crate::domain::{GeometryAnalyzer, TilingModel, WindowReconciler};
 
 /// Error types for the update loop
 #[derive(Debug, thiserror::Error)]
@@ -45,6 +54,10 @@ pub struct UpdateLoop {
     client: Box<dyn GlazewmClient + Send + Sync>,
     config: UpdateConfig,
     state: AppState,
+    /// Rewrites titles and process names in every response
+    redactor: Redactor,
+    /// Computes where tiling windows should be, to flag the ones that are not
+    tiling: TilingModel,
 }
 
 impl UpdateLoop {
@@ -56,6 +69,8 @@ impl UpdateLoop {
             client: Box::new(client),
             config,
             state,
+            redactor: Redactor::default(),
+            tiling: TilingModel::default(),
         }
     }
 
@@ -67,6 +82,60 @@ impl UpdateLoop {
             client: Box::new(client),
             config,
             state,
+            redactor: Redactor::default(),
+            tiling: TilingModel::default(),
+        }
+    }
+
+    /// Create a new update loop that plays a demo scenario's timeline
+    pub fn new_scenario(
+        config: UpdateConfig,
+        state: AppState,
+        scenario: Scenario,
+    ) -> Result<Self, ScenarioError> {
+        let client = DemoGlazewmClient::with_scenario(scenario)?;
+
+        Ok(Self {
+            client: Box::new(client),
+            config,
+            state,
+            redactor: Redactor::default(),
+            tiling: TilingModel::default(),
+        })
+    }
+
+    /// Create a new update loop that shows captured JSON from stdin or a file
+    pub fn new_input(config: UpdateConfig, state: AppState, source: InputSource) -> Self {
+        Self {
+            client: Box::new(InputGlazewmClient::new(source)),
+            config,
+            state,
+            redactor: Redactor::default(),
+            tiling: TilingModel::default(),
+        }
+    }
+
+    /// Create a new update loop that plays back a recording, one document per
+    /// refresh interval
+    pub fn new_replay(config: UpdateConfig, state: AppState, documents: Vec<Value>) -> Self {
+        let client = InputGlazewmClient::replay(documents, config.refresh_interval);
+        Self {
+            client: Box::new(client),
+            config,
+            state,
+            redactor: Redactor::default(),
+            tiling: TilingModel::default(),
+        }
+    }
+
+    /// Create a new update loop that follows a remote `agent` (`--connect`)
+    pub fn new_remote(config: UpdateConfig, state: AppState, address: String) -> Self {
+        Self {
+            client: Box::new(RemoteGlazewmClient::connect(address)),
+            config,
+            state,
+            redactor: Redactor::default(),
+            tiling: TilingModel::default(),
         }
     }
 
@@ -80,6 +149,8 @@ impl UpdateLoop {
             client,
             config,
             state,
+            redactor: Redactor::default(),
+            tiling: TilingModel::default(),
         }
     }
 
@@ -91,6 +162,10 @@ impl UpdateLoop {
             self.config.refresh_interval
         );
 
+        self.state
+            .set_commands_enabled(self.client.accepts_commands())
+            .await;
+
         // Perform initial update immediately
         debug!("Performing initial update");
         match self.update_once().await {
@@ -109,7 +184,11 @@ impl UpdateLoop {
         let mut interval_timer = interval(self.config.refresh_interval);
 
         while self.state.is_running().await {
-            interval_timer.tick().await;
+            // Queued commands trigger an update right away
+            select! {
+                _ = interval_timer.tick() => {}
+                _ = self.state.wait_for_commands() => {}
+            }
 
             match self.update_once().await {
                 Ok(()) => {
@@ -131,32 +210,214 @@ impl UpdateLoop {
         Ok(())
     }
 
+    /// Wrap the client so its calls fail or slow down as configured
+    pub fn with_faults(mut self, faults: FaultConfig) -> Self {
+        self.client = Box::new(FaultyGlazewmClient::new(self.client, faults));
+        self
+    }
+
+    /// Redact window titles and process names in every response
+    pub fn with_redaction(mut self, mode: RedactionMode) -> Self {
+        self.redactor = Redactor::new(mode);
+        self
+    }
+
+    /// Compare window geometry with the layout from these gaps and tolerance
+    pub fn with_tiling_model(mut self, tiling: TilingModel) -> Self {
+        self.tiling = tiling;
+        self
+    }
+
     /// Perform a single update cycle
     async fn update_once(&self) -> Result<(), UpdateError> {
         if !self.state.is_running().await {
             return Err(UpdateError::Stopped);
         }
 
-        // Query monitors first
-        let monitors_json = timeout(self.config.command_timeout, self.client.query_monitors())
-            .await
-            .map_err(|_| CliError::CommandTimeout {
-                command: "query monitors".to_string(),
-                timeout: self.config.command_timeout,
-            })??;
+        self.run_pending_commands().await;
+        if self.state.take_report_request().await {
+            self.write_requested_report().await;
+        }
+
+        // The TUI keeps showing the previous state along with the error
+        let result = self.refresh().await;
+        self.state
+            .set_last_error(result.as_ref().err().map(ToString::to_string))
+            .await;
+        let succeeded = result.is_ok();
+        self.state
+            .update_metrics(|metrics| metrics.record_update(succeeded))
+            .await;
+        self.state.mark_updated();
+        Ok(result?)
+    }
+
+    /// Query, parse and reconcile glazewm state, then publish it
+    async fn refresh(&self) -> Result<(), CliError> {
+        // Measure glazewm alone: redaction comes after the clock stops
+        let bytes_before = self.client.bytes_received();
+        let poll_started = Instant::now();
+        let (mut monitors_result, mut windows_result) = self.query_unredacted().await;
+        let poll_latency = poll_started.elapsed();
+        let payload_size = match (bytes_before, self.client.bytes_received()) {
+            (Some(before), Some(after)) => after.saturating_sub(before),
+            // Without a glazewm process, size the documents as they arrived
+            _ => [&monitors_result, &windows_result]
+                .into_iter()
+                .flatten()
+                .map(|json| json.to_string().len() as u64)
+                .sum(),
+        };
+        self.redact(&mut monitors_result, &mut windows_result);
+        self.state
+            .update_perf(|perf| {
+                perf.poll_latency.record_duration(poll_latency);
+                perf.payload_size.record(payload_size as f64);
+            })
+            .await;
+        let monitors_json = monitors_result?;
+
+        // Parse monitors and windows
+        let parse_started = Instant::now();
+        let parsed = GlazewmParser::parse_monitors(&monitors_json)?;
+        let parsed_windows = windows_result.and_then(|json| GlazewmParser::parse_windows(&json));
+        let parse_time = parse_started.elapsed();
+        let mut warnings = parsed.warnings;
+
+        // Cross-check the tree against the flat window list. A failing windows
+        // query must not hide the tree, so it only clears the inconsistencies.
+        let inconsistencies = match parsed_windows {
+            Ok(parsed_windows) => {
+                warnings.extend(parsed_windows.warnings);
+                WindowReconciler::reconcile(&parsed.monitors, &parsed_windows.windows)
+            }
+            Err(cli_err) => {
+                error!("Windows query failed, skipping reconciliation: {}", cli_err);
+                Vec::new()
+            }
+        };
+
+        for warning in &warnings {
+            debug!("Parse warning: {}", warning);
+        }
 
-        // Parse monitors
-        let monitors = GlazewmParser::parse_monitors(&monitors_json)?;
+        let deviations = self.tiling.deviations(&parsed.monitors);
+        let geometry = GeometryAnalyzer::analyze(&parsed.monitors);
 
-        // Note: Windows are already included in the monitor/workspace hierarchy from glazewm
-        // No separate window parsing is needed
+        self.state
+            .update_metrics(|metrics| {
+                metrics.record_parse(parse_time);
+                metrics.record_snapshot(&parsed.monitors);
+            })
+            .await;
+        self.state
+            .update_perf(|perf| perf.parse_time.record_duration(parse_time))
+            .await;
 
         // Update application state
-        self.state.update_monitors(monitors).await;
+        self.state.update_monitors(parsed.monitors).await;
+        self.state
+            .update_monitor_placeholders(parsed.placeholders)
+            .await;
+        self.state.update_parse_warnings(warnings).await;
+        self.state.update_inconsistencies(inconsistencies).await;
+        self.state.update_layout_deviations(deviations).await;
+        self.state.update_geometry(geometry).await;
 
         Ok(())
     }
 
+    /// Query monitors and windows concurrently, each bounded by the command
+    /// timeout, without parsing the responses. Both are redacted, so every
+    /// consumer sees the same pseudonyms.
+    pub async fn query_raw(&self) -> (Result<Value, CliError>, Result<Value, CliError>) {
+        let (mut monitors, mut windows) = self.query_unredacted().await;
+        self.redact(&mut monitors, &mut windows);
+        (monitors, windows)
+    }
+
+    /// `query_raw` before redaction
+    async fn query_unredacted(&self) -> (Result<Value, CliError>, Result<Value, CliError>) {
+        tokio::join!(
+            self.query_with_timeout("monitors", self.client.query_monitors()),
+            self.query_with_timeout("windows", self.client.query_windows()),
+        )
+    }
+
+    /// Redact both responses of one poll, whichever succeeded
+    fn redact(
+        &self,
+        monitors: &mut Result<Value, CliError>,
+        windows: &mut Result<Value, CliError>,
+    ) {
+        for document in [monitors, windows].into_iter().flatten() {
+            self.redactor.redact_document(document);
+        }
+    }
+
+    /// Query glazewm's version, bounded by the command timeout
+    pub async fn query_app_metadata(&self) -> Result<Value, CliError> {
+        self.query_with_timeout("app-metadata", self.client.query_app_metadata())
+            .await
+    }
+
+    /// Write a bug report into the working directory and tell the TUI where
+    async fn write_requested_report(&self) {
+        let path = PathBuf::from(report::default_file_name(std::time::SystemTime::now()));
+        let notice = match report::write(self, &path).await {
+            Ok(()) => {
+                info!("Wrote bug report to {}", path.display());
+                format!("Wrote bug report to {}", path.display())
+            }
+            Err(e) => {
+                error!("Failed to write bug report: {}", e);
+                format!("Failed to write bug report: {}", e)
+            }
+        };
+        self.state.set_notice(notice).await;
+    }
+
+    /// Send commands queued from the TUI; failures are logged and skipped
+    async fn run_pending_commands(&self) {
+        for command in self.state.take_pending_commands().await {
+            debug!("Running command: {}", command);
+            let result = timeout(
+                self.config.command_timeout,
+                self.client.run_command(&command),
+            )
+            .await
+            .unwrap_or_else(|_| {
+                Err(CliError::CommandTimeout {
+                    command: command.clone(),
+                    timeout: self.config.command_timeout,
+                })
+            });
+            if let Err(cli_err) = result {
+                error!("Command failed: {}", cli_err);
+            }
+        }
+    }
+
+    /// Await a client query, converting an elapsed timeout into a CLI error
+    /// and recording its latency
+    async fn query_with_timeout(
+        &self,
+        query_name: &'static str,
+        query: impl std::future::Future<Output = Result<Value, CliError>>,
+    ) -> Result<Value, CliError> {
+        let started = Instant::now();
+        let result = timeout(self.config.command_timeout, query).await;
+        let elapsed = started.elapsed();
+        self.state
+            .update_metrics(|metrics| metrics.record_query(query_name, elapsed))
+            .await;
+
+        result.map_err(|_| CliError::CommandTimeout {
+            command: format!("query {}", query_name),
+            timeout: self.config.command_timeout,
+        })?
+    }
+
     /// Perform a single update immediately
     pub async fn update_now(&self) -> Result<(), UpdateError> {
         self.update_once().await
@@ -166,13 +427,21 @@ impl UpdateLoop {
     pub fn state(&self) -> &AppState {
         &self.state
     }
+
+    /// The redactor applied to every response
+    pub fn redactor(&self) -> &Redactor {
+        &self.redactor
+    }
+
+    pub fn config(&self) -> &UpdateConfig {
+        &self.config
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use async_trait::async_trait;
-    use serde_json::Value;
     use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
 
@@ -190,8 +459,8 @@ mod tests {
             }
         }
 
-        fn call_count(&self) -> usize {
-            self.call_count.load(Ordering::Relaxed)
+        fn call_counter(&self) -> Arc<AtomicUsize> {
+            Arc::clone(&self.call_count)
         }
     }
 
@@ -250,10 +519,12 @@ mod tests {
         let config = UpdateConfig::default();
         let state = AppState::new();
         let client = MockGlazewmClient::new(false);
+        let calls = client.call_counter();
         let update_loop = UpdateLoop::with_client(Box::new(client), config, state.clone());
 
         let result = update_loop.update_once().await;
         assert!(result.is_ok());
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
 
         // Should have updated state with one monitor
         assert_eq!(state.monitor_count().await, 1);
@@ -273,6 +544,38 @@ mod tests {
         assert_eq!(state.monitor_count().await, 0);
     }
 
+    #[tokio::test]
+    async fn should_record_injected_faults_until_the_next_success() {
+        let state = AppState::new();
+        let faults = FaultConfig {
+            rate: 1.0,
+            kinds: vec![crate::cli::FaultKind::Unsuccessful],
+            latency: Duration::ZERO,
+            seed: 1,
+        };
+        let update_loop = UpdateLoop::with_client(
+            Box::new(MockGlazewmClient::new(false)),
+            UpdateConfig::default(),
+            state.clone(),
+        )
+        .with_faults(faults);
+
+        assert!(update_loop.update_once().await.is_err());
+        assert!(state
+            .get_last_error()
+            .await
+            .unwrap()
+            .contains("Injected fault"));
+
+        let update_loop = UpdateLoop::with_client(
+            Box::new(MockGlazewmClient::new(false)),
+            UpdateConfig::default(),
+            state.clone(),
+        );
+        update_loop.update_once().await.unwrap();
+        assert_eq!(state.get_last_error().await, None);
+    }
+
     #[tokio::test]
     async fn should_stop_when_application_stops() {
         let config = UpdateConfig::default();
@@ -287,6 +590,28 @@ mod tests {
         assert!(matches!(result.unwrap_err(), UpdateError::Stopped));
     }
 
+    #[tokio::test]
+    async fn should_publish_whether_the_client_accepts_commands() {
+        for (update_loop, state, expected) in [
+            {
+                let state = AppState::new();
+                let client = Box::new(MockGlazewmClient::new(false));
+                let update_loop =
+                    UpdateLoop::with_client(client, UpdateConfig::default(), state.clone());
+                (update_loop, state, false)
+            },
+            {
+                let state = AppState::new();
+                let update_loop = UpdateLoop::new_demo(UpdateConfig::default(), state.clone());
+                (update_loop, state, true)
+            },
+        ] {
+            state.stop().await;
+            update_loop.run().await.unwrap();
+            assert_eq!(state.commands_enabled().await, expected);
+        }
+    }
+
     /// Slow mock client for timeout testing
     struct SlowMockClient {
         delay: Duration,
@@ -313,8 +638,10 @@ mod tests {
 
     #[tokio::test]
     async fn should_handle_timeout_gracefully() {
-        let mut config = UpdateConfig::default();
-        config.command_timeout = Duration::from_millis(100);
+        let config = UpdateConfig {
+            command_timeout: Duration::from_millis(100),
+            ..Default::default()
+        };
 
         let state = AppState::new();
 
@@ -337,8 +664,10 @@ mod tests {
 
     #[tokio::test]
     async fn should_continue_after_timeout_errors() {
-        let mut config = UpdateConfig::default();
-        config.command_timeout = Duration::from_millis(50);
+        let config = UpdateConfig {
+            command_timeout: Duration::from_millis(50),
+            ..Default::default()
+        };
 
         let state = AppState::new();
 
@@ -355,4 +684,219 @@ mod tests {
         // Application should still be responsive
         assert!(state.is_running().await);
     }
+
+    /// Mock client returning fixed responses for both queries
+    struct FixedClient {
+        monitors: Value,
+        windows: Result<Value, CliError>,
+    }
+
+    #[async_trait]
+    impl GlazewmClient for FixedClient {
+        async fn query_monitors(&self) -> Result<Value, CliError> {
+            Ok(self.monitors.clone())
+        }
+
+        async fn query_windows(&self) -> Result<Value, CliError> {
+            self.windows.clone()
+        }
+    }
+
+    fn monitors_with_window(window: Value) -> Value {
+        serde_json::json!({
+            "success": true,
+            "data": {
+                "monitors": [{
+                    "type": "monitor",
+                    "id": "mock-monitor",
+                    "x": 0,
+                    "y": 0,
+                    "width": 1920,
+                    "height": 1080,
+                    "scaleFactor": 1.0,
+                    "dpi": 96,
+                    "hasFocus": true,
+                    "children": [{
+                        "type": "workspace",
+                        "id": "mock-ws",
+                        "name": "1",
+                        "hasFocus": true,
+                        "isDisplayed": true,
+                        "tilingDirection": "horizontal",
+                        "children": [window]
+                    }]
+                }]
+            }
+        })
+    }
+
+    fn mock_window(id: &str, width: u32) -> Value {
+        serde_json::json!({
+            "type": "window",
+            "id": id,
+            "hasFocus": false,
+            "width": width,
+            "height": 1080,
+            "x": 0,
+            "y": 0,
+            "state": { "type": "tiling" },
+            "displayState": "shown",
+            "title": "Mock",
+            "processName": "mock"
+        })
+    }
+
+    #[tokio::test]
+    async fn should_record_inconsistencies_between_queries() {
+        let state = AppState::new();
+        let client = FixedClient {
+            monitors: monitors_with_window(mock_window("w1", 1920)),
+            windows: Ok(serde_json::json!({
+                "success": true,
+                "data": { "windows": [mock_window("w1", 960), mock_window("orphan", 960)] }
+            })),
+        };
+        let update_loop =
+            UpdateLoop::with_client(Box::new(client), UpdateConfig::default(), state.clone());
+
+        update_loop.update_once().await.unwrap();
+
+        let inconsistencies = state.get_inconsistencies().await;
+        assert_eq!(inconsistencies.len(), 2);
+        assert_eq!(inconsistencies[0].window_id().as_str(), "w1");
+        assert_eq!(inconsistencies[1].window_id().as_str(), "orphan");
+    }
+
+    #[tokio::test]
+    async fn should_publish_monitors_that_fail_validation() {
+        let state = AppState::new();
+        let mut monitors = monitors_with_window(mock_window("w1", 1920));
+        let mut unplugged = monitors["data"]["monitors"][0].clone();
+        unplugged["id"] = serde_json::json!("unplugged");
+        unplugged["dpi"] = serde_json::json!(0);
+        unplugged["children"] = serde_json::json!([]);
+        monitors["data"]["monitors"]
+            .as_array_mut()
+            .unwrap()
+            .push(unplugged);
+        let client = FixedClient {
+            monitors,
+            windows: windows_with(mock_window("w1", 1920)),
+        };
+        let update_loop =
+            UpdateLoop::with_client(Box::new(client), UpdateConfig::default(), state.clone());
+
+        update_loop.update_once().await.unwrap();
+
+        let placeholders = state.get_monitor_placeholders().await;
+        assert_eq!(placeholders.len(), 1);
+        assert_eq!(placeholders[0].id(), Some("unplugged"));
+        assert!(placeholders[0].raw_json().contains("unplugged"));
+        assert_eq!(state.monitor_count().await, 1);
+    }
+
+    #[tokio::test]
+    async fn should_update_tree_when_windows_query_fails() {
+        let state = AppState::new();
+        let client = FixedClient {
+            monitors: monitors_with_window(mock_window("w1", 1920)),
+            windows: Err(CliError::CommandExecutionFailed {
+                command: "query windows".to_string(),
+            }),
+        };
+        let update_loop =
+            UpdateLoop::with_client(Box::new(client), UpdateConfig::default(), state.clone());
+
+        assert!(update_loop.update_once().await.is_ok());
+        assert_eq!(state.total_window_count().await, 1);
+        assert!(state.get_inconsistencies().await.is_empty());
+    }
+
+    /// Reports a fixed amount of glazewm output per query
+    struct MeteredClient {
+        inner: FixedClient,
+        bytes: std::sync::atomic::AtomicU64,
+    }
+
+    #[async_trait]
+    impl GlazewmClient for MeteredClient {
+        async fn query_monitors(&self) -> Result<Value, CliError> {
+            self.bytes.fetch_add(1000, Ordering::Relaxed);
+            self.inner.query_monitors().await
+        }
+
+        async fn query_windows(&self) -> Result<Value, CliError> {
+            self.bytes.fetch_add(500, Ordering::Relaxed);
+            self.inner.query_windows().await
+        }
+
+        fn bytes_received(&self) -> Option<u64> {
+            Some(self.bytes.load(Ordering::Relaxed))
+        }
+    }
+
+    fn windows_with(window: Value) -> Result<Value, CliError> {
+        Ok(serde_json::json!({
+            "success": true,
+            "data": { "windows": [window] }
+        }))
+    }
+
+    async fn last_payload_size(state: &AppState) -> f64 {
+        state.get_perf().await.payload_size.summary().unwrap().last
+    }
+
+    #[tokio::test]
+    async fn should_record_the_bytes_the_client_received_as_payload_size() {
+        let state = AppState::new();
+        let client = MeteredClient {
+            inner: FixedClient {
+                monitors: monitors_with_window(mock_window("w1", 1920)),
+                windows: windows_with(mock_window("w1", 1920)),
+            },
+            bytes: std::sync::atomic::AtomicU64::new(123),
+        };
+        let update_loop =
+            UpdateLoop::with_client(Box::new(client), UpdateConfig::default(), state.clone());
+
+        update_loop.update_once().await.unwrap();
+        assert_eq!(last_payload_size(&state).await, 1500.0);
+        update_loop.update_once().await.unwrap();
+        assert_eq!(last_payload_size(&state).await, 1500.0);
+    }
+
+    #[tokio::test]
+    async fn should_size_payloads_before_redacting_them() {
+        let monitors = monitors_with_window(mock_window("w1", 1920));
+        let windows = windows_with(mock_window("w1", 1920));
+        let unredacted_size =
+            (monitors.to_string().len() + windows.as_ref().unwrap().to_string().len()) as f64;
+        let state = AppState::new();
+        let update_loop = UpdateLoop::with_client(
+            Box::new(FixedClient { monitors, windows }),
+            UpdateConfig::default(),
+            state.clone(),
+        )
+        .with_redaction(RedactionMode::Hash);
+
+        update_loop.update_once().await.unwrap();
+        assert_eq!(last_payload_size(&state).await, unredacted_size);
+    }
+
+    #[tokio::test]
+    async fn should_run_queued_commands_before_querying() {
+        let state = AppState::new();
+        let update_loop = UpdateLoop::with_client(
+            Box::new(DemoGlazewmClient::new()),
+            UpdateConfig::default(),
+            state.clone(),
+        );
+
+        state.queue_command("shell-exec notepad".to_string()).await;
+        state.queue_command("not-a-command".to_string()).await;
+
+        assert!(update_loop.update_once().await.is_ok());
+        assert_eq!(state.total_window_count().await, 6);
+        assert!(state.take_pending_commands().await.is_empty());
+    }
 }
@@ -0,0 +1,203 @@
+// Watch mode
+// Polls glazewm like the TUI, but prints one line per change to stdout for
+// CI logs, tmux panes and files
+
+use serde_json::{json, Value};
+use std::io::{self, Write};
+use std::time::SystemTime;
+use tokio::time::interval;
+
+use crate::app::UpdateLoop;
+use crate::domain::changes::{self, Change};
+use crate::domain::Monitor;
+use crate::utils::timestamp;
+
+/// How the watcher prints events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchFormat {
+    /// `<timestamp> <description>`
+    Text,
+    /// One JSON object per line, with `time` and `event` fields
+    Json,
+}
+
+/// Turns successive snapshots into printed change events
+pub struct Watcher<W: Write> {
+    format: WatchFormat,
+    out: W,
+    previous: Option<Vec<Monitor>>,
+    last_error: Option<String>,
+}
+
+impl<W: Write> Watcher<W> {
+    pub fn new(format: WatchFormat, out: W) -> Self {
+        Self {
+            format,
+            out,
+            previous: None,
+            last_error: None,
+        }
+    }
+
+    /// Print what changed since the previous update; the first successful
+    /// one prints a summary instead. `error` is the update's error, if it
+    /// failed, in which case `monitors` are the last good ones.
+    pub fn observe(
+        &mut self,
+        time: SystemTime,
+        monitors: &[Monitor],
+        error: Option<&str>,
+    ) -> io::Result<()> {
+        if error != self.last_error.as_deref() {
+            match error {
+                Some(error) => self.emit(
+                    time,
+                    &format!("update failed: {}", error),
+                    json!({ "event": "update_failed", "error": error }),
+                )?,
+                None => self.emit(
+                    time,
+                    "update recovered",
+                    json!({ "event": "update_recovered" }),
+                )?,
+            }
+            self.last_error = error.map(str::to_string);
+        }
+        if error.is_some() {
+            return Ok(());
+        }
+
+        match &self.previous {
+            None => {
+                let windows: usize = monitors.iter().map(Monitor::total_window_count).sum();
+                self.emit(
+                    time,
+                    &format!(
+                        "watching {} monitor(s) with {} window(s)",
+                        monitors.len(),
+                        windows
+                    ),
+                    json!({ "event": "started", "monitors": monitors.len(), "windows": windows }),
+                )?;
+            }
+            Some(previous) => {
+                for change in changes::compare(previous, monitors) {
+                    self.emit_change(time, &change)?;
+                }
+            }
+        }
+        self.previous = Some(monitors.to_vec());
+        self.out.flush()
+    }
+
+    fn emit_change(&mut self, time: SystemTime, change: &Change) -> io::Result<()> {
+        let value = serde_json::to_value(change)?;
+        self.emit(time, &change.to_string(), value)
+    }
+
+    fn emit(&mut self, time: SystemTime, text: &str, mut value: Value) -> io::Result<()> {
+        let time = timestamp::rfc3339(time);
+        match self.format {
+            WatchFormat::Text => writeln!(self.out, "{} {}", time, text),
+            WatchFormat::Json => {
+                value["time"] = Value::String(time);
+                writeln!(self.out, "{}", value)
+            }
+        }
+    }
+}
+
+/// Update at the refresh interval and print changes until the application
+/// stops or the output is closed
+pub async fn run<W: Write>(update_loop: &UpdateLoop, mut watcher: Watcher<W>) -> io::Result<()> {
+    let state = update_loop.state();
+    let mut timer = interval(update_loop.config().refresh_interval);
+
+    while state.is_running().await {
+        timer.tick().await;
+        // Failures are recorded in the state as the last error
+        let _ = update_loop.update_now().await;
+
+        let monitors = state.get_monitors().await;
+        let error = state.get_last_error().await;
+        watcher.observe(SystemTime::now(), &monitors, error.as_deref())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{GlazewmParser, Simulator};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn monitors(simulator: &Simulator) -> Vec<Monitor> {
+        GlazewmParser::parse_monitors(&simulator.monitors_json())
+            .unwrap()
+            .monitors
+    }
+
+    fn output(watcher: Watcher<Vec<u8>>) -> Vec<String> {
+        String::from_utf8(watcher.out)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn should_print_timestamped_changes() {
+        let mut simulator = Simulator::demo();
+        let mut watcher = Watcher::new(WatchFormat::Text, Vec::new());
+        let time = UNIX_EPOCH + Duration::from_secs(60);
+
+        watcher.observe(time, &monitors(&simulator), None).unwrap();
+        simulator.execute("toggle-floating").unwrap();
+        watcher.observe(time, &monitors(&simulator), None).unwrap();
+        watcher
+            .observe(time, &monitors(&simulator), Some("timed out"))
+            .unwrap();
+        watcher.observe(time, &monitors(&simulator), None).unwrap();
+
+        let lines = output(watcher);
+        assert_eq!(
+            lines[0],
+            "1970-01-01T00:01:00.000Z watching 2 monitor(s) with 5 window(s)"
+        );
+        assert!(lines
+            .iter()
+            .any(|line| line.ends_with("became floating (was tiling)")));
+        assert_eq!(
+            &lines[lines.len() - 2..],
+            [
+                "1970-01-01T00:01:00.000Z update failed: timed out",
+                "1970-01-01T00:01:00.000Z update recovered",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_print_ndjson_events() {
+        let mut simulator = Simulator::demo();
+        let mut watcher = Watcher::new(WatchFormat::Json, Vec::new());
+
+        watcher
+            .observe(UNIX_EPOCH, &monitors(&simulator), None)
+            .unwrap();
+        simulator
+            .execute("shell-exec notepad Untitled - Notepad")
+            .unwrap();
+        watcher
+            .observe(UNIX_EPOCH, &monitors(&simulator), None)
+            .unwrap();
+
+        let events: Vec<Value> = output(watcher)
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events[0]["event"], "started");
+        assert_eq!(events[1]["event"], "window_opened");
+        assert_eq!(events[1]["window"]["name"], "notepad: Untitled - Notepad");
+        assert_eq!(events[1]["time"], "1970-01-01T00:00:00.000Z");
+    }
+}
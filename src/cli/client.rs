@@ -4,11 +4,16 @@
 use async_trait::async_trait;
 use serde_json::Value;
 use std::path::PathBuf;
-use std::time::Duration;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 use tokio::process::Command;
 use tokio::time::timeout;
 
 use crate::cli::errors::CliError;
+use crate::cli::parser::GlazewmParser;
+use crate::cli::scenario::{Scenario, ScenarioError, ScenarioPlayer};
+use crate::cli::simulator::Simulator;
 
 /// Trait for communicating with glazewm CLI
 #[async_trait]
@@ -18,12 +23,48 @@ pub trait GlazewmClient {
 
     /// Query all windows from glazewm
     async fn query_windows(&self) -> Result<Value, CliError>;
+
+    /// Query glazewm's version via `query app-metadata`
+    ///
+    /// Clients without a glazewm process behind them cannot answer it.
+    async fn query_app_metadata(&self) -> Result<Value, CliError> {
+        Err(CliError::CommandRejected {
+            command: "query app-metadata".to_string(),
+            reason: "this client has no glazewm to ask".to_string(),
+        })
+    }
+
+    /// Bytes glazewm has written to stdout so far, for clients that run it
+    ///
+    /// Comparing two readings gives the size of the responses in between, as
+    /// glazewm sent them rather than as they look after parsing.
+    fn bytes_received(&self) -> Option<u64> {
+        None
+    }
+
+    /// Whether `run_command` can succeed at all, so callers can say so up
+    /// front instead of failing on every command
+    fn accepts_commands(&self) -> bool {
+        false
+    }
+
+    /// Run a glazewm command such as `focus --direction left`
+    ///
+    /// Clients that only observe glazewm reject every command.
+    async fn run_command(&self, command: &str) -> Result<(), CliError> {
+        Err(CliError::CommandRejected {
+            command: command.to_string(),
+            reason: "this client cannot change window manager state".to_string(),
+        })
+    }
 }
 
 /// Real implementation of GlazewmClient that executes actual commands
 pub struct RealGlazewmClient {
     glazewm_path: PathBuf,
     command_timeout: Duration,
+    /// Total stdout of every query, see `bytes_received`
+    bytes_received: AtomicU64,
 }
 
 impl RealGlazewmClient {
@@ -32,6 +73,7 @@ impl RealGlazewmClient {
         Self {
             glazewm_path,
             command_timeout,
+            bytes_received: AtomicU64::new(0),
         }
     }
 
@@ -46,9 +88,10 @@ impl RealGlazewmClient {
 
         // Create command
         let mut cmd = Command::new(&self.glazewm_path);
-        cmd.args(&["query", query_type]);
+        cmd.args(["query", query_type]);
 
         // Execute with timeout
+        let started = std::time::Instant::now();
         let output = timeout(self.command_timeout, cmd.output())
             .await
             .map_err(|_| {
@@ -65,10 +108,25 @@ impl RealGlazewmClient {
                 }
             })?;
 
+        tracing::debug!(
+            "{} returned {} bytes in {:?}",
+            command_str,
+            output.stdout.len(),
+            started.elapsed()
+        );
+        self.bytes_received
+            .fetch_add(output.stdout.len() as u64, Ordering::Relaxed);
+
         // Check exit status
         if !output.status.success() {
             let exit_code = output.status.code().unwrap_or(-1);
             let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            tracing::error!(
+                "Command exited with code {}: {} - stderr: {}",
+                exit_code,
+                command_str,
+                stderr.trim()
+            );
             return Err(CliError::CommandFailed {
                 command: command_str,
                 code: exit_code,
@@ -88,6 +146,9 @@ impl RealGlazewmClient {
 
     /// Validate that the response has the expected structure
     fn validate_response(&self, response: &Value) -> Result<(), CliError> {
+        // Keep glazewm's own message when it reports a failure
+        GlazewmParser::check_success(response)?;
+
         // Check for success field
         if !response
             .get("success")
@@ -119,182 +180,109 @@ impl GlazewmClient for RealGlazewmClient {
     async fn query_windows(&self) -> Result<Value, CliError> {
         self.execute_query("windows").await
     }
+
+    async fn query_app_metadata(&self) -> Result<Value, CliError> {
+        self.execute_query("app-metadata").await
+    }
+
+    fn bytes_received(&self) -> Option<u64> {
+        Some(self.bytes_received.load(Ordering::Relaxed))
+    }
 }
 
-/// Demo client that provides sample data without requiring glazewm
+/// Demo client backed by an in-process glazewm simulator (no glazewm required)
+///
+/// With a scenario, the simulator follows its timeline from the moment the
+/// client is created.
 pub struct DemoGlazewmClient {
-    window_count: std::sync::atomic::AtomicUsize,
+    player: Mutex<ScenarioPlayer>,
+    started: Instant,
 }
 
 impl DemoGlazewmClient {
+    /// Create a demo client with the sample layout from `Simulator::demo`
     pub fn new() -> Self {
+        Self::with_simulator(Simulator::demo())
+    }
+
+    /// Create a demo client around an existing simulator
+    pub fn with_simulator(simulator: Simulator) -> Self {
+        Self::with_player(ScenarioPlayer::from_simulator(simulator))
+    }
+
+    /// Create a demo client that plays a scenario's timeline
+    pub fn with_scenario(scenario: Scenario) -> Result<Self, ScenarioError> {
+        if let Some(name) = &scenario.name {
+            tracing::info!("Playing scenario: {}", name);
+        }
+        Ok(Self::with_player(scenario.into_player()?))
+    }
+
+    fn with_player(player: ScenarioPlayer) -> Self {
         Self {
-            window_count: std::sync::atomic::AtomicUsize::new(1),
+            player: Mutex::new(player),
+            started: Instant::now(),
         }
     }
 
-    fn get_demo_data(&self) -> Value {
-        let window_count = self
-            .window_count
-            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-        let focused_window = window_count % 4; // Rotate focus every 4 updates
+    /// Lock the player after applying every step that is due
+    fn player(&self) -> MutexGuard<'_, ScenarioPlayer> {
+        // A panic while applying a command leaves the model usable
+        let mut player = self
+            .player
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        player.advance(self.started.elapsed());
+        player
+    }
+}
 
-        serde_json::json!({
-            "success": true,
-            "data": {
-                "monitors": [
-                    {
-                        "type": "monitor",
-                        "id": "demo-monitor-1",
-                        "x": 0,
-                        "y": 0,
-                        "width": 1920,
-                        "height": 1080,
-                        "scaleFactor": 1.0,
-                        "dpi": 96,
-                        "hasFocus": true,
-                        "children": [
-                            {
-                                "type": "workspace",
-                                "id": "demo-workspace-1",
-                                "name": "Development",
-                                "hasFocus": true,
-                                "isDisplayed": true,
-                                "children": [
-                                    {
-                                        "type": "window",
-                                        "id": "demo-window-1",
-                                        "title": "Visual Studio Code - glazewm-debug",
-                                        "processName": "Code",
-                                        "className": "Chrome_WidgetWin_1",
-                                        "x": 0,
-                                        "y": 0,
-                                        "width": 960,
-                                        "height": 1040,
-                                        "state": {"type": "tiling"},
-                                        "hasFocus": focused_window == 0,
-                                        "isDisplayed": true,
-                                        "displayState": "shown"
-                                    },
-                                    {
-                                        "type": "window",
-                                        "id": "demo-window-2",
-                                        "title": "Firefox - Documentation",
-                                        "processName": "firefox",
-                                        "className": "MozillaWindowClass",
-                                        "x": 960,
-                                        "y": 0,
-                                        "width": 960,
-                                        "height": 1040,
-                                        "state": {"type": "tiling"},
-                                        "hasFocus": focused_window == 1,
-                                        "isDisplayed": true,
-                                        "displayState": "shown"
-                                    }
-                                ]
-                            },
-                            {
-                                "type": "workspace",
-                                "id": "demo-workspace-2",
-                                "name": "Testing",
-                                "hasFocus": false,
-                                "isDisplayed": false,
-                                "children": [
-                                    {
-                                        "type": "window",
-                                        "id": "demo-window-3",
-                                        "title": "Terminal - cargo test",
-                                        "processName": "wezterm-gui",
-                                        "className": "WEZTERM_GUI_CLASS",
-                                        "x": 0,
-                                        "y": 0,
-                                        "width": 1920,
-                                        "height": 1040,
-                                        "state": {"type": "tiling"},
-                                        "hasFocus": false,
-                                        "isDisplayed": false,
-                                        "displayState": "hidden"
-                                    }
-                                ]
-                            }
-                        ]
-                    },
-                    {
-                        "type": "monitor",
-                        "id": "demo-monitor-2",
-                        "x": 1920,
-                        "y": 0,
-                        "width": 2560,
-                        "height": 1440,
-                        "scaleFactor": 1.25,
-                        "dpi": 120,
-                        "hasFocus": false,
-                        "children": [
-                            {
-                                "type": "workspace",
-                                "id": "demo-workspace-3",
-                                "name": "Communication",
-                                "hasFocus": true,
-                                "isDisplayed": true,
-                                "children": [
-                                    {
-                                        "type": "window",
-                                        "id": "demo-window-4",
-                                        "title": "Discord - #general",
-                                        "processName": "Discord",
-                                        "className": "Chrome_WidgetWin_1",
-                                        "x": 0,
-                                        "y": 0,
-                                        "width": 1280,
-                                        "height": 1400,
-                                        "state": {"type": "tiling"},
-                                        "hasFocus": focused_window == 2,
-                                        "isDisplayed": true,
-                                        "displayState": "shown"
-                                    },
-                                    {
-                                        "type": "window",
-                                        "id": "demo-window-5",
-                                        "title": "Spotify - Currently Playing",
-                                        "processName": "Spotify",
-                                        "className": "Chrome_WidgetWin_1",
-                                        "x": 1280,
-                                        "y": 0,
-                                        "width": 1280,
-                                        "height": 1400,
-                                        "state": {"type": "floating"},
-                                        "hasFocus": focused_window == 3,
-                                        "isDisplayed": true,
-                                        "displayState": "shown"
-                                    }
-                                ]
-                            }
-                        ]
-                    }
-                ]
-            },
-            "error": null
-        })
+impl Default for DemoGlazewmClient {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[async_trait]
 impl GlazewmClient for DemoGlazewmClient {
     async fn query_monitors(&self) -> Result<Value, CliError> {
+        // Take the snapshot before the simulated delay so a concurrent
+        // windows query observes the same state
+        let data = self.player().simulator().monitors_json();
+
         // Simulate some processing time
         tokio::time::sleep(Duration::from_millis(50)).await;
-        Ok(self.get_demo_data())
+        Ok(data)
     }
 
     async fn query_windows(&self) -> Result<Value, CliError> {
-        // For demo, windows are included in monitor query
+        let data = self.player().simulator().windows_json();
+
         tokio::time::sleep(Duration::from_millis(50)).await;
+        Ok(data)
+    }
+
+    async fn query_app_metadata(&self) -> Result<Value, CliError> {
         Ok(serde_json::json!({
             "success": true,
-            "data": { "windows": [] },
+            "data": { "version": "simulator" },
             "error": null
         }))
     }
+
+    fn accepts_commands(&self) -> bool {
+        true
+    }
+
+    async fn run_command(&self, command: &str) -> Result<(), CliError> {
+        self.player()
+            .simulator_mut()
+            .execute(command)
+            .map_err(|e| CliError::CommandRejected {
+                command: command.to_string(),
+                reason: e.to_string(),
+            })
+    }
 }
 
 #[cfg(test)]
@@ -334,4 +322,71 @@ mod tests {
 
         assert!(client.validate_response(&invalid_response).is_err());
     }
+
+    #[tokio::test]
+    async fn demo_window_list_should_match_monitor_tree() {
+        let client = DemoGlazewmClient::new();
+
+        let (monitors, windows) = tokio::join!(client.query_monitors(), client.query_windows());
+        let monitors = crate::cli::GlazewmParser::parse_monitors(&monitors.unwrap()).unwrap();
+        let windows = crate::cli::GlazewmParser::parse_windows(&windows.unwrap()).unwrap();
+
+        assert_eq!(windows.windows.len(), 5);
+        assert!(
+            crate::domain::WindowReconciler::reconcile(&monitors.monitors, &windows.windows)
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn demo_data_should_parse_without_warnings() {
+        let client = DemoGlazewmClient::new();
+        let parsed =
+            crate::cli::GlazewmParser::parse_monitors(&client.query_monitors().await.unwrap())
+                .unwrap();
+
+        assert_eq!(parsed.monitors.len(), 2);
+        assert!(parsed.warnings.is_empty());
+        assert_eq!(parsed.monitors[0].hardware_id(), Some("DEMO0001"));
+        assert_eq!(parsed.monitors[0].reserved_insets().unwrap().bottom, 40);
+    }
+
+    #[tokio::test]
+    async fn demo_should_apply_commands_and_reject_invalid_ones() {
+        let client = DemoGlazewmClient::new();
+
+        client.run_command("shell-exec notepad").await.unwrap();
+        let windows = client.query_windows().await.unwrap();
+        assert_eq!(windows["data"]["windows"].as_array().unwrap().len(), 6);
+
+        assert!(matches!(
+            client.run_command("wm-exit").await,
+            Err(CliError::CommandRejected { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn demo_should_play_due_scenario_steps() {
+        let scenario = Scenario::from_json(
+            r#"{
+                "monitors": [{ "rect": { "x": 0, "y": 0, "width": 800, "height": 600 }, "workspace": "1" }],
+                "timeline": [
+                    { "at_ms": 0, "commands": ["shell-exec notepad"] },
+                    { "at_ms": 600000, "commands": ["close"] }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let client = DemoGlazewmClient::with_scenario(scenario).unwrap();
+
+        let windows = client.query_windows().await.unwrap();
+        assert_eq!(windows["data"]["windows"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn real_client_should_reject_commands() {
+        let client = RealGlazewmClient::new(PathBuf::from("glazewm"), Duration::from_secs(5));
+
+        assert!(client.run_command("close").await.is_err());
+    }
 }
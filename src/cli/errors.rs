@@ -23,6 +23,12 @@ pub enum CliError {
     #[error("Failed to parse JSON response: {message}")]
     JsonParseError { message: String },
 
+    #[error("glazewm reported an error: {message}")]
+    QueryFailed { message: String },
+
+    #[error("Agent could not query glazewm: {message}")]
+    AgentQueryFailed { message: String },
+
     #[error("Invalid JSON schema: missing field '{field}'")]
     InvalidJsonSchema { field: String },
 
@@ -31,6 +37,9 @@ pub enum CliError {
 
     #[error("IO error: {message}")]
     IoError { message: String },
+
+    #[error("Command rejected: {command}: {reason}")]
+    CommandRejected { command: String, reason: String },
 }
 
 impl From<std::io::Error> for CliError {
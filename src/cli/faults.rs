@@ -0,0 +1,324 @@
+// Fault injection
+// GlazewmClient decorator that makes a working client slow or broken on
+// purpose, to exercise the error handling of the update loop and the TUI
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::cli::client::GlazewmClient;
+use crate::cli::errors::CliError;
+
+/// A way a glazewm call can go wrong
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultKind {
+    /// The call never returns, so the caller's timeout fires
+    Timeout,
+    /// glazewm exits with a non-zero code
+    Exit,
+    /// The JSON output is cut off halfway
+    Truncated,
+    /// Valid JSON that does not match the glazewm schema
+    Schema,
+    /// A `success: false` response
+    Unsuccessful,
+}
+
+impl FaultKind {
+    pub const ALL: [FaultKind; 5] = [
+        FaultKind::Timeout,
+        FaultKind::Exit,
+        FaultKind::Truncated,
+        FaultKind::Schema,
+        FaultKind::Unsuccessful,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FaultKind::Timeout => "timeout",
+            FaultKind::Exit => "exit",
+            FaultKind::Truncated => "truncated",
+            FaultKind::Schema => "schema",
+            FaultKind::Unsuccessful => "unsuccessful",
+        }
+    }
+}
+
+impl fmt::Display for FaultKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FaultKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        FaultKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == value)
+            .ok_or_else(|| {
+                let names: Vec<&str> = FaultKind::ALL.iter().map(|kind| kind.as_str()).collect();
+                format!(
+                    "unknown fault '{}', expected one of: {}",
+                    value,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// What to inject and how often
+#[derive(Debug, Clone)]
+pub struct FaultConfig {
+    /// Share of calls that fail, from 0.0 to 1.0
+    pub rate: f64,
+    /// Faults to pick from, uniformly; all of them when empty
+    pub kinds: Vec<FaultKind>,
+    /// Delay added to every call, failing or not
+    pub latency: Duration,
+    /// Seed for the fault sequence; the same seed replays the same faults
+    pub seed: u64,
+}
+
+impl Default for FaultConfig {
+    fn default() -> Self {
+        Self {
+            rate: 0.0,
+            kinds: Vec::new(),
+            latency: Duration::ZERO,
+            seed: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(1),
+        }
+    }
+}
+
+/// Decorator that injects faults into another client's calls
+pub struct FaultyGlazewmClient {
+    inner: Box<dyn GlazewmClient + Send + Sync>,
+    config: FaultConfig,
+    rng: AtomicU64,
+}
+
+impl FaultyGlazewmClient {
+    pub fn new(inner: Box<dyn GlazewmClient + Send + Sync>, config: FaultConfig) -> Self {
+        // xorshift gets stuck at zero
+        let rng = AtomicU64::new(config.seed.max(1));
+        Self { inner, config, rng }
+    }
+
+    /// Next pseudo-random number (xorshift64), shared across concurrent calls
+    fn next_random(&self) -> u64 {
+        let mut next = 0;
+        let _ = self
+            .rng
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |mut x| {
+                x ^= x << 13;
+                x ^= x >> 7;
+                x ^= x << 17;
+                next = x;
+                Some(x)
+            });
+        next
+    }
+
+    /// Decide whether this call fails, and how
+    fn pick_fault(&self) -> Option<FaultKind> {
+        let roll = (self.next_random() >> 11) as f64 / (1u64 << 53) as f64;
+        if roll >= self.config.rate {
+            return None;
+        }
+
+        let kinds: &[FaultKind] = if self.config.kinds.is_empty() {
+            &FaultKind::ALL
+        } else {
+            &self.config.kinds
+        };
+        Some(kinds[(self.next_random() % kinds.len() as u64) as usize])
+    }
+
+    async fn query(
+        &self,
+        command: &str,
+        query: impl std::future::Future<Output = Result<Value, CliError>>,
+    ) -> Result<Value, CliError> {
+        tokio::time::sleep(self.config.latency).await;
+
+        let Some(fault) = self.pick_fault() else {
+            return query.await;
+        };
+        tracing::debug!("Injecting {} fault into {}", fault, command);
+
+        match fault {
+            FaultKind::Timeout => std::future::pending().await,
+            FaultKind::Exit => Err(exit_error(command)),
+            FaultKind::Truncated => {
+                let text = query.await?.to_string();
+                let mut cut = text.len() / 2;
+                while !text.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                Ok(serde_json::from_str(&text[..cut])?)
+            }
+            FaultKind::Schema => Ok(json!({
+                "success": true,
+                "data": { "monitors": "not-a-list", "windows": 42 },
+                "error": null
+            })),
+            FaultKind::Unsuccessful => Ok(json!({
+                "success": false,
+                "data": null,
+                "error": "Injected fault: glazewm reported a failure"
+            })),
+        }
+    }
+}
+
+fn exit_error(command: &str) -> CliError {
+    CliError::CommandFailed {
+        command: format!("glazewm {}", command),
+        code: 1,
+        stderr: "Injected fault".to_string(),
+    }
+}
+
+#[async_trait]
+impl GlazewmClient for FaultyGlazewmClient {
+    async fn query_monitors(&self) -> Result<Value, CliError> {
+        self.query("query monitors", self.inner.query_monitors())
+            .await
+    }
+
+    async fn query_windows(&self) -> Result<Value, CliError> {
+        self.query("query windows", self.inner.query_windows())
+            .await
+    }
+
+    async fn query_app_metadata(&self) -> Result<Value, CliError> {
+        self.query("query app-metadata", self.inner.query_app_metadata())
+            .await
+    }
+
+    fn bytes_received(&self) -> Option<u64> {
+        self.inner.bytes_received()
+    }
+
+    fn accepts_commands(&self) -> bool {
+        self.inner.accepts_commands()
+    }
+
+    /// Commands have no JSON output, so only timeouts and exits apply
+    async fn run_command(&self, command: &str) -> Result<(), CliError> {
+        tokio::time::sleep(self.config.latency).await;
+
+        match self.pick_fault() {
+            Some(FaultKind::Timeout) => std::future::pending().await,
+            Some(FaultKind::Exit) => Err(exit_error(&format!("command {}", command))),
+            _ => self.inner.run_command(command).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{GlazewmParser, Simulator};
+
+    /// Answers instantly, unlike the demo client
+    struct StubClient(Simulator);
+
+    #[async_trait]
+    impl GlazewmClient for StubClient {
+        async fn query_monitors(&self) -> Result<Value, CliError> {
+            Ok(self.0.monitors_json())
+        }
+
+        async fn query_windows(&self) -> Result<Value, CliError> {
+            Ok(self.0.windows_json())
+        }
+    }
+
+    fn faulty(rate: f64, kinds: Vec<FaultKind>) -> FaultyGlazewmClient {
+        FaultyGlazewmClient::new(
+            Box::new(StubClient(Simulator::demo())),
+            FaultConfig {
+                rate,
+                kinds,
+                latency: Duration::ZERO,
+                seed: 7,
+            },
+        )
+    }
+
+    #[test]
+    fn should_parse_fault_kinds() {
+        assert_eq!("truncated".parse::<FaultKind>(), Ok(FaultKind::Truncated));
+        assert!("flaky"
+            .parse::<FaultKind>()
+            .unwrap_err()
+            .contains("timeout, exit"));
+    }
+
+    #[tokio::test]
+    async fn should_pass_through_at_zero_rate() {
+        let client = faulty(0.0, Vec::new());
+
+        for _ in 0..5 {
+            assert!(client.query_monitors().await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn should_inject_each_kind_of_fault() {
+        assert!(matches!(
+            faulty(1.0, vec![FaultKind::Exit]).query_monitors().await,
+            Err(CliError::CommandFailed { code: 1, .. })
+        ));
+        assert!(matches!(
+            faulty(1.0, vec![FaultKind::Truncated])
+                .query_windows()
+                .await,
+            Err(CliError::JsonParseError { .. })
+        ));
+
+        let schema = faulty(1.0, vec![FaultKind::Schema])
+            .query_monitors()
+            .await
+            .unwrap();
+        assert!(GlazewmParser::parse_monitors(&schema).is_err());
+
+        let unsuccessful = faulty(1.0, vec![FaultKind::Unsuccessful])
+            .query_monitors()
+            .await
+            .unwrap();
+        assert!(matches!(
+            GlazewmParser::parse_monitors(&unsuccessful),
+            Err(CliError::QueryFailed { .. })
+        ));
+
+        let timeout = tokio::time::timeout(
+            Duration::from_millis(50),
+            faulty(1.0, vec![FaultKind::Timeout]).query_monitors(),
+        )
+        .await;
+        assert!(timeout.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_fail_roughly_at_the_configured_rate() {
+        let client = faulty(0.5, vec![FaultKind::Exit]);
+
+        let mut failures = 0;
+        for _ in 0..200 {
+            if client.query_monitors().await.is_err() {
+                failures += 1;
+            }
+        }
+        assert!((60..140).contains(&failures), "{} failures", failures);
+    }
+}
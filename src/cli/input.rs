@@ -0,0 +1,333 @@
+// Captured input
+// Client that reads `glazewm query monitors` output from stdin or a file
+// instead of running glazewm, e.g. to view captures taken on Windows
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::watch;
+
+use crate::cli::client::GlazewmClient;
+use crate::cli::errors::CliError;
+use crate::cli::simulator::collect_windows;
+
+/// Where captured JSON comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// One or more documents on stdin, the latest one shown
+    Stdin,
+    /// A file that is re-read whenever it changes on disk
+    File(PathBuf),
+}
+
+impl InputSource {
+    /// Interpret a command line argument; `-` means stdin
+    pub fn from_arg(arg: &Path) -> Self {
+        if arg == Path::new("-") {
+            InputSource::Stdin
+        } else {
+            InputSource::File(arg.to_path_buf())
+        }
+    }
+}
+
+/// Latest document read from a stream; `None` until the first one arrives
+type StreamSlot = Option<Result<Value, CliError>>;
+
+enum Source {
+    Stream(watch::Receiver<StreamSlot>),
+    File {
+        path: PathBuf,
+        /// Modification time and contents of the last successful read
+        cache: Mutex<Option<(SystemTime, Value)>>,
+    },
+    /// Recorded documents, advancing one per interval and then holding the
+    /// last one
+    Replay {
+        documents: Vec<Value>,
+        started: Instant,
+        interval: Duration,
+    },
+}
+
+/// Read-only client serving captured `query monitors` responses
+///
+/// The window list is derived from the monitor tree, since a capture has no
+/// separate `query windows` output.
+pub struct InputGlazewmClient {
+    source: Source,
+}
+
+impl InputGlazewmClient {
+    pub fn new(source: InputSource) -> Self {
+        match source {
+            InputSource::Stdin => Self::from_reader(std::io::stdin()),
+            InputSource::File(path) => Self {
+                source: Source::File {
+                    path,
+                    cache: Mutex::new(None),
+                },
+            },
+        }
+    }
+
+    /// Read a stream of concatenated or newline-delimited JSON documents on a
+    /// background thread
+    pub fn from_reader<R: Read + Send + 'static>(reader: R) -> Self {
+        let (sender, receiver) = watch::channel(None);
+
+        std::thread::spawn(move || {
+            let mut received = false;
+            for document in serde_json::Deserializer::from_reader(reader).into_iter::<Value>() {
+                match document {
+                    Ok(value) => {
+                        received = true;
+                        if sender.send(Some(Ok(value))).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => {
+                        // The stream cannot resynchronize after malformed JSON
+                        let _ = sender.send(Some(Err(CliError::from(e))));
+                        return;
+                    }
+                }
+            }
+            if !received {
+                let _ = sender.send(Some(Err(CliError::IoError {
+                    message: "input ended without a JSON document".to_string(),
+                })));
+            }
+        });
+
+        Self {
+            source: Source::Stream(receiver),
+        }
+    }
+
+    /// Play back a recording (see `read_recording`) from now on; never empty
+    pub fn replay(documents: Vec<Value>, interval: Duration) -> Self {
+        assert!(
+            !documents.is_empty(),
+            "a replay needs at least one document"
+        );
+        Self {
+            source: Source::Replay {
+                documents,
+                started: Instant::now(),
+                interval,
+            },
+        }
+    }
+
+    async fn latest(&self) -> Result<Value, CliError> {
+        let document =
+            match &self.source {
+                Source::Stream(receiver) => {
+                    // Wait for the first document; later calls return immediately
+                    let mut receiver = receiver.clone();
+                    let slot = receiver.wait_for(Option::is_some).await.map_err(|_| {
+                        CliError::IoError {
+                            message: "input reader stopped".to_string(),
+                        }
+                    })?;
+                    slot.clone().expect("waited for a document")?
+                }
+                Source::File { path, cache } => read_file(path, cache)?,
+                Source::Replay {
+                    documents,
+                    started,
+                    interval,
+                } => {
+                    let step = started.elapsed().as_millis() / interval.as_millis().max(1);
+                    let index = usize::try_from(step).unwrap_or(usize::MAX);
+                    documents[index.min(documents.len() - 1)].clone()
+                }
+            };
+        Ok(normalize(document))
+    }
+}
+
+/// Re-read the file only when its modification time changes
+fn read_file(path: &Path, cache: &Mutex<Option<(SystemTime, Value)>>) -> Result<Value, CliError> {
+    let modified = std::fs::metadata(path)?.modified()?;
+    let mut cache = cache
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    if let Some((cached_at, value)) = cache.as_ref() {
+        if *cached_at == modified {
+            return Ok(value.clone());
+        }
+    }
+
+    tracing::debug!("Reading input file {}", path.display());
+    let value: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    *cache = Some((modified, value.clone()));
+    Ok(value)
+}
+
+/// Read a single captured `query monitors` document, e.g. one attached to an
+/// issue
+pub fn read_capture(path: &Path) -> Result<Value, CliError> {
+    let value: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    Ok(normalize(value))
+}
+
+/// Read every document of a recording made by the `record` command
+pub fn read_recording(path: &Path) -> Result<Vec<Value>, CliError> {
+    let text = std::fs::read_to_string(path)?;
+    let documents = serde_json::Deserializer::from_str(&text)
+        .into_iter::<Value>()
+        .map(|document| document.map(normalize))
+        .collect::<Result<Vec<_>, _>>()?;
+    if documents.is_empty() {
+        return Err(CliError::IoError {
+            message: format!("{} contains no JSON document", path.display()),
+        });
+    }
+    Ok(documents)
+}
+
+/// Accept a full CLI response or just its `data` object
+fn normalize(document: Value) -> Value {
+    if document.get("data").is_some() {
+        document
+    } else {
+        json!({ "success": true, "data": document, "error": null })
+    }
+}
+
+#[async_trait]
+impl GlazewmClient for InputGlazewmClient {
+    async fn query_monitors(&self) -> Result<Value, CliError> {
+        self.latest().await
+    }
+
+    async fn query_windows(&self) -> Result<Value, CliError> {
+        let monitors = self.latest().await?;
+        let mut windows = Vec::new();
+        collect_windows(&monitors["data"], &mut windows);
+
+        Ok(json!({
+            "success": true,
+            "data": { "windows": windows },
+            "error": null
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{GlazewmParser, Simulator};
+    use std::time::Duration;
+
+    fn monitor_count(value: &Value) -> usize {
+        GlazewmParser::parse_monitors(value).unwrap().monitors.len()
+    }
+
+    #[tokio::test]
+    async fn should_show_latest_document_from_stream() {
+        let mut simulator = Simulator::demo();
+        let first = simulator.monitors_json().to_string();
+        simulator.remove_monitor(1).unwrap();
+        // The second document is bare `data`, newline separated
+        let second = simulator.monitors_json()["data"].to_string();
+        let input = format!("{}\n{}\n", first, second);
+
+        let client = InputGlazewmClient::from_reader(std::io::Cursor::new(input));
+
+        // The reader thread may not have reached the second document yet
+        let mut count = 0;
+        for _ in 0..100 {
+            count = monitor_count(&client.query_monitors().await.unwrap());
+            if count == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(count, 1);
+
+        let windows = client.query_windows().await.unwrap();
+        assert_eq!(windows["data"]["windows"].as_array().unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn should_report_malformed_and_empty_streams() {
+        let client = InputGlazewmClient::from_reader(std::io::Cursor::new("{ \"data\": "));
+        assert!(matches!(
+            client.query_monitors().await,
+            Err(CliError::JsonParseError { .. })
+        ));
+
+        let client = InputGlazewmClient::from_reader(std::io::Cursor::new(""));
+        assert!(matches!(
+            client.query_monitors().await,
+            Err(CliError::IoError { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_reload_file_when_it_changes() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut simulator = Simulator::demo();
+        std::fs::write(file.path(), simulator.monitors_json().to_string()).unwrap();
+
+        let client = InputGlazewmClient::new(InputSource::from_arg(file.path()));
+        assert_eq!(monitor_count(&client.query_monitors().await.unwrap()), 2);
+
+        simulator.remove_monitor(1).unwrap();
+        std::fs::write(file.path(), simulator.monitors_json().to_string()).unwrap();
+        // Make the change visible even on filesystems with coarse timestamps
+        let later = SystemTime::now() + Duration::from_secs(2);
+        std::fs::File::options()
+            .write(true)
+            .open(file.path())
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+
+        assert_eq!(monitor_count(&client.query_monitors().await.unwrap()), 1);
+    }
+
+    #[tokio::test]
+    async fn should_replay_recorded_documents_one_per_interval() {
+        let mut simulator = Simulator::demo();
+        let mut recording = simulator.monitors_json().to_string();
+        simulator.remove_monitor(1).unwrap();
+        recording.push_str(&format!("\n{}\n", simulator.monitors_json()["data"]));
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), recording).unwrap();
+
+        let documents = read_recording(file.path()).unwrap();
+        assert_eq!(documents.len(), 2);
+
+        let client = InputGlazewmClient::replay(documents, Duration::from_millis(50));
+        assert_eq!(monitor_count(&client.query_monitors().await.unwrap()), 2);
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        // Past the end, the last document stays
+        assert_eq!(monitor_count(&client.query_monitors().await.unwrap()), 1);
+    }
+
+    #[test]
+    fn should_reject_empty_recordings() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        assert!(matches!(
+            read_recording(file.path()),
+            Err(CliError::IoError { .. })
+        ));
+    }
+
+    #[test]
+    fn should_treat_dash_as_stdin() {
+        assert_eq!(InputSource::from_arg(Path::new("-")), InputSource::Stdin);
+        assert_eq!(
+            InputSource::from_arg(Path::new("capture.json")),
+            InputSource::File(PathBuf::from("capture.json"))
+        );
+    }
+}
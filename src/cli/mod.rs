@@ -3,8 +3,22 @@
 
 pub mod client;
 pub mod errors;
+pub mod faults;
+pub mod input;
 pub mod parser;
+pub mod redaction;
+pub mod remote;
+pub mod scenario;
+pub mod simulator;
+pub mod warnings;
 
 pub use client::{DemoGlazewmClient, GlazewmClient, RealGlazewmClient};
 pub use errors::CliError;
-pub use parser::GlazewmParser;
+pub use faults::{FaultConfig, FaultKind, FaultyGlazewmClient};
+pub use input::{read_capture, read_recording, InputGlazewmClient, InputSource};
+pub use parser::{GlazewmParser, ParsedMonitors, ParsedWindows};
+pub use redaction::{RedactionError, RedactionMode, Redactor};
+pub use remote::{AgentMessage, OutcomeChange, QueryOutcome, RemoteGlazewmClient};
+pub use scenario::{Scenario, ScenarioError, ScenarioPlayer};
+pub use simulator::{MonitorSetup, Simulator, SimulatorError, WmCommand};
+pub use warnings::ParseWarning;
@@ -5,21 +5,29 @@ use serde::Deserialize;
 use serde_json::Value;
 
 use crate::cli::errors::CliError;
+use crate::cli::warnings::ParseWarning;
 use crate::domain::{
     values::{Position, Rectangle, Size},
-    DisplayState, FocusState, Monitor, MonitorId, TilingDirection, Window, WindowId, WindowState,
-    Workspace, WorkspaceId,
+    ContainerId, DisplayState, FocusState, LayoutNode, Monitor, MonitorId, NodeKind, Placeholder,
+    SplitContainer, TilingDirection, Window, WindowId, WindowState, Workspace, WorkspaceId,
 };
 
 /// Raw JSON structures from glazewm CLI
+/// Fields mirror glazewm's schema; not every field is consumed by the domain model yet.
 
 #[derive(Debug, Deserialize)]
 struct MonitorResponse {
-    monitors: Vec<RawMonitor>,
+    monitors: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WindowResponse {
+    windows: Vec<Value>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
 struct RawMonitor {
     #[serde(rename = "type")]
     monitor_type: String,
@@ -32,10 +40,9 @@ struct RawMonitor {
     height: u32,
     scale_factor: f64,
     dpi: i32,
-    #[serde(rename = "hasFocus")]
     has_focus: bool,
-    children: Vec<RawMonitorChild>,
-    #[serde(default, rename = "childFocusOrder")]
+    children: Vec<Value>,
+    #[serde(default)]
     child_focus_order: Vec<String>,
     #[serde(default)]
     handle: Option<i64>,
@@ -46,81 +53,119 @@ struct RawMonitor {
     #[serde(default)]
     hardware_id: Option<String>,
     #[serde(default)]
-    working_rect: Option<serde_json::Value>,
+    working_rect: Option<RawRect>,
+}
+
+/// Rectangle as serialized by glazewm; accepts either edge coordinates or
+/// position and size
+#[derive(Debug, Deserialize)]
+struct RawRect {
+    #[serde(default)]
+    x: Option<i32>,
+    #[serde(default)]
+    y: Option<i32>,
+    #[serde(default)]
+    width: Option<u32>,
+    #[serde(default)]
+    height: Option<u32>,
+    #[serde(default)]
+    left: Option<i32>,
+    #[serde(default)]
+    top: Option<i32>,
+    #[serde(default)]
+    right: Option<i32>,
+    #[serde(default)]
+    bottom: Option<i32>,
+}
+
+impl RawRect {
+    fn to_rectangle(&self) -> Option<Rectangle> {
+        let x = self.x.or(self.left)?;
+        let y = self.y.or(self.top)?;
+        let width = self
+            .width
+            .or_else(|| Some(self.right?.saturating_sub(x).max(0) as u32))?;
+        let height = self
+            .height
+            .or_else(|| Some(self.bottom?.saturating_sub(y).max(0) as u32))?;
+
+        Some(Rectangle::from_coords(x, y, width, height))
+    }
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[serde(tag = "type")]
-enum RawMonitorChild {
-    #[serde(rename = "workspace")]
-    Workspace {
-        id: String,
-        name: String,
-        #[serde(default)]
-        display_name: Option<String>,
-        #[serde(default)]
-        parent_id: Option<String>,
-        #[serde(rename = "hasFocus")]
-        has_focus: bool,
-        #[serde(rename = "isDisplayed")]
-        is_displayed: bool,
-        #[serde(rename = "tilingDirection")]
-        tiling_direction: String,
-        children: Vec<RawWorkspaceChild>,
-        #[serde(default, rename = "childFocusOrder")]
-        child_focus_order: Vec<String>,
-        #[serde(default)]
-        x: i32,
-        #[serde(default)]
-        y: i32,
-        #[serde(default)]
-        width: u32,
-        #[serde(default)]
-        height: u32,
-    },
-    #[serde(other)]
-    Other,
+#[allow(dead_code)]
+struct RawWorkspace {
+    id: String,
+    name: String,
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    parent_id: Option<String>,
+    has_focus: bool,
+    is_displayed: bool,
+    #[serde(default)]
+    tiling_direction: Option<String>,
+    children: Vec<Value>,
+    #[serde(default)]
+    child_focus_order: Vec<String>,
+    #[serde(default)]
+    x: i32,
+    #[serde(default)]
+    y: i32,
+    #[serde(default)]
+    width: u32,
+    #[serde(default)]
+    height: u32,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[serde(tag = "type")]
-enum RawWorkspaceChild {
-    #[serde(rename = "window")]
-    Window {
-        id: String,
-        #[serde(default)]
-        parent_id: Option<String>,
-        #[serde(rename = "hasFocus")]
-        has_focus: bool,
-        #[serde(default)]
-        tiling_size: Option<f64>,
-        width: u32,
-        height: u32,
-        x: i32,
-        y: i32,
-        state: RawWindowState,
-        #[serde(default)]
-        prev_state: Option<serde_json::Value>,
-        #[serde(rename = "displayState")]
-        display_state: String,
-        #[serde(default)]
-        border_delta: Option<serde_json::Value>,
-        #[serde(default)]
-        floating_placement: Option<serde_json::Value>,
-        #[serde(default)]
-        handle: Option<i64>,
-        title: String,
-        #[serde(default)]
-        class_name: Option<String>,
-        #[serde(rename = "processName")]
-        process_name: String,
-        #[serde(default)]
-        active_drag: Option<serde_json::Value>,
-    },
-    #[serde(other)]
-    Other,
+#[allow(dead_code)]
+struct RawSplit {
+    id: String,
+    #[serde(default)]
+    parent_id: Option<String>,
+    #[serde(default)]
+    tiling_direction: Option<String>,
+    #[serde(default)]
+    tiling_size: Option<f64>,
+    children: Vec<Value>,
+    #[serde(default)]
+    child_focus_order: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+struct RawWindow {
+    id: String,
+    #[serde(default)]
+    parent_id: Option<String>,
+    has_focus: bool,
+    #[serde(default)]
+    tiling_size: Option<f64>,
+    width: u32,
+    height: u32,
+    x: i32,
+    y: i32,
+    state: RawWindowState,
+    #[serde(default)]
+    prev_state: Option<serde_json::Value>,
+    display_state: String,
+    #[serde(default)]
+    border_delta: Option<serde_json::Value>,
+    #[serde(default)]
+    floating_placement: Option<serde_json::Value>,
+    #[serde(default)]
+    handle: Option<i64>,
+    title: String,
+    #[serde(default)]
+    class_name: Option<String>,
+    process_name: String,
+    #[serde(default)]
+    active_drag: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -130,34 +175,131 @@ struct RawWindowState {
     state_type: String,
 }
 
+/// Monitors parsed from a glazewm response, together with any non-fatal
+/// anomalies found along the way
+#[derive(Debug, Clone, Default)]
+pub struct ParsedMonitors {
+    pub monitors: Vec<Monitor>,
+    /// Monitors that failed to parse or validate. One that only failed
+    /// validation also appears in `monitors`, unvalidated, to keep its
+    /// workspaces and windows.
+    pub placeholders: Vec<Placeholder>,
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Windows parsed from a `query windows` response
+#[derive(Debug, Clone, Default)]
+pub struct ParsedWindows {
+    pub windows: Vec<Window>,
+    /// Windows that failed to parse
+    pub placeholders: Vec<Placeholder>,
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Result of converting a single node; the error becomes the placeholder's message
+type NodeResult<T> = Result<T, String>;
+
 /// Parser for converting glazewm JSON responses to domain models
 pub struct GlazewmParser;
 
 impl GlazewmParser {
     /// Parse monitors response from glazewm
-    pub fn parse_monitors(json: &Value) -> Result<Vec<Monitor>, CliError> {
+    ///
+    /// Unrecognized enum values are preserved as `Unknown` variants, and nodes
+    /// that fail to parse or validate are replaced by placeholders so that the
+    /// rest of the tree survives. Both are reported in `ParsedMonitors::warnings`.
+    pub fn parse_monitors(json: &Value) -> Result<ParsedMonitors, CliError> {
+        Self::check_success(json)?;
         let response: MonitorResponse = serde_json::from_value(json["data"].clone())?;
 
-        let mut monitors = Vec::new();
+        let mut parsed = ParsedMonitors::default();
+
+        for value in &response.monitors {
+            match Self::convert_monitor(value, &mut parsed.warnings) {
+                Ok((monitor, None)) => parsed.monitors.push(monitor),
+                Ok((fallback, Some(error))) => {
+                    let placeholder =
+                        Self::placeholder(NodeKind::Monitor, value, error, &mut parsed.warnings);
+                    parsed.placeholders.push(placeholder);
+                    if !fallback.workspaces().is_empty() {
+                        parsed.monitors.push(fallback);
+                    }
+                }
+                Err(error) => {
+                    let placeholder =
+                        Self::placeholder(NodeKind::Monitor, value, error, &mut parsed.warnings);
+                    parsed.placeholders.push(placeholder);
+                }
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// Turn a `success: false` response into the error glazewm reported
+    pub(crate) fn check_success(json: &Value) -> Result<(), CliError> {
+        if json.get("success").and_then(Value::as_bool) == Some(false) {
+            return Err(CliError::QueryFailed {
+                message: json["error"]
+                    .as_str()
+                    .unwrap_or("no error message")
+                    .to_string(),
+            });
+        }
+        Ok(())
+    }
 
-        for raw_monitor in response.monitors {
-            let monitor = Self::convert_monitor(raw_monitor)?;
-            monitors.push(monitor);
+    /// Parse the flat window list returned by `glazewm query windows`
+    ///
+    /// The monitors response already includes windows in workspace children;
+    /// this list is used to cross-check that tree.
+    pub fn parse_windows(json: &Value) -> Result<ParsedWindows, CliError> {
+        Self::check_success(json)?;
+        let response: WindowResponse = serde_json::from_value(json["data"].clone())?;
+
+        let mut parsed = ParsedWindows::default();
+
+        for value in &response.windows {
+            match Self::convert_window(value, &mut parsed.warnings) {
+                Ok(window) => parsed.windows.push(window),
+                Err(error) => {
+                    let placeholder =
+                        Self::placeholder(NodeKind::Window, value, error, &mut parsed.warnings);
+                    parsed.placeholders.push(placeholder);
+                }
+            }
         }
 
-        Ok(monitors)
+        Ok(parsed)
     }
 
-    /// Parse windows response from glazewm and group by workspace
-    /// Note: glazewm monitors response already includes windows in workspace children,
-    /// so this method is primarily for separate window querying if needed
-    pub fn parse_windows(_json: &Value, monitors: &[Monitor]) -> Result<Vec<Monitor>, CliError> {
-        // In glazewm, windows are already included in the monitor/workspace hierarchy
-        // So we just return the existing monitors for now
-        Ok(monitors.to_vec())
+    /// Build a placeholder for an invalid node and report it as a warning
+    fn placeholder(
+        kind: NodeKind,
+        value: &Value,
+        error: String,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Placeholder {
+        let id = value.get("id").and_then(Value::as_str).map(str::to_string);
+        let placeholder = Placeholder::new(kind, id, error, value.to_string());
+        warnings.push(ParseWarning::invalid_node(&placeholder));
+        placeholder
     }
 
-    fn convert_monitor(raw: RawMonitor) -> Result<Monitor, CliError> {
+    /// Check the `type` discriminator of a container node
+    fn is_node_type(value: &Value, node_type: &str) -> bool {
+        value.get("type").and_then(Value::as_str) == Some(node_type)
+    }
+
+    /// Convert a monitor along with its validation error, if any. A monitor
+    /// that fails validation is still built, unvalidated, so that its valid
+    /// workspaces and windows are not lost with it.
+    fn convert_monitor(
+        value: &Value,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> NodeResult<(Monitor, Option<String>)> {
+        let raw = RawMonitor::deserialize(value).map_err(|e| e.to_string())?;
+
         let geometry = Rectangle::new(
             Position::new(raw.x, raw.y),
             Size::new(raw.width, raw.height),
@@ -170,167 +312,252 @@ impl GlazewmParser {
         };
 
         let mut workspaces = Vec::new();
-        for child in raw.children {
-            if let RawMonitorChild::Workspace {
-                id,
-                name,
-                display_name: _,
-                parent_id: _,
-                has_focus,
-                is_displayed,
-                tiling_direction,
-                children,
-                child_focus_order: _,
-                x: _,
-                y: _,
-                width: _,
-                height: _,
-            } = child
-            {
-                let workspace = Self::convert_workspace_from_raw(
-                    id,
-                    name,
-                    has_focus,
-                    is_displayed,
-                    tiling_direction,
-                    children,
-                )?;
-                workspaces.push(workspace);
+        let mut placeholders = Vec::new();
+        for child in raw
+            .children
+            .iter()
+            .filter(|child| Self::is_node_type(child, "workspace"))
+        {
+            match Self::convert_workspace(child, warnings) {
+                Ok(workspace) => workspaces.push(workspace),
+                Err(error) => placeholders.push(Self::placeholder(
+                    NodeKind::Workspace,
+                    child,
+                    error,
+                    warnings,
+                )),
             }
         }
 
-        Monitor::try_new(
-            MonitorId::new(raw.id),
+        // Validate without the workspaces, so that a failure keeps them
+        let id = MonitorId::new(raw.id);
+        let invalid = Monitor::try_new(
+            id.clone(),
             geometry,
-            workspaces,
+            Vec::new(),
             focus_state,
             raw.dpi,
             raw.scale_factor,
         )
-        .map_err(|e| CliError::JsonParseError {
-            message: format!("Failed to create monitor: {}", e),
-        })
+        .err()
+        .map(|e| e.to_string());
+        let mut monitor = Monitor::new(
+            id,
+            geometry,
+            workspaces,
+            focus_state,
+            raw.dpi,
+            raw.scale_factor,
+        );
+
+        let mut device_info = monitor.device_info().clone();
+        if let Some(device_name) = raw.device_name {
+            device_info.device_name = device_name;
+        }
+        device_info.device_path = raw.device_path;
+        device_info.hardware_id = raw.hardware_id;
+        device_info.handle = raw.handle;
+        monitor.set_device_info(device_info);
+
+        if let Some(working_area) = raw.working_rect.as_ref().and_then(RawRect::to_rectangle) {
+            monitor.set_working_area(working_area);
+        }
+
+        monitor.set_focus_order(raw.child_focus_order);
+
+        for placeholder in placeholders {
+            monitor.add_placeholder(placeholder);
+        }
+
+        Ok((monitor, invalid))
     }
 
-    fn convert_workspace_from_raw(
-        id: String,
-        name: String,
-        has_focus: bool,
-        is_displayed: bool,
-        tiling_direction: String,
-        children: Vec<RawWorkspaceChild>,
-    ) -> Result<Workspace, CliError> {
-        let focus_state = if has_focus {
+    fn convert_workspace(value: &Value, warnings: &mut Vec<ParseWarning>) -> NodeResult<Workspace> {
+        let raw = RawWorkspace::deserialize(value).map_err(|e| e.to_string())?;
+
+        let focus_state = if raw.has_focus {
             FocusState::Focused
         } else {
             FocusState::Unfocused
         };
 
-        let display_state = if is_displayed {
+        let display_state = if raw.is_displayed {
             DisplayState::Shown
         } else {
             DisplayState::Hidden
         };
 
-        let workspace_tiling_direction = match tiling_direction.as_str() {
-            "horizontal" => TilingDirection::Horizontal,
-            "vertical" => TilingDirection::Vertical,
-            _ => TilingDirection::Horizontal, // Default fallback
-        };
+        let tiling_direction =
+            Self::convert_tiling_direction(&raw.id, raw.tiling_direction.as_deref(), warnings);
 
-        // Convert windows from children
+        // Windows nested in split containers are flattened into the workspace;
+        // the layout keeps the tree shape
         let mut windows = Vec::new();
-        for child in children {
-            if let RawWorkspaceChild::Window {
-                id: window_id,
-                parent_id: _,
-                has_focus: window_has_focus,
-                tiling_size: _,
-                width,
-                height,
-                x,
-                y,
-                state,
-                prev_state: _,
-                display_state: window_display_state,
-                border_delta: _,
-                floating_placement: _,
-                handle: _,
-                title,
-                class_name: _,
-                process_name,
-                active_drag: _,
-            } = child
-            {
-                let window = Self::convert_window_from_raw(
-                    window_id,
-                    window_has_focus,
-                    width,
-                    height,
-                    x,
-                    y,
-                    state,
-                    window_display_state,
-                    title,
-                    process_name,
-                )?;
-                windows.push(window);
-            }
-        }
+        let mut placeholders = Vec::new();
+        let layout =
+            Self::convert_children(&raw.children, &mut windows, &mut placeholders, warnings);
 
-        Ok(Workspace::new(
-            WorkspaceId::new(id),
-            name,
+        let mut workspace = Workspace::new(
+            WorkspaceId::new(raw.id),
+            raw.name,
             windows,
-            workspace_tiling_direction,
+            tiling_direction,
             focus_state,
             display_state,
-        ))
+        );
+
+        workspace.set_layout(layout);
+        workspace.set_focus_order(raw.child_focus_order);
+
+        if let Some(display_name) = raw.display_name.filter(|name| !name.is_empty()) {
+            workspace.set_display_name(display_name);
+        }
+
+        // Older glazewm versions do not report workspace geometry
+        if raw.width > 0 && raw.height > 0 {
+            workspace.set_geometry(Rectangle::from_coords(raw.x, raw.y, raw.width, raw.height));
+        }
+
+        for placeholder in placeholders {
+            workspace.add_placeholder(placeholder);
+        }
+
+        Ok(workspace)
     }
 
-    fn convert_window_from_raw(
-        id: String,
-        has_focus: bool,
-        width: u32,
-        height: u32,
-        x: i32,
-        y: i32,
-        state: RawWindowState,
-        display_state: String,
-        title: String,
-        process_name: String,
-    ) -> Result<Window, CliError> {
-        let geometry = Rectangle::new(Position::new(x, y), Size::new(width, height));
-
-        let window_state = match state.state_type.as_str() {
+    /// Convert the window and split container children of a workspace or split
+    ///
+    /// Valid windows are appended to `windows` and referenced from the
+    /// returned layout; invalid windows and splits become placeholders.
+    fn convert_children(
+        children: &[Value],
+        windows: &mut Vec<Window>,
+        placeholders: &mut Vec<Placeholder>,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Vec<LayoutNode> {
+        let mut layout = Vec::new();
+
+        for child in children {
+            if Self::is_node_type(child, "window") {
+                match Self::convert_window(child, warnings) {
+                    Ok(window) => {
+                        layout.push(LayoutNode::Window(window.id().clone()));
+                        windows.push(window);
+                    }
+                    Err(error) => placeholders.push(Self::placeholder(
+                        NodeKind::Window,
+                        child,
+                        error,
+                        warnings,
+                    )),
+                }
+            } else if Self::is_node_type(child, "split") {
+                match Self::convert_split(child, windows, placeholders, warnings) {
+                    Ok(split) => layout.push(LayoutNode::Split(split)),
+                    Err(error) => placeholders.push(Self::placeholder(
+                        NodeKind::Split,
+                        child,
+                        error,
+                        warnings,
+                    )),
+                }
+            }
+        }
+
+        layout
+    }
+
+    fn convert_split(
+        value: &Value,
+        windows: &mut Vec<Window>,
+        placeholders: &mut Vec<Placeholder>,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> NodeResult<SplitContainer> {
+        let raw = RawSplit::deserialize(value).map_err(|e| e.to_string())?;
+
+        let tiling_direction =
+            Self::convert_tiling_direction(&raw.id, raw.tiling_direction.as_deref(), warnings);
+        let children = Self::convert_children(&raw.children, windows, placeholders, warnings);
+
+        let mut split = SplitContainer::new(ContainerId::new(raw.id), tiling_direction, children);
+        split.set_focus_order(raw.child_focus_order);
+        if let Some(tiling_size) = raw.tiling_size {
+            split.set_tiling_size(tiling_size);
+        }
+
+        Ok(split)
+    }
+
+    fn convert_tiling_direction(
+        node_id: &str,
+        tiling_direction: Option<&str>,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> TilingDirection {
+        match tiling_direction {
+            None | Some("horizontal") => TilingDirection::Horizontal,
+            Some("vertical") => TilingDirection::Vertical,
+            Some(other) => {
+                warnings.push(ParseWarning::unknown_value(
+                    node_id,
+                    "tilingDirection",
+                    other,
+                ));
+                TilingDirection::Unknown(other.to_string())
+            }
+        }
+    }
+
+    fn convert_window(value: &Value, warnings: &mut Vec<ParseWarning>) -> NodeResult<Window> {
+        let raw = RawWindow::deserialize(value).map_err(|e| e.to_string())?;
+
+        let geometry = Rectangle::new(
+            Position::new(raw.x, raw.y),
+            Size::new(raw.width, raw.height),
+        );
+
+        let window_state = match raw.state.state_type.as_str() {
             "tiling" => WindowState::Tiling,
             "floating" => WindowState::Floating,
             "fullscreen" => WindowState::Fullscreen,
             "minimized" => WindowState::Minimized,
-            _ => WindowState::Tiling, // Default fallback
+            other => {
+                warnings.push(ParseWarning::unknown_value(&raw.id, "state.type", other));
+                WindowState::Unknown(other.to_string())
+            }
         };
 
-        let focus_state = if has_focus {
+        let focus_state = if raw.has_focus {
             FocusState::Focused
         } else {
             FocusState::Unfocused
         };
 
-        let window_display_state = match display_state.as_str() {
+        let display_state = match raw.display_state.as_str() {
             "shown" => DisplayState::Shown,
             "hidden" => DisplayState::Hidden,
-            _ => DisplayState::Shown, // Default fallback
+            "hiding" => DisplayState::Hiding,
+            other => {
+                warnings.push(ParseWarning::unknown_value(&raw.id, "displayState", other));
+                DisplayState::Unknown(other.to_string())
+            }
         };
 
-        Ok(Window::new(
-            WindowId::new(id),
-            title,
-            process_name,
+        let mut window = Window::new(
+            WindowId::new(raw.id),
+            raw.title,
+            raw.process_name,
             geometry,
             window_state,
             focus_state,
-            window_display_state,
-        ))
+            display_state,
+        );
+        if let Some(handle) = raw.handle {
+            window.set_handle(handle);
+        }
+        if let Some(tiling_size) = raw.tiling_size {
+            window.set_tiling_size(tiling_size);
+        }
+        Ok(window)
     }
 }
 
@@ -367,7 +594,7 @@ mod tests {
             }
         });
 
-        let monitors = GlazewmParser::parse_monitors(&json).unwrap();
+        let monitors = GlazewmParser::parse_monitors(&json).unwrap().monitors;
 
         assert_eq!(monitors.len(), 1);
         assert!(monitors[0].is_focused());
@@ -413,7 +640,7 @@ mod tests {
             }
         });
 
-        let monitors = GlazewmParser::parse_monitors(&json).unwrap();
+        let monitors = GlazewmParser::parse_monitors(&json).unwrap().monitors;
 
         assert_eq!(monitors.len(), 1);
         assert_eq!(monitors[0].workspace_count(), 1);
@@ -429,4 +656,273 @@ mod tests {
         let result = GlazewmParser::parse_monitors(&invalid_json);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn should_report_unsuccessful_responses() {
+        let failed = serde_json::json!({
+            "success": false,
+            "data": null,
+            "error": "IPC server is not running"
+        });
+
+        match GlazewmParser::parse_windows(&failed) {
+            Err(CliError::QueryFailed { message }) => {
+                assert_eq!(message, "IPC server is not running")
+            }
+            other => panic!("unexpected result: {:?}", other.map(|p| p.windows.len())),
+        }
+    }
+
+    fn monitors_json_with_window(state: &str, display_state: &str) -> Value {
+        serde_json::json!({
+            "data": {
+                "monitors": [{
+                    "type": "monitor",
+                    "id": "monitor-1",
+                    "x": 0,
+                    "y": 0,
+                    "width": 1920,
+                    "height": 1080,
+                    "scaleFactor": 1.0,
+                    "dpi": 96,
+                    "hasFocus": true,
+                    "children": [{
+                        "type": "workspace",
+                        "id": "ws-1",
+                        "name": "Workspace 1",
+                        "hasFocus": true,
+                        "isDisplayed": true,
+                        "tilingDirection": "horizontal",
+                        "children": [{
+                            "type": "window",
+                            "id": "window-1",
+                            "hasFocus": true,
+                            "width": 800,
+                            "height": 600,
+                            "x": 100,
+                            "y": 100,
+                            "state": { "type": state },
+                            "displayState": display_state,
+                            "title": "VS Code",
+                            "processName": "Code"
+                        }]
+                    }]
+                }]
+            }
+        })
+    }
+
+    #[test]
+    fn should_map_hiding_display_state() {
+        let json = monitors_json_with_window("tiling", "hiding");
+
+        let parsed = GlazewmParser::parse_monitors(&json).unwrap();
+        let window = &parsed.monitors[0].workspaces()[0].windows()[0];
+
+        assert_eq!(window.display_state(), &DisplayState::Hiding);
+        assert!(parsed.warnings.is_empty());
+    }
+
+    #[test]
+    fn should_preserve_unknown_values_with_warnings() {
+        let json = monitors_json_with_window("snapped", "showing");
+
+        let parsed = GlazewmParser::parse_monitors(&json).unwrap();
+        let window = &parsed.monitors[0].workspaces()[0].windows()[0];
+
+        assert_eq!(window.state(), &WindowState::Unknown("snapped".to_string()));
+        assert_eq!(
+            window.display_state(),
+            &DisplayState::Unknown("showing".to_string())
+        );
+        assert_eq!(
+            parsed.warnings,
+            vec![
+                ParseWarning::unknown_value("window-1", "state.type", "snapped"),
+                ParseWarning::unknown_value("window-1", "displayState", "showing"),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_preserve_unknown_tiling_direction() {
+        let mut json = monitors_json_with_window("tiling", "shown");
+        json["data"]["monitors"][0]["children"][0]["tilingDirection"] =
+            serde_json::json!("diagonal");
+
+        let parsed = GlazewmParser::parse_monitors(&json).unwrap();
+
+        assert_eq!(
+            parsed.monitors[0].workspaces()[0].tiling_direction(),
+            &TilingDirection::Unknown("diagonal".to_string())
+        );
+        assert_eq!(parsed.warnings.len(), 1);
+        assert_eq!(parsed.warnings[0].node_id(), Some("ws-1"));
+    }
+
+    #[test]
+    fn should_keep_valid_monitors_when_one_fails_validation() {
+        let mut json = monitors_json_with_window("tiling", "shown");
+        let mut hotplugged = json["data"]["monitors"][0].clone();
+        hotplugged["id"] = serde_json::json!("monitor-2");
+        hotplugged["width"] = serde_json::json!(0);
+        hotplugged["height"] = serde_json::json!(0);
+        hotplugged["children"][0]["id"] = serde_json::json!("ws-2");
+        hotplugged["children"][0]["children"][0]["id"] = serde_json::json!("window-2");
+        json["data"]["monitors"]
+            .as_array_mut()
+            .unwrap()
+            .push(hotplugged);
+
+        let parsed = GlazewmParser::parse_monitors(&json).unwrap();
+
+        // The invalid monitor's workspace and window survive in a fallback
+        assert_eq!(parsed.monitors.len(), 2);
+        assert_eq!(parsed.monitors[0].total_window_count(), 1);
+        assert_eq!(parsed.monitors[1].id().as_str(), "monitor-2");
+        assert_eq!(parsed.monitors[1].workspaces().len(), 1);
+        assert_eq!(parsed.monitors[1].total_window_count(), 1);
+        assert_eq!(parsed.placeholders.len(), 1);
+        assert_eq!(parsed.placeholders[0].kind(), NodeKind::Monitor);
+        assert_eq!(parsed.placeholders[0].id(), Some("monitor-2"));
+        assert!(parsed.placeholders[0].raw_json().contains("monitor-2"));
+        assert_eq!(parsed.warnings.len(), 1);
+        assert_eq!(parsed.warnings[0].node_id(), Some("monitor-2"));
+    }
+
+    #[test]
+    fn should_replace_malformed_window_with_placeholder() {
+        let mut json = monitors_json_with_window("tiling", "shown");
+        let windows = json["data"]["monitors"][0]["children"][0]["children"]
+            .as_array_mut()
+            .unwrap();
+        let mut broken = windows[0].clone();
+        broken["id"] = serde_json::json!("window-2");
+        broken.as_object_mut().unwrap().remove("title");
+        windows.push(broken);
+
+        let parsed = GlazewmParser::parse_monitors(&json).unwrap();
+        let workspace = &parsed.monitors[0].workspaces()[0];
+
+        assert_eq!(workspace.window_count(), 1);
+        assert_eq!(workspace.placeholders().len(), 1);
+        assert_eq!(workspace.placeholders()[0].id(), Some("window-2"));
+        assert!(workspace.placeholders()[0].error().contains("title"));
+        assert_eq!(parsed.monitors[0].total_placeholder_count(), 1);
+        assert_eq!(parsed.warnings.len(), 1);
+    }
+
+    #[test]
+    fn should_replace_malformed_workspace_with_placeholder() {
+        let mut json = monitors_json_with_window("tiling", "shown");
+        json["data"]["monitors"][0]["children"]
+            .as_array_mut()
+            .unwrap()
+            .push(serde_json::json!({ "type": "workspace", "id": "ws-broken" }));
+
+        let parsed = GlazewmParser::parse_monitors(&json).unwrap();
+
+        assert_eq!(parsed.monitors[0].workspace_count(), 1);
+        assert_eq!(parsed.monitors[0].placeholders().len(), 1);
+        assert_eq!(
+            parsed.monitors[0].placeholders()[0].kind(),
+            NodeKind::Workspace
+        );
+    }
+
+    #[test]
+    fn should_parse_flat_window_list() {
+        let monitors_json = monitors_json_with_window("floating", "shown");
+        let window = monitors_json["data"]["monitors"][0]["children"][0]["children"][0].clone();
+        let json = serde_json::json!({
+            "data": { "windows": [window, { "type": "window", "id": "window-2" }] }
+        });
+
+        let parsed = GlazewmParser::parse_windows(&json).unwrap();
+
+        assert_eq!(parsed.windows.len(), 1);
+        assert_eq!(parsed.windows[0].state(), &WindowState::Floating);
+        assert_eq!(parsed.placeholders.len(), 1);
+        assert_eq!(parsed.placeholders[0].id(), Some("window-2"));
+        assert_eq!(parsed.warnings.len(), 1);
+    }
+
+    #[test]
+    fn should_carry_monitor_and_workspace_metadata() {
+        let mut json = monitors_json_with_window("tiling", "shown");
+        let monitor = &mut json["data"]["monitors"][0];
+        monitor["deviceName"] = serde_json::json!("\\\\.\\DISPLAY1");
+        monitor["devicePath"] = serde_json::json!("\\\\?\\DISPLAY#DEL4321");
+        monitor["hardwareId"] = serde_json::json!("DEL4321");
+        monitor["handle"] = serde_json::json!(65537);
+        monitor["workingRect"] =
+            serde_json::json!({ "left": 0, "top": 0, "right": 1920, "bottom": 1040 });
+        let workspace = &mut monitor["children"][0];
+        workspace["displayName"] = serde_json::json!("Code");
+        workspace["x"] = serde_json::json!(0);
+        workspace["y"] = serde_json::json!(0);
+        workspace["width"] = serde_json::json!(1920);
+        workspace["height"] = serde_json::json!(1040);
+        workspace["children"][0]["handle"] = serde_json::json!(131074);
+
+        let parsed = GlazewmParser::parse_monitors(&json).unwrap();
+        let monitor = &parsed.monitors[0];
+        let workspace = &monitor.workspaces()[0];
+
+        assert_eq!(monitor.device_name(), "\\\\.\\DISPLAY1");
+        assert_eq!(monitor.hardware_id(), Some("DEL4321"));
+        assert_eq!(monitor.device_info().handle, Some(65537));
+        assert_eq!(
+            monitor.working_area(),
+            Some(&Rectangle::from_coords(0, 0, 1920, 1040))
+        );
+        assert_eq!(monitor.reserved_insets().unwrap().bottom, 40);
+        assert_eq!(workspace.label(), "Code");
+        assert_eq!(
+            workspace.geometry(),
+            Some(&Rectangle::from_coords(0, 0, 1920, 1040))
+        );
+        assert_eq!(workspace.windows()[0].handle(), Some(131074));
+    }
+
+    #[test]
+    fn should_parse_split_containers_and_focus_order() {
+        let mut json = monitors_json_with_window("tiling", "shown");
+        let monitor = &mut json["data"]["monitors"][0];
+        monitor["childFocusOrder"] = serde_json::json!(["ws-1"]);
+
+        let workspace = &mut monitor["children"][0];
+        let first = workspace["children"][0].clone();
+        let mut second = first.clone();
+        second["id"] = serde_json::json!("window-2");
+        second["hasFocus"] = serde_json::json!(false);
+        workspace["children"] = serde_json::json!([{
+            "type": "split",
+            "id": "split-1",
+            "tilingDirection": "vertical",
+            "children": [first, second],
+            "childFocusOrder": ["window-2", "window-1"]
+        }]);
+        workspace["childFocusOrder"] = serde_json::json!(["split-1"]);
+
+        let parsed = GlazewmParser::parse_monitors(&json).unwrap();
+        let monitor = &parsed.monitors[0];
+        let workspace = &monitor.workspaces()[0];
+
+        assert!(parsed.warnings.is_empty());
+        assert_eq!(monitor.focus_order(), ["ws-1".to_string()]);
+        assert_eq!(workspace.window_count(), 2);
+        assert_eq!(workspace.focus_order(), ["split-1".to_string()]);
+
+        let ordered: Vec<&str> = workspace
+            .windows_by_focus_order()
+            .iter()
+            .map(|w| w.id().as_str())
+            .collect();
+        assert_eq!(ordered, vec!["window-2", "window-1"]);
+
+        let path = workspace.container_path(&WindowId::new("window-1".to_string()));
+        assert_eq!(path.len(), 1);
+        assert_eq!(path[0].tiling_direction(), &TilingDirection::Vertical);
+    }
 }
@@ -0,0 +1,478 @@
+// Redaction
+// Rewrites window titles and process names in glazewm responses before they
+// are parsed, so the TUI, dumps, recordings, the HTTP API and bug reports
+// can be shared without leaking email subjects, document names or URLs
+
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+use std::path::Path;
+use std::sync::Mutex;
+
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use thiserror::Error;
+
+/// Errors raised while loading redaction rules
+#[derive(Error, Debug)]
+pub enum RedactionError {
+    #[error("cannot read redaction rules {path}: {message}")]
+    Io { path: String, message: String },
+
+    #[error("invalid redaction rules: {0}")]
+    Format(#[from] serde_json::Error),
+
+    #[error("rule {index}: invalid pattern: {message}")]
+    Pattern { index: usize, message: String },
+}
+
+/// How titles and process names are rewritten
+#[derive(Debug, Clone, Default)]
+pub enum RedactionMode {
+    /// Leave responses untouched
+    #[default]
+    Off,
+    /// `title-1a2b3c4d` and `process-5e6f7a8b`, keyed per session
+    Hash,
+    /// `Code #1`, `Code #2`: numbered per process in order of appearance
+    Process,
+    /// Regex replacements from a rules file
+    Rules(Vec<RedactionRule>),
+}
+
+/// Window property a rule applies to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactedField {
+    #[default]
+    Title,
+    Process,
+}
+
+/// One regex replacement, loaded from JSON
+///
+/// ```json
+/// {
+///   "rules": [
+///     { "pattern": "[\\w.+-]+@[\\w-]+\\.[\\w.]+", "replace": "<email>" },
+///     { "pattern": "https?://\\S+", "replace": "<url>" },
+///     { "pattern": "^.* - (Outlook)$", "replace": "$1 message" },
+///     { "field": "process", "pattern": "^internal-.*", "replace": "internal-tool" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RedactionRule {
+    pub field: RedactedField,
+    pub pattern: Regex,
+    /// Replacement for every match; `$1` inserts a capture group
+    pub replace: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    rules: Vec<RuleSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    #[serde(default)]
+    field: RedactedField,
+    pattern: String,
+    replace: String,
+}
+
+impl RedactionMode {
+    /// Parse a rules file's JSON text
+    pub fn rules_from_json(text: &str) -> Result<Self, RedactionError> {
+        let file: RulesFile = serde_json::from_str(text)?;
+        let rules = file
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(index, spec)| {
+                let pattern = Regex::new(&spec.pattern).map_err(|e| RedactionError::Pattern {
+                    index,
+                    message: e.to_string(),
+                })?;
+                Ok(RedactionRule {
+                    field: spec.field,
+                    pattern,
+                    replace: spec.replace,
+                })
+            })
+            .collect::<Result<_, RedactionError>>()?;
+        Ok(RedactionMode::Rules(rules))
+    }
+
+    /// Load a rules file
+    pub fn rules_from_file(path: &Path) -> Result<Self, RedactionError> {
+        let text = std::fs::read_to_string(path).map_err(|e| RedactionError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Self::rules_from_json(&text)
+    }
+
+    /// Short name for logs and report summaries
+    pub fn name(&self) -> &'static str {
+        match self {
+            RedactionMode::Off => "off",
+            RedactionMode::Hash => "hash",
+            RedactionMode::Process => "process",
+            RedactionMode::Rules(_) => "rules",
+        }
+    }
+}
+
+/// Titles and process names whose pseudonyms are remembered; a window that
+/// has been gone for longer is numbered or hashed again when it returns
+const MAX_PSEUDONYMS: usize = 4096;
+
+/// Pseudonyms of the most recently used values, dropping the least
+/// recently used one beyond `MAX_PSEUDONYMS`
+#[derive(Debug)]
+struct RecentPseudonyms<K> {
+    entries: HashMap<K, (String, u64)>,
+    /// Keys by the tick they were last used at, oldest first
+    by_use: BTreeMap<u64, K>,
+    tick: u64,
+}
+
+impl<K> Default for RecentPseudonyms<K> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            by_use: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> RecentPseudonyms<K> {
+    fn get(&mut self, key: &K) -> Option<String> {
+        self.tick += 1;
+        let (pseudonym, used) = self.entries.get_mut(key)?;
+        self.by_use.remove(used);
+        *used = self.tick;
+        self.by_use.insert(self.tick, key.clone());
+        Some(pseudonym.clone())
+    }
+
+    fn insert(&mut self, key: K, pseudonym: String) {
+        self.tick += 1;
+        if let Some((_, used)) = self.entries.insert(key.clone(), (pseudonym, self.tick)) {
+            self.by_use.remove(&used);
+        }
+        self.by_use.insert(self.tick, key);
+        while self.entries.len() > MAX_PSEUDONYMS {
+            let Some((_, oldest)) = self.by_use.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&K, &String)> {
+        self.entries
+            .iter()
+            .map(|(key, (pseudonym, _))| (key, pseudonym))
+    }
+}
+
+/// Pseudonyms handed out recently; the same input gets the same one as long
+/// as it keeps appearing
+#[derive(Debug, Default)]
+struct Pseudonyms {
+    titles: RecentPseudonyms<(String, String)>,
+    processes: RecentPseudonyms<String>,
+    /// Titles numbered so far per process, for `RedactionMode::Process`.
+    /// Never forgotten, so an evicted title cannot free up its number.
+    per_process: HashMap<String, usize>,
+}
+
+/// Applies a redaction mode to glazewm responses with pseudonyms that stay
+/// the same while a window is around, so diffs and recordings still line up
+#[derive(Debug)]
+pub struct Redactor {
+    mode: RedactionMode,
+    /// Random per session, so hashes cannot be matched against guesses
+    /// from another run
+    keys: RandomState,
+    pseudonyms: Mutex<Pseudonyms>,
+}
+
+impl Redactor {
+    pub fn new(mode: RedactionMode) -> Self {
+        Self {
+            mode,
+            keys: RandomState::new(),
+            pseudonyms: Mutex::new(Pseudonyms::default()),
+        }
+    }
+
+    pub fn mode(&self) -> &RedactionMode {
+        &self.mode
+    }
+
+    pub fn is_active(&self) -> bool {
+        !matches!(self.mode, RedactionMode::Off)
+    }
+
+    /// Rewrite every window in a glazewm response (`query monitors`,
+    /// `query windows`, ...), wherever it is nested
+    pub fn redact_document(&self, document: &mut Value) {
+        if !self.is_active() {
+            return;
+        }
+        match document {
+            Value::Object(map) => {
+                let process = map.get("processName").and_then(Value::as_str);
+                let title = map.get("title").and_then(Value::as_str);
+                if let (Some(process), Some(title)) = (process, title) {
+                    let (process, title) = self.redact_window(process, title);
+                    map.insert("processName".to_string(), Value::String(process));
+                    map.insert("title".to_string(), Value::String(title));
+                }
+                map.values_mut()
+                    .for_each(|value| self.redact_document(value));
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_document(item)),
+            _ => {}
+        }
+    }
+
+    /// Pseudonyms for a window's process name and title
+    pub fn redact_window(&self, process: &str, title: &str) -> (String, String) {
+        let mut pseudonyms = self
+            .pseudonyms
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let new_title = if title.is_empty() {
+            String::new()
+        } else {
+            let key = (process.to_string(), title.to_string());
+            match pseudonyms.titles.get(&key) {
+                Some(pseudonym) => pseudonym,
+                None => {
+                    let pseudonym = match &self.mode {
+                        RedactionMode::Off => title.to_string(),
+                        RedactionMode::Hash => self.hash("title", title),
+                        RedactionMode::Process => {
+                            let count = pseudonyms
+                                .per_process
+                                .entry(process.to_string())
+                                .or_default();
+                            *count += 1;
+                            format!("{} #{}", process, count)
+                        }
+                        RedactionMode::Rules(rules) => apply(rules, RedactedField::Title, title),
+                    };
+                    pseudonyms.titles.insert(key, pseudonym.clone());
+                    pseudonym
+                }
+            }
+        };
+
+        let new_process = match pseudonyms.processes.get(&process.to_string()) {
+            Some(pseudonym) => pseudonym,
+            None => {
+                let pseudonym = match &self.mode {
+                    RedactionMode::Hash if !process.is_empty() => self.hash("process", process),
+                    RedactionMode::Rules(rules) => apply(rules, RedactedField::Process, process),
+                    _ => process.to_string(),
+                };
+                pseudonyms
+                    .processes
+                    .insert(process.to_string(), pseudonym.clone());
+                pseudonym
+            }
+        };
+
+        (new_process, new_title)
+    }
+
+    /// Replace every title and process name seen so far in free text such
+    /// as log lines. Values shorter than four characters are left alone,
+    /// since replacing them everywhere would mangle unrelated text.
+    pub fn scrub_text(&self, text: &str) -> String {
+        if !self.is_active() {
+            return text.to_string();
+        }
+        let pseudonyms = self
+            .pseudonyms
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut replacements: Vec<(&str, &str)> = pseudonyms
+            .titles
+            .iter()
+            .map(|((_, title), pseudonym)| (title.as_str(), pseudonym.as_str()))
+            .chain(
+                pseudonyms
+                    .processes
+                    .iter()
+                    .map(|(process, pseudonym)| (process.as_str(), pseudonym.as_str())),
+            )
+            .filter(|(original, pseudonym)| original.chars().count() >= 4 && original != pseudonym)
+            .collect();
+        // Longest first, so a title containing another is replaced whole
+        replacements.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(b.0)));
+
+        replacements
+            .into_iter()
+            .fold(text.to_string(), |text, (original, pseudonym)| {
+                text.replace(original, pseudonym)
+            })
+    }
+
+    fn hash(&self, prefix: &str, value: &str) -> String {
+        format!("{}-{:08x}", prefix, self.keys.hash_one(value) >> 32)
+    }
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new(RedactionMode::Off)
+    }
+}
+
+fn apply(rules: &[RedactionRule], field: RedactedField, value: &str) -> String {
+    rules
+        .iter()
+        .filter(|rule| rule.field == field)
+        .fold(value.to_string(), |value, rule| {
+            rule.pattern
+                .replace_all(&value, rule.replace.as_str())
+                .into_owned()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Simulator;
+
+    fn titles(document: &Value) -> Vec<String> {
+        document["data"]["windows"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|window| {
+                format!(
+                    "{}: {}",
+                    window["processName"].as_str().unwrap(),
+                    window["title"].as_str().unwrap()
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_number_titles_per_process_consistently() {
+        let mut simulator = Simulator::demo();
+        simulator.execute("shell-exec Code second.rs").unwrap();
+        let redactor = Redactor::new(RedactionMode::Process);
+
+        let mut windows = simulator.windows_json();
+        redactor.redact_document(&mut windows);
+        let redacted = titles(&windows);
+        assert!(redacted.contains(&"Code: Code #1".to_string()));
+        assert!(redacted.contains(&"Code: Code #2".to_string()));
+        assert!(redacted.contains(&"firefox: firefox #1".to_string()));
+
+        // The tree and later responses get the same pseudonyms
+        let mut monitors = simulator.monitors_json();
+        redactor.redact_document(&mut monitors);
+        assert!(!monitors.to_string().contains("Firefox - Documentation"));
+        let mut again = simulator.windows_json();
+        redactor.redact_document(&mut again);
+        assert_eq!(titles(&again), redacted);
+    }
+
+    #[test]
+    fn should_hash_titles_and_processes() {
+        let redactor = Redactor::new(RedactionMode::Hash);
+
+        let (process, title) = redactor.redact_window("outlook", "Quarterly numbers - Outlook");
+
+        assert!(process.starts_with("process-"));
+        assert!(title.starts_with("title-"));
+        assert_eq!(title.len(), "title-".len() + 8);
+        assert_eq!(
+            redactor.redact_window("outlook", "Quarterly numbers - Outlook"),
+            (process.clone(), title.clone())
+        );
+        assert_eq!(
+            redactor.scrub_text("Focused outlook: Quarterly numbers - Outlook"),
+            format!("Focused {}: {}", process, title)
+        );
+    }
+
+    #[test]
+    fn should_apply_regex_rules() {
+        let mode = RedactionMode::rules_from_json(
+            r#"{ "rules": [
+                { "pattern": "[\\w.]+@[\\w.]+", "replace": "<email>" },
+                { "pattern": "^.* - (Outlook)$", "replace": "$1 message" },
+                { "field": "process", "pattern": "^secret-", "replace": "" }
+            ] }"#,
+        )
+        .unwrap();
+        let redactor = Redactor::new(mode);
+
+        assert_eq!(
+            redactor.redact_window("secret-tool", "Mail to a.b@example.com"),
+            ("tool".to_string(), "Mail to <email>".to_string())
+        );
+        assert_eq!(
+            redactor.redact_window("outlook", "Budget - Outlook").1,
+            "Outlook message"
+        );
+
+        assert!(matches!(
+            RedactionMode::rules_from_json(r#"{ "rules": [{ "pattern": "(", "replace": "" }] }"#),
+            Err(RedactionError::Pattern { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn should_forget_the_least_recently_used_pseudonyms() {
+        let redactor = Redactor::new(RedactionMode::Process);
+        let (_, kept) = redactor.redact_window("Code", "kept.rs");
+
+        for index in 0..3 * MAX_PSEUDONYMS {
+            redactor.redact_window(&format!("app{}", index % 3), &format!("file {}", index));
+            // Still in use, so never the least recently used
+            assert_eq!(redactor.redact_window("Code", "kept.rs").1, kept);
+        }
+
+        let pseudonyms = redactor.pseudonyms.lock().unwrap();
+        assert_eq!(pseudonyms.titles.entries.len(), MAX_PSEUDONYMS);
+        assert_eq!(pseudonyms.titles.by_use.len(), MAX_PSEUDONYMS);
+        assert_eq!(pseudonyms.processes.entries.len(), 4);
+        assert_eq!(pseudonyms.per_process["app0"], MAX_PSEUDONYMS);
+        drop(pseudonyms);
+
+        // A forgotten title gets a new number rather than a reused one
+        assert_eq!(
+            redactor.redact_window("app0", "file 0").1,
+            format!("app0 #{}", MAX_PSEUDONYMS + 1)
+        );
+    }
+
+    #[test]
+    fn should_leave_documents_alone_when_off() {
+        let redactor = Redactor::default();
+        let mut windows = Simulator::demo().windows_json();
+        let original = windows.clone();
+
+        redactor.redact_document(&mut windows);
+
+        assert_eq!(windows, original);
+        assert_eq!(redactor.scrub_text("Firefox"), "Firefox");
+    }
+}
@@ -0,0 +1,256 @@
+// Remote agent protocol
+// Newline-delimited JSON messages from an agent that polls glazewm, and the
+// client a viewer on another machine uses to read them
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+
+use crate::cli::client::GlazewmClient;
+use crate::cli::errors::CliError;
+use crate::utils::json_diff::{self, JsonPatch};
+
+/// Delay before the viewer reconnects to an agent that went away
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Result of one glazewm query on the agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryOutcome {
+    Ok(Value),
+    Error(String),
+}
+
+impl QueryOutcome {
+    pub fn from_result(result: Result<Value, CliError>) -> Self {
+        match result {
+            Ok(value) => QueryOutcome::Ok(value),
+            Err(e) => QueryOutcome::Error(e.to_string()),
+        }
+    }
+
+    fn to_result(&self) -> Result<Value, CliError> {
+        match self {
+            QueryOutcome::Ok(value) => Ok(value.clone()),
+            QueryOutcome::Error(message) => Err(CliError::AgentQueryFailed {
+                message: message.clone(),
+            }),
+        }
+    }
+}
+
+/// How one query's outcome changed since the previous message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutcomeChange {
+    Unchanged,
+    Full(QueryOutcome),
+    Patch(Vec<JsonPatch>),
+}
+
+impl OutcomeChange {
+    /// Smallest change from `old` (what the viewer has) to `new`
+    pub fn between(old: Option<&QueryOutcome>, new: &QueryOutcome) -> Self {
+        match (old, new) {
+            (Some(old), new) if old == new => OutcomeChange::Unchanged,
+            (Some(QueryOutcome::Ok(old)), QueryOutcome::Ok(new)) => {
+                OutcomeChange::Patch(json_diff::diff(old, new))
+            }
+            _ => OutcomeChange::Full(new.clone()),
+        }
+    }
+
+    /// Update the viewer's copy of an outcome
+    pub fn apply(self, current: &mut Option<QueryOutcome>) -> Result<(), String> {
+        match (self, current.as_mut()) {
+            (OutcomeChange::Unchanged, _) => Ok(()),
+            (OutcomeChange::Full(outcome), _) => {
+                *current = Some(outcome);
+                Ok(())
+            }
+            (OutcomeChange::Patch(patches), Some(QueryOutcome::Ok(value))) => {
+                json_diff::apply(value, &patches)
+            }
+            (OutcomeChange::Patch(_), _) => Err("patch without a previous snapshot".to_string()),
+        }
+    }
+}
+
+/// One line sent by the agent: the first one a connection receives carries
+/// full snapshots, later ones carry diffs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentMessage {
+    /// Poll number on the agent; gaps mean the viewer skipped states
+    pub seq: u64,
+    pub monitors: OutcomeChange,
+    pub windows: OutcomeChange,
+}
+
+/// What the viewer knows about the agent's latest poll
+#[derive(Debug, Clone, Default)]
+struct RemoteState {
+    monitors: Option<QueryOutcome>,
+    windows: Option<QueryOutcome>,
+    /// Set while the connection is down; the last data is kept
+    connection_error: Option<String>,
+}
+
+/// Client that reads glazewm state from a remote `agent` (`--connect`)
+pub struct RemoteGlazewmClient {
+    state: watch::Receiver<RemoteState>,
+}
+
+impl RemoteGlazewmClient {
+    /// Connect to an agent in the background, reconnecting when the
+    /// connection drops; must be called inside a tokio runtime
+    pub fn connect(address: String) -> Self {
+        let (sender, receiver) = watch::channel(RemoteState::default());
+
+        tokio::spawn(async move {
+            loop {
+                let error = match TcpStream::connect(&address).await {
+                    Ok(stream) => {
+                        tracing::info!("Connected to agent at {}", address);
+                        follow(stream, &sender).await
+                    }
+                    Err(e) => format!("cannot connect to agent at {}: {}", address, e),
+                };
+                tracing::warn!("{}", error);
+
+                // Keep the last data; the next connection starts with a snapshot
+                sender.send_modify(|state| state.connection_error = Some(error));
+                if sender.is_closed() {
+                    // The client was dropped
+                    return;
+                }
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        });
+
+        Self { state: receiver }
+    }
+
+    async fn latest(
+        &self,
+        select: impl Fn(&RemoteState) -> &Option<QueryOutcome>,
+    ) -> Result<Value, CliError> {
+        let mut receiver = self.state.clone();
+        let state = receiver
+            .wait_for(|state| select(state).is_some() || state.connection_error.is_some())
+            .await
+            .map_err(|_| CliError::IoError {
+                message: "agent connection task stopped".to_string(),
+            })?;
+
+        if let Some(error) = &state.connection_error {
+            return Err(CliError::IoError {
+                message: error.clone(),
+            });
+        }
+        select(&state)
+            .as_ref()
+            .expect("waited for an outcome")
+            .to_result()
+    }
+}
+
+/// Apply messages from one connection until it ends; returns why it ended
+async fn follow(stream: TcpStream, sender: &watch::Sender<RemoteState>) -> String {
+    let mut lines = BufReader::new(stream).lines();
+    let mut current = RemoteState::default();
+
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => return "agent closed the connection".to_string(),
+            Err(e) => return format!("agent connection failed: {}", e),
+        };
+
+        let message: AgentMessage = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(e) => return format!("invalid message from agent: {}", e),
+        };
+        tracing::debug!("Agent update {}", message.seq);
+
+        if let Err(e) = message
+            .monitors
+            .apply(&mut current.monitors)
+            .and_then(|()| message.windows.apply(&mut current.windows))
+        {
+            return format!("cannot apply agent update {}: {}", message.seq, e);
+        }
+        sender.send_replace(current.clone());
+    }
+}
+
+#[async_trait]
+impl GlazewmClient for RemoteGlazewmClient {
+    async fn query_monitors(&self) -> Result<Value, CliError> {
+        self.latest(|state| &state.monitors).await
+    }
+
+    async fn query_windows(&self) -> Result<Value, CliError> {
+        self.latest(|state| &state.windows).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_send_patches_between_successful_outcomes() {
+        let old = QueryOutcome::Ok(json!({ "data": { "focus": "a" } }));
+        let new = QueryOutcome::Ok(json!({ "data": { "focus": "b" } }));
+
+        assert_eq!(
+            OutcomeChange::between(Some(&old), &old),
+            OutcomeChange::Unchanged
+        );
+        let change = OutcomeChange::between(Some(&old), &new);
+        assert!(matches!(&change, OutcomeChange::Patch(patches) if patches.len() == 1));
+
+        let mut current = Some(old.clone());
+        change.apply(&mut current).unwrap();
+        assert_eq!(current, Some(new.clone()));
+
+        let failed = QueryOutcome::Error("timed out".to_string());
+        assert_eq!(
+            OutcomeChange::between(Some(&new), &failed),
+            OutcomeChange::Full(failed.clone())
+        );
+        assert_eq!(OutcomeChange::between(None, &new), OutcomeChange::Full(new));
+    }
+
+    #[test]
+    fn should_reject_patch_without_snapshot() {
+        let mut current = None;
+        assert!(OutcomeChange::Patch(Vec::new())
+            .apply(&mut current)
+            .is_err());
+    }
+
+    #[test]
+    fn should_serialize_messages_as_single_lines() {
+        let message = AgentMessage {
+            seq: 3,
+            monitors: OutcomeChange::Unchanged,
+            windows: OutcomeChange::Full(QueryOutcome::Error("boom".to_string())),
+        };
+
+        let line = serde_json::to_string(&message).unwrap();
+        assert_eq!(
+            line,
+            r#"{"seq":3,"monitors":"unchanged","windows":{"full":{"error":"boom"}}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<AgentMessage>(&line).unwrap(),
+            message
+        );
+    }
+}
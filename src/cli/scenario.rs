@@ -0,0 +1,404 @@
+// Demo scenarios
+// Timeline scripts that drive the glazewm simulator over time, so bug
+// reports and presentations can be replayed from a shareable file
+
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::cli::simulator::{MonitorSetup, Simulator, SimulatorError};
+use crate::domain::Rectangle;
+
+/// Errors raised while loading or validating a scenario
+#[derive(Error, Debug)]
+pub enum ScenarioError {
+    #[error("cannot read scenario {path}: {message}")]
+    Io { path: String, message: String },
+
+    #[error("invalid scenario: {0}")]
+    Format(#[from] serde_json::Error),
+
+    #[error("scenario needs at least one monitor")]
+    NoMonitors,
+
+    #[error("setup command '{command}' failed: {source}")]
+    Setup {
+        command: String,
+        source: SimulatorError,
+    },
+
+    #[error("step {step} (at {at_ms} ms) failed: {source}")]
+    Step {
+        step: usize,
+        at_ms: u64,
+        source: SimulatorError,
+    },
+}
+
+/// A timeline script for the simulator, loaded from JSON
+///
+/// ```json
+/// {
+///   "name": "Focus jumps after unplugging a monitor",
+///   "monitors": [
+///     { "rect": { "x": 0, "y": 0, "width": 1920, "height": 1080 }, "workspace": "1" }
+///   ],
+///   "setup": ["shell-exec code Editor"],
+///   "timeline": [
+///     { "at_ms": 1000, "commands": ["shell-exec notepad"] },
+///     { "at_ms": 2000, "add_monitor": { "rect": { "x": 1920, "y": 0, "width": 1920, "height": 1080 }, "workspace": "2" } },
+///     { "at_ms": 4000, "remove_monitor": 1 }
+///   ],
+///   "loop_after_ms": 6000
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scenario {
+    /// Shown in logs when the scenario starts
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Monitors present at the start, the first one focused
+    pub monitors: Vec<ScenarioMonitor>,
+    /// Commands applied before the timeline starts
+    #[serde(default)]
+    pub setup: Vec<String>,
+    /// Steps in the order they happen
+    #[serde(default)]
+    pub timeline: Vec<ScenarioStep>,
+    /// Restart from the initial state once this much time has passed
+    #[serde(default)]
+    pub loop_after_ms: Option<u64>,
+}
+
+/// A monitor plugged in at the start or by a step
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScenarioMonitor {
+    pub rect: ScenarioRect,
+    /// Defaults to `rect` (no taskbar)
+    #[serde(default)]
+    pub working_rect: Option<ScenarioRect>,
+    /// Name of the workspace displayed on the new monitor
+    pub workspace: String,
+    #[serde(default)]
+    pub device_name: Option<String>,
+    #[serde(default)]
+    pub hardware_id: Option<String>,
+    #[serde(default = "default_dpi")]
+    pub dpi: u32,
+    #[serde(default = "default_scale_factor")]
+    pub scale_factor: f64,
+}
+
+/// Rectangle in the flat form glazewm uses
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ScenarioRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Something that happens at a point on the timeline
+///
+/// Within a step, a monitor is added first, then one is removed, then the
+/// commands run.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScenarioStep {
+    /// Time since the scenario started
+    pub at_ms: u64,
+    #[serde(default)]
+    pub add_monitor: Option<ScenarioMonitor>,
+    /// Index of the monitor to unplug
+    #[serde(default)]
+    pub remove_monitor: Option<usize>,
+    #[serde(default)]
+    pub commands: Vec<String>,
+}
+
+fn step_error(index: usize, step: &ScenarioStep, source: SimulatorError) -> ScenarioError {
+    ScenarioError::Step {
+        step: index,
+        at_ms: step.at_ms,
+        source,
+    }
+}
+
+fn default_dpi() -> u32 {
+    96
+}
+
+fn default_scale_factor() -> f64 {
+    1.0
+}
+
+impl ScenarioRect {
+    fn to_rectangle(self) -> Rectangle {
+        Rectangle::from_coords(self.x, self.y, self.width, self.height)
+    }
+}
+
+impl ScenarioMonitor {
+    fn to_setup(&self, index: usize) -> MonitorSetup {
+        let number = index + 1;
+        MonitorSetup {
+            device_name: self
+                .device_name
+                .clone()
+                .unwrap_or_else(|| format!("\\\\.\\DISPLAY{}", number)),
+            hardware_id: self
+                .hardware_id
+                .clone()
+                .unwrap_or_else(|| format!("SCEN{:04}", number)),
+            rect: self.rect.to_rectangle(),
+            working_rect: self.working_rect.unwrap_or(self.rect).to_rectangle(),
+            dpi: self.dpi,
+            scale_factor: self.scale_factor,
+        }
+    }
+}
+
+impl Scenario {
+    /// Parse a scenario from JSON text
+    pub fn from_json(text: &str) -> Result<Self, ScenarioError> {
+        Ok(serde_json::from_str(text)?)
+    }
+
+    /// Load a scenario file
+    pub fn load(path: &Path) -> Result<Self, ScenarioError> {
+        let text = std::fs::read_to_string(path).map_err(|e| ScenarioError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Self::from_json(&text)
+    }
+
+    /// Build a player, checking that every setup command and step applies
+    pub fn into_player(mut self) -> Result<ScenarioPlayer, ScenarioError> {
+        // Steps are applied in time order whatever order the file lists them in
+        self.timeline.sort_by_key(|step| step.at_ms);
+
+        let mut dry_run = ScenarioPlayer::start(&self)?;
+        for (index, step) in self.timeline.iter().enumerate() {
+            dry_run
+                .apply_step(step)
+                .map_err(|source| step_error(index, step, source))?;
+        }
+
+        Ok(ScenarioPlayer {
+            simulator: ScenarioPlayer::start(&self)?.simulator,
+            scenario: Some(self),
+            next_step: 0,
+            cycle_start: Duration::ZERO,
+        })
+    }
+
+    fn initial_simulator(&self) -> Result<Simulator, ScenarioError> {
+        if self.monitors.is_empty() {
+            return Err(ScenarioError::NoMonitors);
+        }
+
+        let mut simulator = Simulator::new();
+        for (index, monitor) in self.monitors.iter().enumerate() {
+            simulator.add_monitor(monitor.to_setup(index), &monitor.workspace);
+        }
+        for command in &self.setup {
+            simulator
+                .execute(command)
+                .map_err(|source| ScenarioError::Setup {
+                    command: command.clone(),
+                    source,
+                })?;
+        }
+        Ok(simulator)
+    }
+}
+
+/// Plays a scenario's timeline against a simulator as time passes
+#[derive(Debug, Clone)]
+pub struct ScenarioPlayer {
+    simulator: Simulator,
+    /// None for a plain simulator without a timeline
+    scenario: Option<Scenario>,
+    next_step: usize,
+    cycle_start: Duration,
+}
+
+impl ScenarioPlayer {
+    /// Wrap a simulator that has no timeline
+    pub fn from_simulator(simulator: Simulator) -> Self {
+        Self {
+            simulator,
+            scenario: None,
+            next_step: 0,
+            cycle_start: Duration::ZERO,
+        }
+    }
+
+    fn start(scenario: &Scenario) -> Result<Self, ScenarioError> {
+        Ok(Self::from_simulator(scenario.initial_simulator()?))
+    }
+
+    pub fn simulator(&self) -> &Simulator {
+        &self.simulator
+    }
+
+    pub fn simulator_mut(&mut self) -> &mut Simulator {
+        &mut self.simulator
+    }
+
+    /// True once every step has been applied and the scenario does not loop
+    pub fn is_finished(&self) -> bool {
+        self.scenario.as_ref().is_none_or(|scenario| {
+            scenario.loop_after_ms.is_none() && self.next_step >= scenario.timeline.len()
+        })
+    }
+
+    /// Apply every step due at `elapsed` (time since the scenario started)
+    pub fn advance(&mut self, elapsed: Duration) {
+        let Some(scenario) = self.scenario.take() else {
+            return;
+        };
+
+        // Steps were validated when the player was built
+        if let Err(e) = self.advance_to(&scenario, elapsed) {
+            tracing::warn!("Scenario step failed: {}", e);
+        }
+        self.scenario = Some(scenario);
+    }
+
+    fn advance_to(&mut self, scenario: &Scenario, elapsed: Duration) -> Result<(), ScenarioError> {
+        if let Some(loop_after) = scenario.loop_after_ms.map(Duration::from_millis) {
+            if !loop_after.is_zero() && elapsed.saturating_sub(self.cycle_start) >= loop_after {
+                // Restart at the beginning of the cycle `elapsed` falls in
+                let elapsed_ms = elapsed.as_millis() as u64;
+                let loop_ms = loop_after.as_millis() as u64;
+                self.simulator = scenario.initial_simulator()?;
+                self.next_step = 0;
+                self.cycle_start = Duration::from_millis(elapsed_ms - elapsed_ms % loop_ms);
+            }
+        }
+
+        let cycle_elapsed = elapsed.saturating_sub(self.cycle_start);
+        while let Some(step) = scenario.timeline.get(self.next_step) {
+            if Duration::from_millis(step.at_ms) > cycle_elapsed {
+                break;
+            }
+            self.apply_step(step)
+                .map_err(|source| step_error(self.next_step, step, source))?;
+            self.next_step += 1;
+        }
+        Ok(())
+    }
+
+    fn apply_step(&mut self, step: &ScenarioStep) -> Result<(), SimulatorError> {
+        if let Some(monitor) = &step.add_monitor {
+            let index = self.simulator.monitor_count();
+            self.simulator
+                .add_monitor(monitor.to_setup(index), &monitor.workspace);
+        }
+        if let Some(index) = step.remove_monitor {
+            self.simulator.remove_monitor(index)?;
+        }
+        for command in &step.commands {
+            self.simulator.execute(command)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::GlazewmParser;
+
+    const SCENARIO: &str = r#"{
+        "name": "hotplug",
+        "monitors": [
+            { "rect": { "x": 0, "y": 0, "width": 1000, "height": 800 }, "workspace": "1" }
+        ],
+        "setup": ["shell-exec code Editor"],
+        "timeline": [
+            { "at_ms": 2000, "remove_monitor": 1, "commands": ["focus --workspace 2"] },
+            { "at_ms": 1000, "add_monitor": { "rect": { "x": 1000, "y": 0, "width": 1000, "height": 800 }, "workspace": "2" }, "commands": ["focus --monitor 1", "shell-exec notepad"] }
+        ]
+    }"#;
+
+    fn window_count(player: &ScenarioPlayer) -> usize {
+        player.simulator().windows_json()["data"]["windows"]
+            .as_array()
+            .unwrap()
+            .len()
+    }
+
+    fn monitor_count(player: &ScenarioPlayer) -> usize {
+        GlazewmParser::parse_monitors(&player.simulator().monitors_json())
+            .unwrap()
+            .monitors
+            .len()
+    }
+
+    #[test]
+    fn should_apply_steps_as_time_passes() {
+        let mut player = Scenario::from_json(SCENARIO)
+            .unwrap()
+            .into_player()
+            .unwrap();
+        assert_eq!((monitor_count(&player), window_count(&player)), (1, 1));
+
+        player.advance(Duration::from_millis(999));
+        assert_eq!(monitor_count(&player), 1);
+
+        player.advance(Duration::from_millis(1500));
+        assert_eq!((monitor_count(&player), window_count(&player)), (2, 2));
+        assert!(!player.is_finished());
+
+        player.advance(Duration::from_millis(2000));
+        assert_eq!((monitor_count(&player), window_count(&player)), (1, 2));
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn should_restart_when_looping() {
+        let mut scenario = Scenario::from_json(SCENARIO).unwrap();
+        scenario.loop_after_ms = Some(3000);
+        let mut player = scenario.into_player().unwrap();
+
+        player.advance(Duration::from_millis(2500));
+        assert_eq!(window_count(&player), 2);
+
+        player.advance(Duration::from_millis(3500));
+        assert_eq!((monitor_count(&player), window_count(&player)), (1, 1));
+        assert!(!player.is_finished());
+
+        player.advance(Duration::from_millis(4000));
+        assert_eq!(monitor_count(&player), 2);
+    }
+
+    #[test]
+    fn should_reject_steps_that_cannot_apply() {
+        let scenario = Scenario::from_json(
+            r#"{
+                "monitors": [{ "rect": { "x": 0, "y": 0, "width": 100, "height": 100 }, "workspace": "1" }],
+                "timeline": [{ "at_ms": 10, "commands": ["close"] }]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            scenario.into_player(),
+            Err(ScenarioError::Step {
+                step: 0,
+                at_ms: 10,
+                ..
+            })
+        ));
+        assert!(matches!(
+            Scenario::from_json(r#"{ "monitors": [] }"#)
+                .unwrap()
+                .into_player(),
+            Err(ScenarioError::NoMonitors)
+        ));
+    }
+}
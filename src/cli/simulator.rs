@@ -0,0 +1,1782 @@
+// glazewm simulator
+// In-process model of glazewm's container tree that applies window manager
+// commands and serializes its state to the JSON the glazewm CLI returns
+
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::domain::Rectangle;
+
+/// Smallest share of its parent a tiling container can be resized to
+const MIN_TILING_SIZE: f64 = 0.05;
+
+/// Distance a floating window moves per `move --direction` command
+const FLOATING_MOVE_STEP: i32 = 50;
+
+/// Smallest width or height a floating window can be resized to
+const MIN_FLOATING_EXTENT: u32 = 50;
+
+/// Errors raised when a command cannot be applied
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SimulatorError {
+    #[error("unknown command: {0}")]
+    UnknownCommand(String),
+
+    #[error("invalid arguments for '{command}': {reason}")]
+    InvalidArguments { command: String, reason: String },
+
+    #[error("no window is focused")]
+    NoFocusedWindow,
+
+    #[error("no monitor at index {0}")]
+    NoSuchMonitor(usize),
+
+    #[error("no container with id {0}")]
+    NoSuchContainer(String),
+}
+
+/// Direction argument of `focus` and `move`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "left" => Some(Direction::Left),
+            "right" => Some(Direction::Right),
+            "up" => Some(Direction::Up),
+            "down" => Some(Direction::Down),
+            _ => None,
+        }
+    }
+
+    fn axis(self) -> Axis {
+        match self {
+            Direction::Left | Direction::Right => Axis::Horizontal,
+            Direction::Up | Direction::Down => Axis::Vertical,
+        }
+    }
+
+    /// Whether the direction points towards higher coordinates
+    fn is_forward(self) -> bool {
+        matches!(self, Direction::Right | Direction::Down)
+    }
+}
+
+/// Dimension argument of `resize`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Width,
+    Height,
+}
+
+/// Signed resize amount, either relative to the parent or in pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Amount {
+    Percent(f64),
+    Pixels(i32),
+}
+
+impl Amount {
+    fn parse(value: &str) -> Option<Self> {
+        if let Some(percent) = value.strip_suffix('%') {
+            percent.parse().ok().map(Amount::Percent)
+        } else {
+            value
+                .strip_suffix("px")
+                .unwrap_or(value)
+                .parse()
+                .ok()
+                .map(Amount::Pixels)
+        }
+    }
+
+    /// Change in pixels for a container `length` pixels long
+    fn pixels_of(self, length: u32) -> i32 {
+        match self {
+            Amount::Percent(percent) => (length as f64 * percent / 100.0).round() as i32,
+            Amount::Pixels(pixels) => pixels,
+        }
+    }
+}
+
+/// A window manager command, written in glazewm's command syntax
+#[derive(Debug, Clone, PartialEq)]
+pub enum WmCommand {
+    /// `focus --direction <left|right|up|down>`
+    FocusDirection(Direction),
+    /// `focus --workspace <name>`, creating the workspace if needed
+    FocusWorkspace(String),
+    /// `focus --monitor <index>`
+    FocusMonitor(usize),
+    /// `focus --container-id <id>`
+    FocusContainer(String),
+    /// `move --direction <left|right|up|down>`
+    MoveDirection(Direction),
+    /// `move --workspace <name>`
+    MoveToWorkspace(String),
+    /// `resize --width <amount>` or `resize --height <amount>`
+    Resize {
+        dimension: Dimension,
+        amount: Amount,
+    },
+    /// `toggle-floating`
+    ToggleFloating,
+    /// `toggle-tiling-direction`
+    ToggleTilingDirection,
+    /// `shell-exec <program> [title]`; spawns a window for the program
+    ShellExec {
+        program: String,
+        title: Option<String>,
+    },
+    /// `close`
+    Close,
+}
+
+impl WmCommand {
+    /// Parse a command such as `focus --direction left`
+    pub fn parse(input: &str) -> Result<Self, SimulatorError> {
+        let command = input.trim();
+        let tokens: Vec<&str> = command.split_whitespace().collect();
+        let invalid = |reason: &str| SimulatorError::InvalidArguments {
+            command: command.to_string(),
+            reason: reason.to_string(),
+        };
+        let direction = |value: &str| {
+            Direction::parse(value).ok_or_else(|| invalid("expected left, right, up or down"))
+        };
+        let amount = |value: &str| {
+            Amount::parse(value).ok_or_else(|| invalid("expected e.g. +10% or -50px"))
+        };
+
+        match tokens.as_slice() {
+            ["focus", "--direction", value] => direction(value).map(WmCommand::FocusDirection),
+            ["focus", "--workspace", name] => Ok(WmCommand::FocusWorkspace(name.to_string())),
+            ["focus", "--monitor", index] => index
+                .parse()
+                .map(WmCommand::FocusMonitor)
+                .map_err(|_| invalid("expected a monitor index")),
+            ["focus", "--container-id", id] => Ok(WmCommand::FocusContainer(id.to_string())),
+            ["move", "--direction", value] => direction(value).map(WmCommand::MoveDirection),
+            ["move", "--workspace", name] => Ok(WmCommand::MoveToWorkspace(name.to_string())),
+            ["resize", "--width", value] => Ok(WmCommand::Resize {
+                dimension: Dimension::Width,
+                amount: amount(value)?,
+            }),
+            ["resize", "--height", value] => Ok(WmCommand::Resize {
+                dimension: Dimension::Height,
+                amount: amount(value)?,
+            }),
+            ["toggle-floating"] => Ok(WmCommand::ToggleFloating),
+            ["toggle-tiling-direction"] => Ok(WmCommand::ToggleTilingDirection),
+            ["close"] => Ok(WmCommand::Close),
+            ["shell-exec", program, title @ ..] => Ok(WmCommand::ShellExec {
+                program: program.to_string(),
+                title: (!title.is_empty()).then(|| title.join(" ")),
+            }),
+            ["focus" | "move" | "resize" | "shell-exec", ..] => {
+                Err(invalid("unsupported arguments"))
+            }
+            _ => Err(SimulatorError::UnknownCommand(command.to_string())),
+        }
+    }
+}
+
+/// Physical description of a simulated monitor
+#[derive(Debug, Clone)]
+pub struct MonitorSetup {
+    pub device_name: String,
+    pub hardware_id: String,
+    pub rect: Rectangle,
+    /// Area left for windows once the taskbar and other app bars are reserved
+    pub working_rect: Rectangle,
+    pub dpi: u32,
+    pub scale_factor: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+impl Axis {
+    fn flipped(self) -> Self {
+        match self {
+            Axis::Horizontal => Axis::Vertical,
+            Axis::Vertical => Axis::Horizontal,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Axis::Horizontal => "horizontal",
+            Axis::Vertical => "vertical",
+        }
+    }
+
+    fn length_of(self, rect: &Rectangle) -> u32 {
+        match self {
+            Axis::Horizontal => rect.size.width,
+            Axis::Vertical => rect.size.height,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SimWindow {
+    id: String,
+    handle: i64,
+    title: String,
+    process_name: String,
+    floating: bool,
+    tiling_size: f64,
+    floating_rect: Rectangle,
+}
+
+/// Workspace root or split container
+#[derive(Debug, Clone)]
+struct SimContainer {
+    id: String,
+    tiling_direction: Axis,
+    tiling_size: f64,
+    children: Vec<SimNode>,
+    child_focus_order: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+enum SimNode {
+    Window(SimWindow),
+    Split(SimContainer),
+}
+
+impl SimNode {
+    fn id(&self) -> &str {
+        match self {
+            SimNode::Window(window) => &window.id,
+            SimNode::Split(split) => &split.id,
+        }
+    }
+
+    fn is_tiling(&self) -> bool {
+        match self {
+            SimNode::Window(window) => !window.floating,
+            SimNode::Split(_) => true,
+        }
+    }
+
+    fn tiling_size(&self) -> f64 {
+        match self {
+            SimNode::Window(window) => window.tiling_size,
+            SimNode::Split(split) => split.tiling_size,
+        }
+    }
+
+    fn set_tiling_size(&mut self, size: f64) {
+        match self {
+            SimNode::Window(window) => window.tiling_size = size,
+            SimNode::Split(split) => split.tiling_size = size,
+        }
+    }
+}
+
+impl SimContainer {
+    fn new(id: String, tiling_direction: Axis) -> Self {
+        Self {
+            id,
+            tiling_direction,
+            tiling_size: 1.0,
+            children: Vec::new(),
+            child_focus_order: Vec::new(),
+        }
+    }
+
+    /// Split container reached by following child indices
+    fn descend(&self, path: &[usize]) -> Option<&SimContainer> {
+        match path.split_first() {
+            None => Some(self),
+            Some((index, rest)) => match self.children.get(*index)? {
+                SimNode::Split(split) => split.descend(rest),
+                SimNode::Window(_) => None,
+            },
+        }
+    }
+
+    fn descend_mut(&mut self, path: &[usize]) -> Option<&mut SimContainer> {
+        match path.split_first() {
+            None => Some(self),
+            Some((index, rest)) => match self.children.get_mut(*index)? {
+                SimNode::Split(split) => split.descend_mut(rest),
+                SimNode::Window(_) => None,
+            },
+        }
+    }
+
+    /// Child indices leading to the node with `id`
+    fn find_path(&self, id: &str) -> Option<Vec<usize>> {
+        for (index, child) in self.children.iter().enumerate() {
+            if child.id() == id {
+                return Some(vec![index]);
+            }
+            if let SimNode::Split(split) = child {
+                if let Some(mut path) = split.find_path(id) {
+                    path.insert(0, index);
+                    return Some(path);
+                }
+            }
+        }
+        None
+    }
+
+    fn tiling_count(&self) -> usize {
+        self.children
+            .iter()
+            .filter(|child| child.is_tiling())
+            .count()
+    }
+
+    /// Descendant windows, most recently focused first
+    fn windows_by_focus<'a>(&'a self, out: &mut Vec<&'a SimWindow>) {
+        let mut children: Vec<&SimNode> = self.children.iter().collect();
+        children.sort_by_key(|child| {
+            self.child_focus_order
+                .iter()
+                .position(|id| id == child.id())
+                .unwrap_or(usize::MAX)
+        });
+
+        for child in children {
+            match child {
+                SimNode::Window(window) => out.push(window),
+                SimNode::Split(split) => split.windows_by_focus(out),
+            }
+        }
+    }
+
+    /// Insert a child, giving a tiling child an equal share of the container
+    fn insert(&mut self, index: usize, mut node: SimNode) {
+        if node.is_tiling() {
+            let siblings = self.tiling_count();
+            node.set_tiling_size(if siblings == 0 {
+                1.0
+            } else {
+                1.0 / siblings as f64
+            });
+        }
+        self.child_focus_order.push(node.id().to_string());
+        self.children.insert(index.min(self.children.len()), node);
+        self.normalize_sizes();
+    }
+
+    fn remove(&mut self, index: usize) -> SimNode {
+        let node = self.children.remove(index);
+        self.child_focus_order.retain(|id| id != node.id());
+        node
+    }
+
+    /// Scale tiling sizes so siblings always add up to one
+    fn normalize_sizes(&mut self) {
+        let total: f64 = self
+            .children
+            .iter()
+            .filter(|child| child.is_tiling())
+            .map(SimNode::tiling_size)
+            .sum();
+
+        for child in self.children.iter_mut() {
+            if child.is_tiling() && total > 0.0 {
+                child.set_tiling_size(child.tiling_size() / total);
+            }
+            if let SimNode::Split(split) = child {
+                split.normalize_sizes();
+            }
+        }
+    }
+
+    /// Compute the rectangle of this container and every descendant
+    fn layout(&self, rect: Rectangle, out: &mut HashMap<String, Rectangle>) {
+        out.insert(self.id.clone(), rect);
+
+        let tiling: Vec<&SimNode> = self.children.iter().filter(|c| c.is_tiling()).collect();
+        let length = self.tiling_direction.length_of(&rect);
+        let mut offset = 0u32;
+
+        for (index, child) in tiling.iter().enumerate() {
+            let extent = if index == tiling.len() - 1 {
+                length.saturating_sub(offset)
+            } else {
+                (length as f64 * child.tiling_size()).round() as u32
+            };
+            let child_rect = match self.tiling_direction {
+                Axis::Horizontal => Rectangle::from_coords(
+                    rect.position.x + offset as i32,
+                    rect.position.y,
+                    extent,
+                    rect.size.height,
+                ),
+                Axis::Vertical => Rectangle::from_coords(
+                    rect.position.x,
+                    rect.position.y + offset as i32,
+                    rect.size.width,
+                    extent,
+                ),
+            };
+            offset += extent;
+
+            match child {
+                SimNode::Split(split) => split.layout(child_rect, out),
+                SimNode::Window(window) => {
+                    out.insert(window.id.clone(), child_rect);
+                }
+            }
+        }
+
+        for child in &self.children {
+            if let SimNode::Window(window) = child {
+                if window.floating {
+                    out.insert(window.id.clone(), window.floating_rect);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SimWorkspace {
+    name: String,
+    root: SimContainer,
+}
+
+#[derive(Debug, Clone)]
+struct SimMonitor {
+    id: String,
+    handle: i64,
+    setup: MonitorSetup,
+    workspaces: Vec<SimWorkspace>,
+    child_focus_order: Vec<String>,
+    displayed: String,
+}
+
+/// Position of a container in the tree: a workspace, then child indices
+#[derive(Debug, Clone, PartialEq)]
+struct Location {
+    monitor: usize,
+    workspace: usize,
+    path: Vec<usize>,
+}
+
+impl Location {
+    fn parent(&self) -> Option<Location> {
+        let (_, path) = self.path.split_last()?;
+        Some(Location {
+            path: path.to_vec(),
+            ..self.clone()
+        })
+    }
+}
+
+/// Stateful model of glazewm that applies commands with glazewm-like semantics
+#[derive(Debug, Clone)]
+pub struct Simulator {
+    monitors: Vec<SimMonitor>,
+    focused: String,
+    next_id: u64,
+}
+
+impl Simulator {
+    /// Create an empty simulator; add monitors before running commands
+    pub fn new() -> Self {
+        Self {
+            monitors: Vec::new(),
+            focused: String::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Two monitors with a handful of windows, used by `--demo`
+    pub fn demo() -> Self {
+        let mut simulator = Self::new();
+
+        simulator.add_monitor(
+            MonitorSetup {
+                device_name: "\\\\.\\DISPLAY1".to_string(),
+                hardware_id: "DEMO0001".to_string(),
+                rect: Rectangle::from_coords(0, 0, 1920, 1080),
+                working_rect: Rectangle::from_coords(0, 0, 1920, 1040),
+                dpi: 96,
+                scale_factor: 1.0,
+            },
+            "Development",
+        );
+        simulator.add_monitor(
+            MonitorSetup {
+                device_name: "\\\\.\\DISPLAY2".to_string(),
+                hardware_id: "DEMO0002".to_string(),
+                rect: Rectangle::from_coords(1920, 0, 2560, 1440),
+                working_rect: Rectangle::from_coords(1920, 0, 2560, 1400),
+                dpi: 120,
+                scale_factor: 1.25,
+            },
+            "Communication",
+        );
+
+        let commands = [
+            "shell-exec Code Visual Studio Code - glazewm-debug",
+            "shell-exec firefox Firefox - Documentation",
+            "focus --workspace Testing",
+            "shell-exec wezterm-gui Terminal - cargo test",
+            "focus --workspace Development",
+            "focus --monitor 1",
+            "shell-exec Discord Discord - #general",
+            "shell-exec Spotify Spotify - Currently Playing",
+            "toggle-floating",
+            "focus --monitor 0",
+        ];
+        for command in commands {
+            simulator.execute(command).expect("demo commands are valid");
+        }
+
+        simulator
+    }
+
+    /// Add a monitor with a single displayed workspace; returns the monitor id
+    ///
+    /// The first monitor added receives focus.
+    pub fn add_monitor(&mut self, setup: MonitorSetup, workspace_name: &str) -> String {
+        let id = self.next_id("monitor");
+        self.monitors.push(SimMonitor {
+            id: id.clone(),
+            handle: 0x10000 + self.monitors.len() as i64,
+            setup,
+            workspaces: Vec::new(),
+            child_focus_order: Vec::new(),
+            displayed: String::new(),
+        });
+
+        let monitor = self.monitors.len() - 1;
+        let workspace = self.create_workspace(monitor, workspace_name);
+        self.monitors[monitor].displayed = workspace.clone();
+        if self.focused.is_empty() {
+            self.set_focus(&workspace);
+        }
+
+        id
+    }
+
+    /// Unplug a monitor; its non-empty workspaces move to the first remaining
+    /// monitor, hidden behind the workspace displayed there
+    pub fn remove_monitor(&mut self, index: usize) -> Result<(), SimulatorError> {
+        if index >= self.monitors.len() {
+            return Err(SimulatorError::NoSuchMonitor(index));
+        }
+        if self.monitors.len() == 1 {
+            return Err(SimulatorError::InvalidArguments {
+                command: "remove-monitor".to_string(),
+                reason: "cannot remove the last monitor".to_string(),
+            });
+        }
+
+        let had_focus = self
+            .locate(&self.focused)
+            .is_some_and(|location| location.monitor == index);
+        let removed = self.monitors.remove(index);
+
+        let target = &mut self.monitors[0];
+        for workspace in removed.workspaces {
+            if !workspace.root.children.is_empty() {
+                target.child_focus_order.push(workspace.root.id.clone());
+                target.workspaces.push(workspace);
+            }
+        }
+
+        if had_focus {
+            let displayed = self.monitors[0].displayed.clone();
+            if let Some(location) = self.locate(&displayed) {
+                let focus = self.workspace_focus_target(&location);
+                self.set_focus(&focus);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn monitor_count(&self) -> usize {
+        self.monitors.len()
+    }
+
+    /// Id of the focused window, or of the focused workspace when it is empty
+    pub fn focused_id(&self) -> &str {
+        &self.focused
+    }
+
+    /// Parse and apply a command such as `move --direction left`
+    pub fn execute(&mut self, command: &str) -> Result<(), SimulatorError> {
+        let command = WmCommand::parse(command)?;
+        self.apply(command)
+    }
+
+    /// Apply a parsed command
+    pub fn apply(&mut self, command: WmCommand) -> Result<(), SimulatorError> {
+        match command {
+            WmCommand::FocusDirection(direction) => self.focus_direction(direction),
+            WmCommand::FocusWorkspace(name) => {
+                let location = match self.find_workspace(&name) {
+                    Some(location) => location,
+                    None => {
+                        let monitor = self.focused_location()?.monitor;
+                        let id = self.create_workspace(monitor, &name);
+                        self.locate(&id)
+                            .ok_or(SimulatorError::NoSuchContainer(id))?
+                    }
+                };
+                let target = self.workspace_focus_target(&location);
+                self.set_focus(&target);
+                Ok(())
+            }
+            WmCommand::FocusMonitor(index) => {
+                let monitor = self
+                    .monitors
+                    .get(index)
+                    .ok_or(SimulatorError::NoSuchMonitor(index))?;
+                let displayed = monitor.displayed.clone();
+                let location = self
+                    .locate(&displayed)
+                    .ok_or(SimulatorError::NoSuchContainer(displayed))?;
+                let target = self.workspace_focus_target(&location);
+                self.set_focus(&target);
+                Ok(())
+            }
+            WmCommand::FocusContainer(id) => {
+                self.locate(&id)
+                    .ok_or_else(|| SimulatorError::NoSuchContainer(id.clone()))?;
+                self.set_focus(&id);
+                Ok(())
+            }
+            WmCommand::MoveDirection(direction) => self.move_direction(direction),
+            WmCommand::MoveToWorkspace(name) => self.move_to_workspace(&name),
+            WmCommand::Resize { dimension, amount } => self.resize(dimension, amount),
+            WmCommand::ToggleFloating => self.toggle_floating(),
+            WmCommand::ToggleTilingDirection => {
+                let location = self.focused_location()?;
+                let container = match self.window(&location) {
+                    Some(window) if !window.floating => location.parent(),
+                    _ => None,
+                }
+                .unwrap_or(Location {
+                    path: Vec::new(),
+                    ..location
+                });
+                if let Some(container) = self.container_mut(&container) {
+                    container.tiling_direction = container.tiling_direction.flipped();
+                }
+                Ok(())
+            }
+            WmCommand::ShellExec { program, title } => self.shell_exec(program, title),
+            WmCommand::Close => {
+                let location = self.focused_window_location()?;
+                let (target, _) = self.detach_window(&location);
+                self.set_focus(&target);
+                Ok(())
+            }
+        }
+    }
+
+    /// Serialize the tree as a `glazewm query monitors` response
+    pub fn monitors_json(&self) -> Value {
+        let chain = self.focus_chain();
+        let layout = self.layout();
+        let monitors: Vec<Value> = self
+            .monitors
+            .iter()
+            .map(|monitor| self.monitor_json(monitor, &chain, &layout))
+            .collect();
+
+        json!({
+            "success": true,
+            "data": { "monitors": monitors },
+            "error": null
+        })
+    }
+
+    /// Serialize every window as a `glazewm query windows` response
+    pub fn windows_json(&self) -> Value {
+        let mut windows = Vec::new();
+        collect_windows(&self.monitors_json()["data"], &mut windows);
+
+        json!({
+            "success": true,
+            "data": { "windows": windows },
+            "error": null
+        })
+    }
+
+    fn next_id(&mut self, kind: &str) -> String {
+        let id = format!("sim-{}-{}", kind, self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    /// Create a hidden workspace on a monitor; returns its id
+    fn create_workspace(&mut self, monitor: usize, name: &str) -> String {
+        let id = self.next_id("workspace");
+        let monitor = &mut self.monitors[monitor];
+        let rect = &monitor.setup.working_rect;
+        // glazewm tiles along the longer side of the monitor by default
+        let direction = if rect.size.width >= rect.size.height {
+            Axis::Horizontal
+        } else {
+            Axis::Vertical
+        };
+
+        monitor.workspaces.push(SimWorkspace {
+            name: name.to_string(),
+            root: SimContainer::new(id.clone(), direction),
+        });
+        monitor.child_focus_order.push(id.clone());
+        id
+    }
+
+    fn locate(&self, id: &str) -> Option<Location> {
+        for (monitor_index, monitor) in self.monitors.iter().enumerate() {
+            for (workspace_index, workspace) in monitor.workspaces.iter().enumerate() {
+                let path = if workspace.root.id == id {
+                    Some(Vec::new())
+                } else {
+                    workspace.root.find_path(id)
+                };
+                if let Some(path) = path {
+                    return Some(Location {
+                        monitor: monitor_index,
+                        workspace: workspace_index,
+                        path,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn find_workspace(&self, name: &str) -> Option<Location> {
+        self.monitors
+            .iter()
+            .flat_map(|monitor| &monitor.workspaces)
+            .find(|workspace| workspace.name == name)
+            .and_then(|workspace| self.locate(&workspace.root.id))
+    }
+
+    fn focused_location(&self) -> Result<Location, SimulatorError> {
+        self.locate(&self.focused)
+            .ok_or(SimulatorError::NoSuchMonitor(0))
+    }
+
+    fn focused_window_location(&self) -> Result<Location, SimulatorError> {
+        let location = self.focused_location()?;
+        if self.window(&location).is_some() {
+            Ok(location)
+        } else {
+            Err(SimulatorError::NoFocusedWindow)
+        }
+    }
+
+    fn workspace(&self, location: &Location) -> &SimWorkspace {
+        &self.monitors[location.monitor].workspaces[location.workspace]
+    }
+
+    fn container(&self, location: &Location) -> Option<&SimContainer> {
+        self.workspace(location).root.descend(&location.path)
+    }
+
+    fn container_mut(&mut self, location: &Location) -> Option<&mut SimContainer> {
+        self.monitors[location.monitor].workspaces[location.workspace]
+            .root
+            .descend_mut(&location.path)
+    }
+
+    fn window(&self, location: &Location) -> Option<&SimWindow> {
+        let parent = self.container(&location.parent()?)?;
+        match parent.children.get(*location.path.last()?)? {
+            SimNode::Window(window) => Some(window),
+            SimNode::Split(_) => None,
+        }
+    }
+
+    fn window_mut(&mut self, location: &Location) -> Option<&mut SimWindow> {
+        let index = *location.path.last()?;
+        let parent = self.container_mut(&location.parent()?)?;
+        match parent.children.get_mut(index)? {
+            SimNode::Window(window) => Some(window),
+            SimNode::Split(_) => None,
+        }
+    }
+
+    /// Window glazewm focuses when switching to a workspace, or the
+    /// workspace itself when it is empty
+    fn workspace_focus_target(&self, location: &Location) -> String {
+        let root = &self.workspace(location).root;
+        let mut windows = Vec::new();
+        root.windows_by_focus(&mut windows);
+        windows
+            .first()
+            .map(|window| window.id.clone())
+            .unwrap_or_else(|| root.id.clone())
+    }
+
+    /// Focus a container and bring it to the front of every focus order on
+    /// the way up, displaying its workspace
+    fn set_focus(&mut self, id: &str) {
+        let Some(location) = self.locate(id) else {
+            return;
+        };
+        self.focused = id.to_string();
+
+        let monitor = &mut self.monitors[location.monitor];
+        let workspace_id = monitor.workspaces[location.workspace].root.id.clone();
+        let previous = std::mem::replace(&mut monitor.displayed, workspace_id.clone());
+        move_to_front(&mut monitor.child_focus_order, &workspace_id);
+
+        let mut container = &mut monitor.workspaces[location.workspace].root;
+        for &index in &location.path {
+            let child_id = container.children[index].id().to_string();
+            move_to_front(&mut container.child_focus_order, &child_id);
+            container = match &mut container.children[index] {
+                SimNode::Split(split) => split,
+                SimNode::Window(_) => break,
+            };
+        }
+
+        // glazewm destroys empty workspaces once they are no longer displayed
+        if previous != workspace_id {
+            monitor.workspaces.retain(|workspace| {
+                workspace.root.id != previous || !workspace.root.children.is_empty()
+            });
+            let workspaces = &monitor.workspaces;
+            monitor
+                .child_focus_order
+                .retain(|id| workspaces.iter().any(|workspace| &workspace.root.id == id));
+        }
+    }
+
+    /// Ids from the focused monitor down to the focused container
+    fn focus_chain(&self) -> Vec<String> {
+        let Some(location) = self.locate(&self.focused) else {
+            return Vec::new();
+        };
+        let monitor = &self.monitors[location.monitor];
+        let mut container = &monitor.workspaces[location.workspace].root;
+        let mut chain = vec![monitor.id.clone(), container.id.clone()];
+
+        for &index in &location.path {
+            let child = &container.children[index];
+            chain.push(child.id().to_string());
+            match child {
+                SimNode::Split(split) => container = split,
+                SimNode::Window(_) => break,
+            }
+        }
+        chain
+    }
+
+    /// Rectangles of every workspace, split container and window
+    fn layout(&self) -> HashMap<String, Rectangle> {
+        let mut layout = HashMap::new();
+        for monitor in &self.monitors {
+            for workspace in &monitor.workspaces {
+                workspace
+                    .root
+                    .layout(monitor.setup.working_rect, &mut layout);
+            }
+        }
+        layout
+    }
+
+    /// Remove a window, collapse its emptied parents and pick the container
+    /// glazewm would focus next; returns that target and the window
+    ///
+    /// Like glazewm, the most recently focused window of the same kind
+    /// (tiling or floating) wins, then any other window, then the workspace.
+    fn detach_window(&mut self, location: &Location) -> (String, SimNode) {
+        let root = &self.workspace(location).root;
+        let mut windows = Vec::new();
+        root.windows_by_focus(&mut windows);
+
+        let window = self
+            .window(location)
+            .map(|window| (window.id.clone(), window.floating));
+        let (window_id, floating) = window.unwrap_or_default();
+        let target = windows
+            .iter()
+            .filter(|candidate| candidate.id != window_id)
+            .min_by_key(|candidate| candidate.floating != floating)
+            .map(|candidate| candidate.id.clone())
+            .unwrap_or_else(|| root.id.clone());
+
+        let node = self.detach(location);
+        (target, node)
+    }
+
+    /// Remove the node at a location, collapsing split containers that are
+    /// left empty or with a single child
+    fn detach(&mut self, location: &Location) -> SimNode {
+        let parent_location = location.parent().expect("detached node has a parent");
+        let index = *location.path.last().expect("detached node has a parent");
+        let parent = self
+            .container_mut(&parent_location)
+            .expect("parent container exists");
+        let node = parent.remove(index);
+        parent.normalize_sizes();
+        let parent_id = parent.id.clone();
+
+        self.collapse(&parent_id);
+        node
+    }
+
+    /// Flatten a split container with one child and remove empty ones
+    fn collapse(&mut self, split_id: &str) {
+        let Some(location) = self.locate(split_id) else {
+            return;
+        };
+        let Some(parent_location) = location.parent() else {
+            return;
+        };
+        let index = *location.path.last().expect("split has a parent");
+        let parent = self
+            .container_mut(&parent_location)
+            .expect("parent container exists");
+
+        let SimNode::Split(split) = &mut parent.children[index] else {
+            return;
+        };
+        match split.children.len() {
+            0 => {
+                parent.remove(index);
+                parent.normalize_sizes();
+                let parent_id = parent.id.clone();
+                self.collapse(&parent_id);
+            }
+            1 => {
+                let tiling_size = split.tiling_size;
+                let mut child = split.children.remove(0);
+                child.set_tiling_size(tiling_size);
+                for id in parent.child_focus_order.iter_mut() {
+                    if id == split_id {
+                        *id = child.id().to_string();
+                    }
+                }
+                parent.children[index] = child;
+            }
+            _ => {}
+        }
+    }
+
+    /// Insert a node into the container with `container_id` at `index`
+    fn attach(&mut self, container_id: &str, index: usize, node: SimNode) {
+        if let Some(location) = self.locate(container_id) {
+            if let Some(container) = self.container_mut(&location) {
+                container.insert(index, node);
+            }
+        }
+    }
+
+    fn focus_direction(&mut self, direction: Direction) -> Result<(), SimulatorError> {
+        let layout = self.layout();
+        let origin = layout
+            .get(&self.focused)
+            .ok_or(SimulatorError::NoSuchMonitor(0))?;
+
+        // Windows on displayed workspaces, plus displayed workspaces that are
+        // empty and can only receive focus themselves
+        let mut candidates = Vec::new();
+        for monitor in &self.monitors {
+            for workspace in &monitor.workspaces {
+                if workspace.root.id != monitor.displayed {
+                    continue;
+                }
+                let mut windows = Vec::new();
+                workspace.root.windows_by_focus(&mut windows);
+                if windows.is_empty() {
+                    candidates.push(workspace.root.id.clone());
+                }
+                candidates.extend(windows.into_iter().map(|window| window.id.clone()));
+            }
+        }
+
+        let target = candidates
+            .into_iter()
+            .filter(|id| id != &self.focused)
+            .filter_map(|id| {
+                let score = direction_score(origin, layout.get(&id)?, direction)?;
+                Some((score, id))
+            })
+            .min_by_key(|(score, _)| *score)
+            .map(|(_, id)| id);
+
+        if let Some(target) = target {
+            self.set_focus(&target);
+        }
+        Ok(())
+    }
+
+    fn move_direction(&mut self, direction: Direction) -> Result<(), SimulatorError> {
+        let location = self.focused_window_location()?;
+        let window_id = self.focused.clone();
+
+        if let Some(window) = self.window_mut(&location).filter(|window| window.floating) {
+            let (dx, dy) = match direction {
+                Direction::Left => (-FLOATING_MOVE_STEP, 0),
+                Direction::Right => (FLOATING_MOVE_STEP, 0),
+                Direction::Up => (0, -FLOATING_MOVE_STEP),
+                Direction::Down => (0, FLOATING_MOVE_STEP),
+            };
+            window.floating_rect.position.x += dx;
+            window.floating_rect.position.y += dy;
+            return Ok(());
+        }
+
+        let parent_location = location.parent().expect("window has a parent");
+        let index = *location.path.last().expect("window has a parent");
+        let parent = self
+            .container(&parent_location)
+            .expect("parent container exists");
+        let parent_id = parent.id.clone();
+
+        // 1. Swap with the next tiling sibling, or move into it if it is a split
+        if parent.tiling_direction == direction.axis() {
+            let sibling = if direction.is_forward() {
+                parent.children[index + 1..]
+                    .iter()
+                    .position(SimNode::is_tiling)
+                    .map(|offset| index + 1 + offset)
+            } else {
+                parent.children[..index]
+                    .iter()
+                    .rposition(SimNode::is_tiling)
+            };
+
+            if let Some(sibling) = sibling {
+                match &parent.children[sibling] {
+                    SimNode::Split(split) => {
+                        let split_id = split.id.clone();
+                        let insert_at = if direction.is_forward() {
+                            0
+                        } else {
+                            split.children.len()
+                        };
+                        let node = self.detach(&location);
+                        self.attach(&split_id, insert_at, node);
+                    }
+                    SimNode::Window(_) => {
+                        let parent = self
+                            .container_mut(&parent_location)
+                            .expect("parent container exists");
+                        parent.children.swap(index, sibling);
+                    }
+                }
+                self.set_focus(&window_id);
+                return Ok(());
+            }
+        }
+
+        // 2. Move next to the nearest ancestor along the same axis
+        let mut ancestor = parent_location.clone();
+        while let Some(grandparent) = ancestor.parent() {
+            let child_index = *ancestor.path.last().expect("ancestor has a parent");
+            let container = self.container(&grandparent).expect("ancestor exists");
+            if container.tiling_direction == direction.axis() {
+                let container_id = container.id.clone();
+                let node = self.remove_without_collapse(&location);
+                let insert_at = child_index + direction.is_forward() as usize;
+                self.attach(&container_id, insert_at, node);
+                self.collapse(&parent_id);
+                self.set_focus(&window_id);
+                return Ok(());
+            }
+            ancestor = grandparent;
+        }
+
+        // 3. Move to the workspace displayed on the monitor in that direction
+        let current = &self.monitors[location.monitor];
+        let neighbour = self
+            .monitors
+            .iter()
+            .filter(|monitor| monitor.id != current.id)
+            .filter_map(|monitor| {
+                let score = direction_score(&current.setup.rect, &monitor.setup.rect, direction)?;
+                Some((score, monitor.displayed.clone()))
+            })
+            .min_by_key(|(score, _)| *score)
+            .map(|(_, workspace)| workspace);
+
+        if let Some(workspace_id) = neighbour {
+            let node = self.detach(&location);
+            let insert_at = if direction.is_forward() {
+                0
+            } else {
+                usize::MAX
+            };
+            self.attach(&workspace_id, insert_at, node);
+            self.set_focus(&window_id);
+            return Ok(());
+        }
+
+        // 4. At the edge of the screen: turn the workspace to the new axis,
+        // keeping the other windows together in a split container
+        let workspace_location = Location {
+            path: Vec::new(),
+            ..location.clone()
+        };
+        let root = self
+            .container(&workspace_location)
+            .expect("workspace exists");
+        let alone = location.path.len() == 1 && root.tiling_count() <= 1;
+        if alone || root.tiling_direction == direction.axis() {
+            return Ok(());
+        }
+
+        let node = self.detach(&location);
+        let split_id = self.next_id("split");
+        let root = self
+            .container_mut(&workspace_location)
+            .expect("workspace exists");
+        if root.tiling_count() > 1 {
+            let mut split = SimContainer::new(split_id.clone(), root.tiling_direction);
+            let (tiling, floating): (Vec<SimNode>, Vec<SimNode>) =
+                root.children.drain(..).partition(SimNode::is_tiling);
+            split.child_focus_order = root
+                .child_focus_order
+                .iter()
+                .filter(|id| tiling.iter().any(|node| node.id() == id.as_str()))
+                .cloned()
+                .collect();
+            split.children = tiling;
+            root.child_focus_order
+                .retain(|id| !split.child_focus_order.contains(id));
+            root.child_focus_order.push(split_id);
+            root.children.push(SimNode::Split(split));
+            root.children.extend(floating);
+        }
+        root.tiling_direction = direction.axis();
+        let insert_at = if direction.is_forward() {
+            usize::MAX
+        } else {
+            0
+        };
+        root.insert(insert_at, node);
+        self.set_focus(&window_id);
+        Ok(())
+    }
+
+    /// Remove a node but leave collapsing its parent to the caller, so that
+    /// indices of ancestors stay valid while it is re-inserted
+    fn remove_without_collapse(&mut self, location: &Location) -> SimNode {
+        let parent_location = location.parent().expect("node has a parent");
+        let index = *location.path.last().expect("node has a parent");
+        let parent = self
+            .container_mut(&parent_location)
+            .expect("parent container exists");
+        let node = parent.remove(index);
+        parent.normalize_sizes();
+        node
+    }
+
+    fn move_to_workspace(&mut self, name: &str) -> Result<(), SimulatorError> {
+        let location = self.focused_window_location()?;
+        let target = match self.find_workspace(name) {
+            Some(target) => target,
+            None => {
+                let id = self.create_workspace(location.monitor, name);
+                self.locate(&id)
+                    .ok_or(SimulatorError::NoSuchContainer(id))?
+            }
+        };
+        if (target.monitor, target.workspace) == (location.monitor, location.workspace) {
+            return Ok(());
+        }
+
+        let source_origin = self.monitors[location.monitor].setup.working_rect.position;
+        let target_monitor = target.monitor;
+        let target_id = self.workspace(&target).root.id.clone();
+
+        let (focus_target, mut node) = self.detach_window(&location);
+        let window_id = node.id().to_string();
+
+        // Floating windows keep their offset from the monitor's corner
+        if let SimNode::Window(window) = &mut node {
+            let target_origin = self.monitors[target_monitor].setup.working_rect.position;
+            window.floating_rect.position.x += target_origin.x - source_origin.x;
+            window.floating_rect.position.y += target_origin.y - source_origin.y;
+        }
+
+        self.attach(&target_id, usize::MAX, node);
+        if let Some(target) = self.locate(&target_id) {
+            if let Some(root) = self.container_mut(&target) {
+                move_to_front(&mut root.child_focus_order, &window_id);
+            }
+        }
+        self.set_focus(&focus_target);
+        Ok(())
+    }
+
+    fn resize(&mut self, dimension: Dimension, amount: Amount) -> Result<(), SimulatorError> {
+        let location = self.focused_window_location()?;
+        let axis = match dimension {
+            Dimension::Width => Axis::Horizontal,
+            Dimension::Height => Axis::Vertical,
+        };
+
+        if let Some(window) = self.window_mut(&location).filter(|window| window.floating) {
+            let size = &mut window.floating_rect.size;
+            let extent = match axis {
+                Axis::Horizontal => &mut size.width,
+                Axis::Vertical => &mut size.height,
+            };
+            let delta = amount.pixels_of(*extent);
+            *extent = (*extent as i64 + delta as i64).max(MIN_FLOATING_EXTENT as i64) as u32;
+            return Ok(());
+        }
+
+        // Resize the nearest ancestor laid out along the requested axis
+        let layout = self.layout();
+        let mut current = location;
+        while let Some(parent_location) = current.parent() {
+            let parent = self.container(&parent_location).expect("parent exists");
+            if parent.tiling_direction == axis && parent.tiling_count() > 1 {
+                let index = *current.path.last().expect("node has a parent");
+                let length = layout
+                    .get(&parent.id)
+                    .map(|rect| axis.length_of(rect))
+                    .unwrap_or(1)
+                    .max(1);
+                let delta = amount.pixels_of(length) as f64 / length as f64;
+                let siblings = parent.tiling_count() - 1;
+
+                let parent = self.container_mut(&parent_location).expect("parent exists");
+                let old_size = parent.children[index].tiling_size();
+                let max_size = 1.0 - MIN_TILING_SIZE * siblings as f64;
+                let new_size = (old_size + delta).clamp(MIN_TILING_SIZE, max_size);
+                let scale = (1.0 - new_size) / (1.0 - old_size).max(f64::EPSILON);
+
+                for (child_index, child) in parent.children.iter_mut().enumerate() {
+                    if child_index == index {
+                        child.set_tiling_size(new_size);
+                    } else if child.is_tiling() {
+                        child.set_tiling_size(child.tiling_size() * scale);
+                    }
+                }
+                return Ok(());
+            }
+            current = parent_location;
+        }
+        Ok(())
+    }
+
+    fn toggle_floating(&mut self) -> Result<(), SimulatorError> {
+        let location = self.focused_window_location()?;
+        let window_id = self.focused.clone();
+        let workspace_id = self.workspace(&location).root.id.clone();
+        let working_rect = self.monitors[location.monitor].setup.working_rect;
+        let current_rect = self.layout().get(&window_id).copied();
+
+        let mut node = self.detach(&location);
+        if let SimNode::Window(window) = &mut node {
+            window.floating = !window.floating;
+            if window.floating {
+                // glazewm centers newly floating windows on their monitor
+                let size = current_rect
+                    .map(|rect| rect.size)
+                    .unwrap_or(working_rect.size);
+                let width = size.width.min(working_rect.size.width * 3 / 4);
+                let height = size.height.min(working_rect.size.height * 3 / 4);
+                window.floating_rect = Rectangle::from_coords(
+                    working_rect.position.x + (working_rect.size.width - width) as i32 / 2,
+                    working_rect.position.y + (working_rect.size.height - height) as i32 / 2,
+                    width,
+                    height,
+                );
+            }
+        }
+
+        self.attach(&workspace_id, usize::MAX, node);
+        self.set_focus(&window_id);
+        Ok(())
+    }
+
+    fn shell_exec(&mut self, program: String, title: Option<String>) -> Result<(), SimulatorError> {
+        let location = self.focused_location()?;
+        let id = self.next_id("window");
+        let handle = 0x20000 + self.next_id as i64;
+        let working_rect = self.monitors[location.monitor].setup.working_rect;
+
+        let window = SimNode::Window(SimWindow {
+            id: id.clone(),
+            handle,
+            title: title.unwrap_or_else(|| program.clone()),
+            process_name: program,
+            floating: false,
+            tiling_size: 1.0,
+            floating_rect: working_rect,
+        });
+
+        // New windows open next to the focused tiling window
+        let (container_id, index) = match self.window(&location) {
+            Some(focused) if !focused.floating => {
+                let parent = location.parent().expect("window has a parent");
+                let container = self.container(&parent).expect("parent exists");
+                (
+                    container.id.clone(),
+                    location.path.last().copied().unwrap_or(0) + 1,
+                )
+            }
+            _ => (self.workspace(&location).root.id.clone(), usize::MAX),
+        };
+
+        self.attach(&container_id, index, window);
+        self.set_focus(&id);
+        Ok(())
+    }
+
+    fn monitor_json(
+        &self,
+        monitor: &SimMonitor,
+        chain: &[String],
+        layout: &HashMap<String, Rectangle>,
+    ) -> Value {
+        let rect = &monitor.setup.rect;
+        let children: Vec<Value> = monitor
+            .workspaces
+            .iter()
+            .map(|workspace| {
+                let root = &workspace.root;
+                let mut value = container_json(root, &monitor.id, chain, layout);
+                value["type"] = json!("workspace");
+                value["name"] = json!(workspace.name);
+                value["displayName"] = Value::Null;
+                let shown = root.id == monitor.displayed;
+                value["isDisplayed"] = json!(shown);
+                value["children"] = children_json(root, shown, chain, layout);
+                value
+            })
+            .collect();
+
+        json!({
+            "type": "monitor",
+            "id": monitor.id,
+            "parentId": null,
+            "hasFocus": chain.contains(&monitor.id),
+            "x": rect.position.x,
+            "y": rect.position.y,
+            "width": rect.size.width,
+            "height": rect.size.height,
+            "dpi": monitor.setup.dpi,
+            "scaleFactor": monitor.setup.scale_factor,
+            "handle": monitor.handle,
+            "deviceName": monitor.setup.device_name,
+            "hardwareId": monitor.setup.hardware_id,
+            "workingRect": edges_json(&monitor.setup.working_rect),
+            "children": children,
+            "childFocusOrder": monitor.child_focus_order,
+        })
+    }
+}
+
+impl Default for Simulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fields shared by workspaces and split containers
+fn container_json(
+    container: &SimContainer,
+    parent_id: &str,
+    chain: &[String],
+    layout: &HashMap<String, Rectangle>,
+) -> Value {
+    let rect = layout_rect(layout, &container.id);
+    json!({
+        "type": "split",
+        "id": container.id,
+        "parentId": parent_id,
+        "hasFocus": chain.contains(&container.id),
+        "tilingSize": container.tiling_size,
+        "tilingDirection": container.tiling_direction.as_str(),
+        "x": rect.position.x,
+        "y": rect.position.y,
+        "width": rect.size.width,
+        "height": rect.size.height,
+        "childFocusOrder": container.child_focus_order,
+    })
+}
+
+fn children_json(
+    container: &SimContainer,
+    shown: bool,
+    chain: &[String],
+    layout: &HashMap<String, Rectangle>,
+) -> Value {
+    let children = container
+        .children
+        .iter()
+        .map(|child| match child {
+            SimNode::Split(split) => {
+                let mut value = container_json(split, &container.id, chain, layout);
+                value["children"] = children_json(split, shown, chain, layout);
+                value
+            }
+            SimNode::Window(window) => {
+                let rect = layout_rect(layout, &window.id);
+                json!({
+                    "type": "window",
+                    "id": window.id,
+                    "parentId": container.id,
+                    "hasFocus": chain.contains(&window.id),
+                    "tilingSize": window.tiling_size,
+                    "x": rect.position.x,
+                    "y": rect.position.y,
+                    "width": rect.size.width,
+                    "height": rect.size.height,
+                    "state": { "type": if window.floating { "floating" } else { "tiling" } },
+                    "prevState": null,
+                    "displayState": if shown { "shown" } else { "hidden" },
+                    "borderDelta": { "left": 0, "top": 0, "right": 0, "bottom": 0 },
+                    "floatingPlacement": edges_json(&window.floating_rect),
+                    "handle": window.handle,
+                    "title": window.title,
+                    "className": "SimulatedWindow",
+                    "processName": window.process_name,
+                    "activeDrag": null,
+                })
+            }
+        })
+        .collect();
+    Value::Array(children)
+}
+
+/// Rectangle computed for a container, empty if it is not laid out
+fn layout_rect(layout: &HashMap<String, Rectangle>, id: &str) -> Rectangle {
+    layout
+        .get(id)
+        .copied()
+        .unwrap_or(Rectangle::from_coords(0, 0, 0, 0))
+}
+
+/// Rectangle as glazewm serializes it, by its edges
+fn edges_json(rect: &Rectangle) -> Value {
+    json!({
+        "left": rect.position.x,
+        "top": rect.position.y,
+        "right": rect.position.x + rect.size.width as i32,
+        "bottom": rect.position.y + rect.size.height as i32,
+    })
+}
+
+/// Collect every window in a monitors response into a flat list, mirroring
+/// what `glazewm query windows` returns
+pub(crate) fn collect_windows(node: &Value, windows: &mut Vec<Value>) {
+    if node.get("type").and_then(Value::as_str) == Some("window") {
+        windows.push(node.clone());
+    }
+
+    let children = node
+        .get("children")
+        .or_else(|| node.get("monitors"))
+        .and_then(Value::as_array);
+    for child in children.into_iter().flatten() {
+        collect_windows(child, windows);
+    }
+}
+
+fn move_to_front(order: &mut Vec<String>, id: &str) {
+    order.retain(|existing| existing != id);
+    order.insert(0, id.to_string());
+}
+
+/// How far `to` lies from `from` in a direction, favouring rectangles that
+/// stay aligned with it; `None` when `to` is not in that direction at all
+fn direction_score(from: &Rectangle, to: &Rectangle, direction: Direction) -> Option<i64> {
+    let center = |rect: &Rectangle| {
+        (
+            rect.position.x as i64 + rect.size.width as i64 / 2,
+            rect.position.y as i64 + rect.size.height as i64 / 2,
+        )
+    };
+    let (from_x, from_y) = center(from);
+    let (to_x, to_y) = center(to);
+
+    let (primary, secondary) = match direction {
+        Direction::Left => (from_x - to_x, to_y - from_y),
+        Direction::Right => (to_x - from_x, to_y - from_y),
+        Direction::Up => (from_y - to_y, to_x - from_x),
+        Direction::Down => (to_y - from_y, to_x - from_x),
+    };
+
+    (primary > 0).then_some(primary + 2 * secondary.abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::GlazewmParser;
+
+    fn single_monitor() -> Simulator {
+        let mut simulator = Simulator::new();
+        simulator.add_monitor(
+            MonitorSetup {
+                device_name: "\\\\.\\DISPLAY1".to_string(),
+                hardware_id: "TEST0001".to_string(),
+                rect: Rectangle::from_coords(0, 0, 1000, 800),
+                working_rect: Rectangle::from_coords(0, 0, 1000, 800),
+                dpi: 96,
+                scale_factor: 1.0,
+            },
+            "1",
+        );
+        simulator
+    }
+
+    fn run(simulator: &mut Simulator, commands: &[&str]) {
+        for command in commands {
+            simulator.execute(command).unwrap();
+        }
+    }
+
+    fn window_widths(simulator: &Simulator) -> Vec<(String, u64)> {
+        let mut windows = Vec::new();
+        collect_windows(&simulator.monitors_json()["data"], &mut windows);
+        windows
+            .iter()
+            .map(|w| {
+                (
+                    w["title"].as_str().unwrap().to_string(),
+                    w["width"].as_u64().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_parse_glazewm_command_syntax() {
+        assert_eq!(
+            WmCommand::parse("focus --direction left").unwrap(),
+            WmCommand::FocusDirection(Direction::Left)
+        );
+        assert_eq!(
+            WmCommand::parse("resize --width -5%").unwrap(),
+            WmCommand::Resize {
+                dimension: Dimension::Width,
+                amount: Amount::Percent(-5.0)
+            }
+        );
+        assert_eq!(
+            WmCommand::parse("shell-exec notepad My Notes").unwrap(),
+            WmCommand::ShellExec {
+                program: "notepad".to_string(),
+                title: Some("My Notes".to_string())
+            }
+        );
+        assert!(matches!(
+            WmCommand::parse("focus --direction sideways"),
+            Err(SimulatorError::InvalidArguments { .. })
+        ));
+        assert!(matches!(
+            WmCommand::parse("wm-exit"),
+            Err(SimulatorError::UnknownCommand(_))
+        ));
+    }
+
+    #[test]
+    fn should_spawn_windows_next_to_focus_and_split_space() {
+        let mut simulator = single_monitor();
+        run(
+            &mut simulator,
+            &[
+                "shell-exec a",
+                "shell-exec b",
+                "focus --direction left",
+                "shell-exec c",
+            ],
+        );
+
+        assert_eq!(
+            window_widths(&simulator),
+            vec![
+                ("a".to_string(), 333),
+                ("c".to_string(), 333),
+                ("b".to_string(), 334)
+            ]
+        );
+    }
+
+    #[test]
+    fn should_focus_and_swap_in_direction() {
+        let mut simulator = single_monitor();
+        run(&mut simulator, &["shell-exec a", "shell-exec b"]);
+        let b = simulator.focused_id().to_string();
+
+        simulator.execute("focus --direction left").unwrap();
+        assert_ne!(simulator.focused_id(), b);
+
+        simulator.execute("move --direction right").unwrap();
+        let titles: Vec<String> = window_widths(&simulator)
+            .into_iter()
+            .map(|(t, _)| t)
+            .collect();
+        assert_eq!(titles, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn should_wrap_windows_in_split_when_moving_across_axis() {
+        let mut simulator = single_monitor();
+        run(
+            &mut simulator,
+            &[
+                "shell-exec a",
+                "shell-exec b",
+                "shell-exec c",
+                "move --direction down",
+            ],
+        );
+
+        let json = simulator.monitors_json();
+        let workspace = &json["data"]["monitors"][0]["children"][0];
+        assert_eq!(workspace["tilingDirection"], "vertical");
+        assert_eq!(workspace["children"][0]["type"], "split");
+        assert_eq!(workspace["children"][0]["tilingDirection"], "horizontal");
+        assert_eq!(workspace["children"][1]["title"], "c");
+        assert_eq!(workspace["children"][1]["height"], 400);
+    }
+
+    #[test]
+    fn should_resize_within_parent() {
+        let mut simulator = single_monitor();
+        run(
+            &mut simulator,
+            &["shell-exec a", "shell-exec b", "resize --width +10%"],
+        );
+
+        assert_eq!(
+            window_widths(&simulator),
+            vec![("a".to_string(), 400), ("b".to_string(), 600)]
+        );
+    }
+
+    #[test]
+    fn should_fall_back_to_most_recent_window_on_close() {
+        let mut simulator = single_monitor();
+        run(
+            &mut simulator,
+            &[
+                "shell-exec a",
+                "shell-exec b",
+                "shell-exec c",
+                "focus --direction left",
+                "focus --direction left",
+                "focus --direction right",
+            ],
+        );
+        // Focus history is now b, a, c; closing b returns to a
+        simulator.execute("close").unwrap();
+
+        let json = simulator.windows_json();
+        let focused: Vec<&str> = json["data"]["windows"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|w| w["hasFocus"] == true)
+            .map(|w| w["title"].as_str().unwrap())
+            .collect();
+        assert_eq!(focused, vec!["a"]);
+    }
+
+    #[test]
+    fn should_move_window_to_workspace_and_destroy_empty_ones() {
+        let mut simulator = single_monitor();
+        run(
+            &mut simulator,
+            &["shell-exec a", "move --workspace 2", "focus --workspace 2"],
+        );
+
+        let json = simulator.monitors_json();
+        let workspaces = json["data"]["monitors"][0]["children"].as_array().unwrap();
+        assert_eq!(workspaces.len(), 1);
+        assert_eq!(workspaces[0]["name"], "2");
+        assert_eq!(workspaces[0]["children"][0]["hasFocus"], true);
+    }
+
+    #[test]
+    fn should_toggle_floating_out_of_the_layout() {
+        let mut simulator = single_monitor();
+        run(
+            &mut simulator,
+            &["shell-exec a", "shell-exec b", "toggle-floating"],
+        );
+
+        let json = simulator.windows_json();
+        let windows = json["data"]["windows"].as_array().unwrap();
+        assert_eq!(windows[0]["width"], 1000);
+        assert_eq!(windows[1]["state"]["type"], "floating");
+        assert_eq!(windows[1]["width"], 500);
+        assert_eq!(windows[1]["x"], 250);
+    }
+
+    #[test]
+    fn should_move_workspaces_off_an_unplugged_monitor() {
+        let mut simulator = Simulator::demo();
+        run(&mut simulator, &["focus --monitor 1"]);
+
+        simulator.remove_monitor(1).unwrap();
+
+        let json = simulator.monitors_json();
+        let monitors = json["data"]["monitors"].as_array().unwrap();
+        assert_eq!(monitors.len(), 1);
+        let names: Vec<&str> = monitors[0]["children"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|w| w["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["Development", "Testing", "Communication"]);
+        assert_eq!(monitors[0]["hasFocus"], true);
+        assert_eq!(
+            simulator.windows_json()["data"]["windows"]
+                .as_array()
+                .unwrap()
+                .len(),
+            5
+        );
+
+        assert!(simulator.remove_monitor(0).is_err());
+        assert_eq!(
+            simulator.remove_monitor(3),
+            Err(SimulatorError::NoSuchMonitor(3))
+        );
+    }
+
+    #[test]
+    fn should_serialize_state_the_parser_accepts() {
+        let mut simulator = Simulator::demo();
+        run(
+            &mut simulator,
+            &[
+                "toggle-tiling-direction",
+                "move --direction down",
+                "focus --workspace Testing",
+            ],
+        );
+
+        let parsed = GlazewmParser::parse_monitors(&simulator.monitors_json()).unwrap();
+        let windows = GlazewmParser::parse_windows(&simulator.windows_json()).unwrap();
+
+        assert!(parsed.warnings.is_empty());
+        assert_eq!(windows.windows.len(), 5);
+        assert!(
+            crate::domain::WindowReconciler::reconcile(&parsed.monitors, &windows.windows)
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,111 @@
+// Parse warning types
+// Non-fatal anomalies found while converting glazewm JSON into domain models
+
+use serde::Serialize;
+use std::fmt;
+
+use crate::domain::{NodeKind, Placeholder};
+
+/// A non-fatal anomaly found while parsing a glazewm response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseWarning {
+    /// A field held a value this debugger does not recognize.
+    /// The value is preserved in the domain model as an `Unknown` variant.
+    UnknownValue {
+        node_id: String,
+        field: String,
+        value: String,
+    },
+    /// A node could not be parsed or validated and was replaced by a placeholder
+    InvalidNode {
+        kind: NodeKind,
+        node_id: Option<String>,
+        error: String,
+    },
+}
+
+impl ParseWarning {
+    /// Create a warning for an unrecognized field value
+    pub fn unknown_value(node_id: &str, field: &str, value: &str) -> Self {
+        ParseWarning::UnknownValue {
+            node_id: node_id.to_string(),
+            field: field.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    /// Create a warning for a node that was replaced by a placeholder
+    pub fn invalid_node(placeholder: &Placeholder) -> Self {
+        ParseWarning::InvalidNode {
+            kind: placeholder.kind(),
+            node_id: placeholder.id().map(str::to_string),
+            error: placeholder.error().to_string(),
+        }
+    }
+
+    /// Id of the node the warning refers to, if glazewm sent one
+    pub fn node_id(&self) -> Option<&str> {
+        match self {
+            ParseWarning::UnknownValue { node_id, .. } => Some(node_id),
+            ParseWarning::InvalidNode { node_id, .. } => node_id.as_deref(),
+        }
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseWarning::UnknownValue {
+                node_id,
+                field,
+                value,
+            } => write!(f, "{}: unknown {} '{}'", node_id, field, value),
+            ParseWarning::InvalidNode {
+                kind,
+                node_id,
+                error,
+            } => write!(
+                f,
+                "invalid {} {}: {}",
+                kind,
+                node_id.as_deref().unwrap_or("<no id>"),
+                error
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_display_unknown_value_warning() {
+        let warning = ParseWarning::unknown_value("window-1", "displayState", "showing");
+
+        assert_eq!(warning.node_id(), Some("window-1"));
+        assert_eq!(
+            format!("{}", warning),
+            "window-1: unknown displayState 'showing'"
+        );
+    }
+
+    #[test]
+    fn should_display_invalid_node_warning() {
+        let placeholder = Placeholder::new(
+            NodeKind::Monitor,
+            Some("monitor-2".to_string()),
+            "Invalid geometry: width and height must be positive".to_string(),
+            "{}".to_string(),
+        );
+
+        let warning = ParseWarning::invalid_node(&placeholder);
+
+        assert_eq!(warning.node_id(), Some("monitor-2"));
+        assert_eq!(
+            format!("{}", warning),
+            "invalid monitor monitor-2: Invalid geometry: width and height must be positive"
+        );
+    }
+}
@@ -0,0 +1,576 @@
+// Snapshot comparison
+// What changed in the monitor tree between two polls of glazewm
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::domain::values::Rectangle;
+use crate::domain::{Monitor, MonitorId, Window, WindowId, Workspace, WorkspaceId};
+
+/// A window as named in change descriptions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WindowRef {
+    pub id: WindowId,
+    /// `process: title`
+    pub name: String,
+}
+
+impl WindowRef {
+    fn new(window: &Window) -> Self {
+        Self {
+            id: window.id().clone(),
+            name: window.display_name(),
+        }
+    }
+}
+
+impl fmt::Display for WindowRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// One difference between two snapshots of the monitor tree
+///
+/// Workspaces are named by their label, monitors by their id.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Change {
+    MonitorAdded {
+        monitor: MonitorId,
+    },
+    MonitorRemoved {
+        monitor: MonitorId,
+    },
+    WorkspaceAdded {
+        workspace: String,
+        monitor: MonitorId,
+    },
+    WorkspaceRemoved {
+        workspace: String,
+        monitor: MonitorId,
+    },
+    WorkspaceMoved {
+        workspace: String,
+        from_monitor: MonitorId,
+        to_monitor: MonitorId,
+    },
+    TilingDirectionChanged {
+        workspace: String,
+        from: String,
+        to: String,
+    },
+    WindowClosed {
+        window: WindowRef,
+        workspace: String,
+    },
+    WindowOpened {
+        window: WindowRef,
+        workspace: String,
+    },
+    WindowMoved {
+        window: WindowRef,
+        from_workspace: String,
+        to_workspace: String,
+    },
+    WindowStateChanged {
+        window: WindowRef,
+        from: String,
+        to: String,
+    },
+    WindowTitleChanged {
+        window: WindowRef,
+        from: String,
+        to: String,
+    },
+    WindowGeometryChanged {
+        window: WindowRef,
+        from: Rectangle,
+        to: Rectangle,
+    },
+    WorkspaceFocusChanged {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    FocusChanged {
+        from: Option<WindowRef>,
+        to: Option<WindowRef>,
+    },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn or_none<T: fmt::Display>(value: &Option<T>) -> String {
+            value
+                .as_ref()
+                .map_or_else(|| "(none)".to_string(), ToString::to_string)
+        }
+
+        match self {
+            Change::MonitorAdded { monitor } => write!(f, "monitor {} connected", monitor),
+            Change::MonitorRemoved { monitor } => write!(f, "monitor {} disconnected", monitor),
+            Change::WorkspaceAdded { workspace, monitor } => {
+                write!(f, "workspace {} created on monitor {}", workspace, monitor)
+            }
+            Change::WorkspaceRemoved { workspace, monitor } => {
+                write!(
+                    f,
+                    "workspace {} removed from monitor {}",
+                    workspace, monitor
+                )
+            }
+            Change::WorkspaceMoved {
+                workspace,
+                from_monitor,
+                to_monitor,
+            } => write!(
+                f,
+                "workspace {} moved from monitor {} to {}",
+                workspace, from_monitor, to_monitor
+            ),
+            Change::TilingDirectionChanged {
+                workspace,
+                from,
+                to,
+            } => write!(
+                f,
+                "workspace {} tiling direction {} -> {}",
+                workspace, from, to
+            ),
+            Change::WindowClosed { window, workspace } => {
+                write!(f, "window closed on workspace {}: {}", workspace, window)
+            }
+            Change::WindowOpened { window, workspace } => {
+                write!(f, "window opened on workspace {}: {}", workspace, window)
+            }
+            Change::WindowMoved {
+                window,
+                from_workspace,
+                to_workspace,
+            } => write!(
+                f,
+                "{} moved from workspace {} to {}",
+                window, from_workspace, to_workspace
+            ),
+            Change::WindowStateChanged { window, from, to } => {
+                write!(f, "{} became {} (was {})", window, to, from)
+            }
+            Change::WindowTitleChanged { window, from, to } => {
+                write!(f, "{} title changed: {} -> {}", window, from, to)
+            }
+            Change::WindowGeometryChanged { window, from, to } => {
+                write!(f, "{} geometry {} -> {}", window, from, to)
+            }
+            Change::WorkspaceFocusChanged { from, to } => write!(
+                f,
+                "workspace focus moved: {} -> {}",
+                or_none(from),
+                or_none(to)
+            ),
+            Change::FocusChanged { from, to } => {
+                write!(f, "focus moved: {} -> {}", or_none(from), or_none(to))
+            }
+        }
+    }
+}
+
+/// Focused window of the focused monitor
+pub fn focused_window(monitors: &[Monitor]) -> Option<&Window> {
+    monitors
+        .iter()
+        .filter(|monitor| monitor.is_focused())
+        .find_map(Monitor::focused_window)
+}
+
+/// Focused workspace of the focused monitor
+fn focused_workspace(monitors: &[Monitor]) -> Option<&Workspace> {
+    monitors
+        .iter()
+        .filter(|monitor| monitor.is_focused())
+        .flat_map(Monitor::workspaces)
+        .find(|workspace| workspace.is_focused())
+}
+
+/// Every change from `old` to `new`: monitors first, then workspaces, closed
+/// and opened windows, changed windows, and focus last
+pub fn compare(old: &[Monitor], new: &[Monitor]) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    // Monitors
+    let old_monitors: HashSet<&MonitorId> = old.iter().map(Monitor::id).collect();
+    let new_monitors: HashSet<&MonitorId> = new.iter().map(Monitor::id).collect();
+    for monitor in old.iter().filter(|m| !new_monitors.contains(m.id())) {
+        changes.push(Change::MonitorRemoved {
+            monitor: monitor.id().clone(),
+        });
+    }
+    for monitor in new.iter().filter(|m| !old_monitors.contains(m.id())) {
+        changes.push(Change::MonitorAdded {
+            monitor: monitor.id().clone(),
+        });
+    }
+
+    // Workspaces
+    let old_workspaces = workspaces_by_id(old);
+    let new_workspaces = workspaces_by_id(new);
+    for (workspace, monitor) in workspaces_in_tree_order(old) {
+        if !new_workspaces.contains_key(workspace.id()) {
+            changes.push(Change::WorkspaceRemoved {
+                workspace: workspace.label().to_string(),
+                monitor: monitor.id().clone(),
+            });
+        }
+    }
+    for (workspace, monitor) in workspaces_in_tree_order(new) {
+        match old_workspaces.get(workspace.id()) {
+            None => changes.push(Change::WorkspaceAdded {
+                workspace: workspace.label().to_string(),
+                monitor: monitor.id().clone(),
+            }),
+            Some((old_workspace, old_monitor)) => {
+                if old_monitor.id() != monitor.id() {
+                    changes.push(Change::WorkspaceMoved {
+                        workspace: workspace.label().to_string(),
+                        from_monitor: old_monitor.id().clone(),
+                        to_monitor: monitor.id().clone(),
+                    });
+                }
+                if old_workspace.tiling_direction() != workspace.tiling_direction() {
+                    changes.push(Change::TilingDirectionChanged {
+                        workspace: workspace.label().to_string(),
+                        from: old_workspace.tiling_direction().to_string(),
+                        to: workspace.tiling_direction().to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    // Windows
+    let old_windows = windows_by_id(old);
+    let new_windows = windows_by_id(new);
+    for (window, workspace) in windows_in_tree_order(old) {
+        if !new_windows.contains_key(window.id()) {
+            changes.push(Change::WindowClosed {
+                window: WindowRef::new(window),
+                workspace: workspace.label().to_string(),
+            });
+        }
+    }
+    for (window, workspace) in windows_in_tree_order(new) {
+        if !old_windows.contains_key(window.id()) {
+            changes.push(Change::WindowOpened {
+                window: WindowRef::new(window),
+                workspace: workspace.label().to_string(),
+            });
+        }
+    }
+    for (window, workspace) in windows_in_tree_order(new) {
+        let Some((old_window, old_workspace)) = old_windows.get(window.id()) else {
+            continue;
+        };
+
+        if old_workspace.id() != workspace.id() {
+            changes.push(Change::WindowMoved {
+                window: WindowRef::new(window),
+                from_workspace: old_workspace.label().to_string(),
+                to_workspace: workspace.label().to_string(),
+            });
+        }
+        if old_window.state() != window.state() {
+            changes.push(Change::WindowStateChanged {
+                window: WindowRef::new(window),
+                from: old_window.state().to_string(),
+                to: window.state().to_string(),
+            });
+        }
+        if old_window.title() != window.title() {
+            changes.push(Change::WindowTitleChanged {
+                window: WindowRef::new(window),
+                from: old_window.title().to_string(),
+                to: window.title().to_string(),
+            });
+        }
+        if old_window.geometry() != window.geometry() {
+            changes.push(Change::WindowGeometryChanged {
+                window: WindowRef::new(window),
+                from: *old_window.geometry(),
+                to: *window.geometry(),
+            });
+        }
+    }
+
+    // Focus
+    let old_workspace = focused_workspace(old);
+    let new_workspace = focused_workspace(new);
+    if old_workspace.map(Workspace::id) != new_workspace.map(Workspace::id) {
+        changes.push(Change::WorkspaceFocusChanged {
+            from: old_workspace.map(|workspace| workspace.label().to_string()),
+            to: new_workspace.map(|workspace| workspace.label().to_string()),
+        });
+    }
+    let old_focus = focused_window(old);
+    let new_focus = focused_window(new);
+    if old_focus.map(Window::id) != new_focus.map(Window::id) {
+        changes.push(Change::FocusChanged {
+            from: old_focus.map(WindowRef::new),
+            to: new_focus.map(WindowRef::new),
+        });
+    }
+
+    changes
+}
+
+type WorkspaceIndex<'a> = HashMap<&'a WorkspaceId, (&'a Workspace, &'a Monitor)>;
+
+fn workspaces_in_tree_order(monitors: &[Monitor]) -> impl Iterator<Item = (&Workspace, &Monitor)> {
+    monitors
+        .iter()
+        .flat_map(|monitor| monitor.workspaces().iter().map(move |ws| (ws, monitor)))
+}
+
+fn workspaces_by_id(monitors: &[Monitor]) -> WorkspaceIndex<'_> {
+    workspaces_in_tree_order(monitors)
+        .map(|(workspace, monitor)| (workspace.id(), (workspace, monitor)))
+        .collect()
+}
+
+fn windows_in_tree_order(monitors: &[Monitor]) -> impl Iterator<Item = (&Window, &Workspace)> {
+    monitors
+        .iter()
+        .flat_map(Monitor::workspaces)
+        .flat_map(|workspace| workspace.windows().iter().map(move |w| (w, workspace)))
+}
+
+fn windows_by_id(monitors: &[Monitor]) -> HashMap<&WindowId, (&Window, &Workspace)> {
+    windows_in_tree_order(monitors)
+        .map(|(window, workspace)| (window.id(), (window, workspace)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{DisplayState, FocusState, TilingDirection, WindowState};
+
+    fn window(id: &str, state: WindowState, focused: bool) -> Window {
+        Window::new(
+            WindowId::new(id.to_string()),
+            format!("{} title", id),
+            "app".to_string(),
+            Rectangle::from_coords(0, 0, 800, 600),
+            state,
+            if focused {
+                FocusState::Focused
+            } else {
+                FocusState::Unfocused
+            },
+            DisplayState::Shown,
+        )
+    }
+
+    fn workspace(name: &str, windows: Vec<Window>, focused: bool) -> Workspace {
+        let focus = if focused {
+            FocusState::Focused
+        } else {
+            FocusState::Unfocused
+        };
+        Workspace::new(
+            WorkspaceId::new(format!("ws-{}", name)),
+            name.to_string(),
+            windows,
+            TilingDirection::Horizontal,
+            focus,
+            DisplayState::Shown,
+        )
+    }
+
+    fn monitor(id: &str, workspaces: Vec<Workspace>, focused: bool) -> Monitor {
+        let focus = if focused {
+            FocusState::Focused
+        } else {
+            FocusState::Unfocused
+        };
+        Monitor::new(
+            MonitorId::new(id.to_string()),
+            Rectangle::from_coords(0, 0, 1920, 1080),
+            workspaces,
+            focus,
+            96,
+            1.0,
+        )
+    }
+
+    #[test]
+    fn should_report_nothing_for_identical_snapshots() {
+        let snapshot = vec![monitor(
+            "m1",
+            vec![workspace(
+                "1",
+                vec![window("a", WindowState::Tiling, true)],
+                true,
+            )],
+            true,
+        )];
+
+        assert!(compare(&snapshot, &snapshot).is_empty());
+    }
+
+    #[test]
+    fn should_report_opened_windows_and_focus() {
+        let old = vec![monitor(
+            "m1",
+            vec![workspace(
+                "1",
+                vec![window("a", WindowState::Tiling, true)],
+                true,
+            )],
+            true,
+        )];
+        let new = vec![monitor(
+            "m1",
+            vec![workspace(
+                "1",
+                vec![
+                    window("a", WindowState::Tiling, false),
+                    window("b", WindowState::Tiling, true),
+                ],
+                true,
+            )],
+            true,
+        )];
+
+        let lines: Vec<String> = compare(&old, &new)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "window opened on workspace 1: app: b title",
+                "focus moved: app: a title -> app: b title",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_moved_floating_and_closed_windows() {
+        let old = vec![monitor(
+            "m1",
+            vec![
+                workspace(
+                    "1",
+                    vec![
+                        window("a", WindowState::Tiling, false),
+                        window("b", WindowState::Tiling, false),
+                    ],
+                    true,
+                ),
+                workspace("2", Vec::new(), false),
+            ],
+            true,
+        )];
+        let new = vec![monitor(
+            "m1",
+            vec![
+                workspace("1", Vec::new(), true),
+                workspace("2", vec![window("b", WindowState::Floating, false)], false),
+            ],
+            true,
+        )];
+
+        let changes = compare(&old, &new);
+        assert_eq!(changes.len(), 3);
+        assert!(
+            matches!(&changes[0], Change::WindowClosed { window, .. } if window.id.as_str() == "a")
+        );
+        assert_eq!(
+            changes[1].to_string(),
+            "app: b title moved from workspace 1 to 2"
+        );
+        assert_eq!(
+            changes[2].to_string(),
+            "app: b title became floating (was tiling)"
+        );
+    }
+
+    #[test]
+    fn should_report_title_changes_with_the_renamed_window() {
+        let old = vec![monitor(
+            "m1",
+            vec![workspace(
+                "1",
+                vec![window("a", WindowState::Tiling, true)],
+                true,
+            )],
+            true,
+        )];
+        let renamed = Window::new(
+            WindowId::new("a".to_string()),
+            "a renamed".to_string(),
+            "app".to_string(),
+            Rectangle::from_coords(0, 0, 800, 600),
+            WindowState::Tiling,
+            FocusState::Focused,
+            DisplayState::Shown,
+        );
+        let new = vec![monitor(
+            "m1",
+            vec![workspace("1", vec![renamed], true)],
+            true,
+        )];
+
+        let changes = compare(&old, &new);
+        assert_eq!(
+            changes,
+            vec![Change::WindowTitleChanged {
+                window: WindowRef {
+                    id: WindowId::new("a".to_string()),
+                    name: "app: a renamed".to_string(),
+                },
+                from: "a title".to_string(),
+                to: "a renamed".to_string(),
+            }]
+        );
+        assert_eq!(
+            changes[0].to_string(),
+            "app: a renamed title changed: a title -> a renamed"
+        );
+    }
+
+    #[test]
+    fn should_report_workspaces_moved_off_a_removed_monitor() {
+        let old = vec![
+            monitor("m1", vec![workspace("1", Vec::new(), true)], true),
+            monitor("m2", vec![workspace("2", Vec::new(), false)], false),
+        ];
+        let new = vec![monitor(
+            "m1",
+            vec![
+                workspace("1", Vec::new(), true),
+                workspace("2", Vec::new(), false),
+            ],
+            true,
+        )];
+
+        let changes = compare(&old, &new);
+        assert_eq!(
+            changes,
+            vec![
+                Change::MonitorRemoved {
+                    monitor: MonitorId::new("m2".to_string())
+                },
+                Change::WorkspaceMoved {
+                    workspace: "2".to_string(),
+                    from_monitor: MonitorId::new("m2".to_string()),
+                    to_monitor: MonitorId::new("m1".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(&changes[0]).unwrap(),
+            serde_json::json!({ "event": "monitor_removed", "monitor": "m2" })
+        );
+    }
+}
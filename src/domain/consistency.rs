@@ -0,0 +1,286 @@
+// Window consistency checks
+// Cross-checks the monitor tree against glazewm's flat window list
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::domain::{Monitor, Placeholder, Window, WindowId, Workspace};
+
+/// A disagreement between the monitor tree and the flat window list
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Inconsistency {
+    /// Window returned by `query windows` but absent from the monitor tree
+    MissingFromTree {
+        window_id: WindowId,
+        display_name: String,
+    },
+    /// Window in the monitor tree but absent from `query windows`
+    MissingFromWindowList {
+        window_id: WindowId,
+        display_name: String,
+    },
+    /// Window present in both sources with differing values for a field
+    Mismatch {
+        window_id: WindowId,
+        field: &'static str,
+        in_tree: String,
+        in_window_list: String,
+    },
+}
+
+impl Inconsistency {
+    /// Id of the window the inconsistency refers to
+    pub fn window_id(&self) -> &WindowId {
+        match self {
+            Inconsistency::MissingFromTree { window_id, .. }
+            | Inconsistency::MissingFromWindowList { window_id, .. }
+            | Inconsistency::Mismatch { window_id, .. } => window_id,
+        }
+    }
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Inconsistency::MissingFromTree {
+                window_id,
+                display_name,
+            } => write!(
+                f,
+                "{} ({}) is in the window list but not in the monitor tree",
+                window_id, display_name
+            ),
+            Inconsistency::MissingFromWindowList {
+                window_id,
+                display_name,
+            } => write!(
+                f,
+                "{} ({}) is in the monitor tree but not in the window list",
+                window_id, display_name
+            ),
+            Inconsistency::Mismatch {
+                window_id,
+                field,
+                in_tree,
+                in_window_list,
+            } => write!(
+                f,
+                "{} {} differs: tree {}, window list {}",
+                window_id, field, in_tree, in_window_list
+            ),
+        }
+    }
+}
+
+/// Reconciles the windows found in the monitor tree with a flat window list
+pub struct WindowReconciler;
+
+impl WindowReconciler {
+    /// Find every disagreement between the two sources, in tree order first
+    pub fn reconcile(monitors: &[Monitor], windows: &[Window]) -> Vec<Inconsistency> {
+        let listed: HashMap<&WindowId, &Window> =
+            windows.iter().map(|window| (window.id(), window)).collect();
+
+        let mut inconsistencies = Vec::new();
+        let mut in_tree = HashSet::new();
+
+        for tree_window in monitors.iter().flat_map(|monitor| monitor.all_windows()) {
+            in_tree.insert(tree_window.id());
+
+            match listed.get(tree_window.id()) {
+                Some(listed_window) => {
+                    Self::compare(tree_window, listed_window, &mut inconsistencies)
+                }
+                None => inconsistencies.push(Inconsistency::MissingFromWindowList {
+                    window_id: tree_window.id().clone(),
+                    display_name: tree_window.display_name(),
+                }),
+            }
+        }
+
+        // Windows the tree degraded into placeholders already have a parse
+        // warning; reporting them as missing would say the same thing twice
+        let placeholders: HashSet<&str> = monitors
+            .iter()
+            .flat_map(|monitor| {
+                monitor.placeholders().iter().chain(
+                    monitor
+                        .workspaces()
+                        .iter()
+                        .flat_map(Workspace::placeholders),
+                )
+            })
+            .filter_map(Placeholder::id)
+            .collect();
+
+        for window in windows
+            .iter()
+            .filter(|w| !in_tree.contains(w.id()) && !placeholders.contains(w.id().as_str()))
+        {
+            inconsistencies.push(Inconsistency::MissingFromTree {
+                window_id: window.id().clone(),
+                display_name: window.display_name(),
+            });
+        }
+
+        inconsistencies
+    }
+
+    fn compare(tree: &Window, listed: &Window, inconsistencies: &mut Vec<Inconsistency>) {
+        let mut check = |field: &'static str, in_tree: String, in_window_list: String| {
+            if in_tree != in_window_list {
+                inconsistencies.push(Inconsistency::Mismatch {
+                    window_id: tree.id().clone(),
+                    field,
+                    in_tree,
+                    in_window_list,
+                });
+            }
+        };
+
+        check(
+            "geometry",
+            tree.geometry().to_string(),
+            listed.geometry().to_string(),
+        );
+        check(
+            "state",
+            format!("{:?}", tree.state()),
+            format!("{:?}", listed.state()),
+        );
+        check(
+            "display state",
+            format!("{:?}", tree.display_state()),
+            format!("{:?}", listed.display_state()),
+        );
+        check(
+            "focus",
+            format!("{:?}", tree.focus_state()),
+            format!("{:?}", listed.focus_state()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        values::Rectangle, DisplayState, FocusState, MonitorId, NodeKind, TilingDirection,
+        WindowState, WorkspaceId,
+    };
+
+    fn create_window(id: &str, x: i32, state: WindowState) -> Window {
+        Window::new(
+            WindowId::new(id.to_string()),
+            "Title".to_string(),
+            "app".to_string(),
+            Rectangle::from_coords(x, 0, 800, 600),
+            state,
+            FocusState::Unfocused,
+            DisplayState::Shown,
+        )
+    }
+
+    fn create_monitor(windows: Vec<Window>) -> Monitor {
+        let workspace = Workspace::new(
+            WorkspaceId::new("ws-1".to_string()),
+            "1".to_string(),
+            windows,
+            TilingDirection::Horizontal,
+            FocusState::Focused,
+            DisplayState::Shown,
+        );
+
+        Monitor::new(
+            MonitorId::new("monitor-1".to_string()),
+            Rectangle::from_coords(0, 0, 1920, 1080),
+            vec![workspace],
+            FocusState::Focused,
+            96,
+            1.0,
+        )
+    }
+
+    #[test]
+    fn should_report_nothing_for_matching_sources() {
+        let window = create_window("w1", 0, WindowState::Tiling);
+        let monitors = vec![create_monitor(vec![window.clone()])];
+
+        assert!(WindowReconciler::reconcile(&monitors, &[window]).is_empty());
+    }
+
+    #[test]
+    fn should_report_windows_missing_from_either_source() {
+        let monitors = vec![create_monitor(vec![create_window(
+            "tree-only",
+            0,
+            WindowState::Tiling,
+        )])];
+        let windows = vec![create_window("list-only", 0, WindowState::Tiling)];
+
+        let inconsistencies = WindowReconciler::reconcile(&monitors, &windows);
+
+        assert_eq!(inconsistencies.len(), 2);
+        assert!(matches!(
+            &inconsistencies[0],
+            Inconsistency::MissingFromWindowList { window_id, .. } if window_id.as_str() == "tree-only"
+        ));
+        assert!(matches!(
+            &inconsistencies[1],
+            Inconsistency::MissingFromTree { window_id, .. } if window_id.as_str() == "list-only"
+        ));
+    }
+
+    #[test]
+    fn should_not_report_windows_the_tree_kept_as_placeholders() {
+        let mut workspace = Workspace::new(
+            WorkspaceId::new("ws-1".to_string()),
+            "1".to_string(),
+            Vec::new(),
+            TilingDirection::Horizontal,
+            FocusState::Focused,
+            DisplayState::Shown,
+        );
+        workspace.add_placeholder(Placeholder::new(
+            NodeKind::Window,
+            Some("broken".to_string()),
+            "missing field `width`".to_string(),
+            "{}".to_string(),
+        ));
+        let monitors = vec![Monitor::new(
+            MonitorId::new("monitor-1".to_string()),
+            Rectangle::from_coords(0, 0, 1920, 1080),
+            vec![workspace],
+            FocusState::Focused,
+            96,
+            1.0,
+        )];
+        let windows = vec![create_window("broken", 0, WindowState::Tiling)];
+
+        assert!(WindowReconciler::reconcile(&monitors, &windows).is_empty());
+    }
+
+    #[test]
+    fn should_report_mismatched_geometry_and_state() {
+        let monitors = vec![create_monitor(vec![create_window(
+            "w1",
+            0,
+            WindowState::Tiling,
+        )])];
+        let windows = vec![create_window("w1", 10, WindowState::Floating)];
+
+        let inconsistencies = WindowReconciler::reconcile(&monitors, &windows);
+
+        assert_eq!(inconsistencies.len(), 2);
+        assert_eq!(
+            inconsistencies[0].to_string(),
+            "w1 geometry differs: tree (0, 0) 800x600, window list (10, 0) 800x600"
+        );
+        assert!(matches!(
+            &inconsistencies[1],
+            Inconsistency::Mismatch { field: "state", .. }
+        ));
+    }
+}
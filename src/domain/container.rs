@@ -0,0 +1,236 @@
+// Split container implementation
+// Represents glazewm's tiling tree between a workspace and its windows
+
+use serde::Serialize;
+
+use crate::domain::{ContainerId, TilingDirection, WindowId};
+
+/// Node in a workspace's tiling tree
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutNode {
+    /// Leaf referencing a window owned by the workspace
+    Window(WindowId),
+    /// Split container grouping further nodes
+    Split(SplitContainer),
+}
+
+impl LayoutNode {
+    /// Id of the node as it appears in glazewm's `childFocusOrder`
+    pub fn id(&self) -> &str {
+        match self {
+            LayoutNode::Window(id) => id.as_str(),
+            LayoutNode::Split(container) => container.id().as_str(),
+        }
+    }
+}
+
+/// Split container arranging its children along a tiling direction
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SplitContainer {
+    id: ContainerId,
+    tiling_direction: TilingDirection,
+    children: Vec<LayoutNode>,
+    focus_order: Vec<String>,
+    /// Share of its parent's length among tiling siblings, when reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tiling_size: Option<f64>,
+}
+
+impl SplitContainer {
+    /// Create a new split container
+    pub fn new(
+        id: ContainerId,
+        tiling_direction: TilingDirection,
+        children: Vec<LayoutNode>,
+    ) -> Self {
+        Self {
+            id,
+            tiling_direction,
+            children,
+            focus_order: Vec::new(),
+            tiling_size: None,
+        }
+    }
+
+    // Getters
+    pub fn id(&self) -> &ContainerId {
+        &self.id
+    }
+
+    pub fn tiling_direction(&self) -> &TilingDirection {
+        &self.tiling_direction
+    }
+
+    pub fn children(&self) -> &[LayoutNode] {
+        &self.children
+    }
+
+    /// Child ids, most recently focused first
+    pub fn focus_order(&self) -> &[String] {
+        &self.focus_order
+    }
+
+    pub fn tiling_size(&self) -> Option<f64> {
+        self.tiling_size
+    }
+
+    pub fn set_focus_order(&mut self, focus_order: Vec<String>) {
+        self.focus_order = focus_order;
+    }
+
+    pub fn set_tiling_size(&mut self, tiling_size: f64) {
+        self.tiling_size = Some(tiling_size);
+    }
+
+    /// Remove a window leaf anywhere below this container
+    pub(crate) fn remove_window(&mut self, window_id: &WindowId) {
+        remove_window(&mut self.children, window_id);
+    }
+}
+
+/// Order nodes by a focus order list; nodes missing from it keep their
+/// relative order and come last
+pub fn order_by_focus<'a>(nodes: &'a [LayoutNode], focus_order: &[String]) -> Vec<&'a LayoutNode> {
+    let mut ordered: Vec<&LayoutNode> = nodes.iter().collect();
+    ordered.sort_by_key(|node| {
+        focus_order
+            .iter()
+            .position(|id| id == node.id())
+            .unwrap_or(usize::MAX)
+    });
+    ordered
+}
+
+/// Collect window ids below `nodes`, most recently focused first
+///
+/// Follows each level's focus order depth-first, like glazewm's
+/// descendant focus order.
+pub fn windows_by_focus<'a>(
+    nodes: &'a [LayoutNode],
+    focus_order: &[String],
+    out: &mut Vec<&'a WindowId>,
+) {
+    for node in order_by_focus(nodes, focus_order) {
+        match node {
+            LayoutNode::Window(id) => out.push(id),
+            LayoutNode::Split(container) => {
+                windows_by_focus(container.children(), container.focus_order(), out)
+            }
+        }
+    }
+}
+
+/// Split containers on the path from `nodes` down to a window, outermost first
+pub fn path_to_window<'a>(
+    nodes: &'a [LayoutNode],
+    window_id: &WindowId,
+) -> Option<Vec<&'a SplitContainer>> {
+    for node in nodes {
+        match node {
+            LayoutNode::Window(id) if id == window_id => return Some(Vec::new()),
+            LayoutNode::Window(_) => {}
+            LayoutNode::Split(container) => {
+                if let Some(mut path) = path_to_window(container.children(), window_id) {
+                    path.insert(0, container);
+                    return Some(path);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Remove a window leaf anywhere in `nodes`
+pub(crate) fn remove_window(nodes: &mut Vec<LayoutNode>, window_id: &WindowId) {
+    nodes.retain(|node| !matches!(node, LayoutNode::Window(id) if id == window_id));
+    for node in nodes.iter_mut() {
+        if let LayoutNode::Split(container) = node {
+            container.remove_window(window_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(id: &str) -> LayoutNode {
+        LayoutNode::Window(WindowId::new(id.to_string()))
+    }
+
+    fn split(id: &str, children: Vec<LayoutNode>, focus_order: &[&str]) -> SplitContainer {
+        let mut container = SplitContainer::new(
+            ContainerId::new(id.to_string()),
+            TilingDirection::Vertical,
+            children,
+        );
+        container.set_focus_order(focus_order.iter().map(|id| id.to_string()).collect());
+        container
+    }
+
+    #[test]
+    fn should_order_windows_by_nested_focus_order() {
+        let nodes = vec![
+            window("w1"),
+            LayoutNode::Split(split("c1", vec![window("w2"), window("w3")], &["w3", "w2"])),
+        ];
+        let focus_order = vec!["c1".to_string(), "w1".to_string()];
+
+        let mut ordered = Vec::new();
+        windows_by_focus(&nodes, &focus_order, &mut ordered);
+
+        let ids: Vec<&str> = ordered.iter().map(|id| id.as_str()).collect();
+        assert_eq!(ids, vec!["w3", "w2", "w1"]);
+    }
+
+    #[test]
+    fn should_keep_unordered_nodes_last() {
+        let nodes = vec![window("w1"), window("w2"), window("w3")];
+        let focus_order = vec!["w3".to_string()];
+
+        let ordered: Vec<&str> = order_by_focus(&nodes, &focus_order)
+            .iter()
+            .map(|node| node.id())
+            .collect();
+
+        assert_eq!(ordered, vec!["w3", "w1", "w2"]);
+    }
+
+    #[test]
+    fn should_find_container_path_to_window() {
+        let inner = split("c2", vec![window("w3")], &[]);
+        let nodes = vec![
+            window("w1"),
+            LayoutNode::Split(split(
+                "c1",
+                vec![window("w2"), LayoutNode::Split(inner)],
+                &[],
+            )),
+        ];
+
+        let path = path_to_window(&nodes, &WindowId::new("w3".to_string())).unwrap();
+        let ids: Vec<&str> = path.iter().map(|c| c.id().as_str()).collect();
+        assert_eq!(ids, vec!["c1", "c2"]);
+
+        assert!(path_to_window(&nodes, &WindowId::new("w1".to_string()))
+            .unwrap()
+            .is_empty());
+        assert!(path_to_window(&nodes, &WindowId::new("missing".to_string())).is_none());
+    }
+
+    #[test]
+    fn should_remove_nested_window() {
+        let mut nodes = vec![
+            window("w1"),
+            LayoutNode::Split(split("c1", vec![window("w2")], &[])),
+        ];
+
+        remove_window(&mut nodes, &WindowId::new("w2".to_string()));
+
+        match &nodes[1] {
+            LayoutNode::Split(container) => assert!(container.children().is_empty()),
+            LayoutNode::Window(_) => panic!("expected split container"),
+        }
+    }
+}
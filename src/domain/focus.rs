@@ -0,0 +1,155 @@
+// Focus stack
+// The chain of containers leading to the focused window, and where focus
+// would go if that window closed
+
+use crate::domain::{Monitor, SplitContainer, Window, Workspace};
+
+/// Full focus path through the tree, plus the fallback order glazewm would use
+#[derive(Debug, Clone)]
+pub struct FocusStack<'a> {
+    monitor: &'a Monitor,
+    workspace: Option<&'a Workspace>,
+    containers: Vec<&'a SplitContainer>,
+    window: Option<&'a Window>,
+    fallback: Vec<&'a Window>,
+}
+
+impl<'a> FocusStack<'a> {
+    /// Build the focus stack for the focused monitor, if any
+    pub fn from_monitors(monitors: &'a [Monitor]) -> Option<Self> {
+        let monitor = monitors.iter().find(|monitor| monitor.is_focused())?;
+        let workspace = monitor
+            .active_workspaces()
+            .first()
+            .copied()
+            .or_else(|| monitor.workspaces_by_focus_order().first().copied());
+        let window = workspace.and_then(|ws| ws.focused_window());
+
+        let (containers, fallback) = match (workspace, window) {
+            (Some(ws), Some(window)) => (
+                ws.container_path(window.id()),
+                ws.focus_fallback_order(window.id()),
+            ),
+            _ => (Vec::new(), Vec::new()),
+        };
+
+        Some(Self {
+            monitor,
+            workspace,
+            containers,
+            window,
+            fallback,
+        })
+    }
+
+    // Getters
+    pub fn monitor(&self) -> &'a Monitor {
+        self.monitor
+    }
+
+    pub fn workspace(&self) -> Option<&'a Workspace> {
+        self.workspace
+    }
+
+    /// Split containers between the workspace and the window, outermost first
+    pub fn containers(&self) -> &[&'a SplitContainer] {
+        &self.containers
+    }
+
+    pub fn window(&self) -> Option<&'a Window> {
+        self.window
+    }
+
+    /// Windows that would receive focus if the focused window closed, in order
+    pub fn fallback(&self) -> &[&'a Window] {
+        &self.fallback
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        values::Rectangle, ContainerId, DisplayState, FocusState, LayoutNode, MonitorId,
+        TilingDirection, WindowId, WindowState, WorkspaceId,
+    };
+
+    fn create_window(id: &str, focus_state: FocusState) -> Window {
+        Window::new(
+            WindowId::new(id.to_string()),
+            "Title".to_string(),
+            "app".to_string(),
+            Rectangle::from_coords(0, 0, 800, 600),
+            WindowState::Tiling,
+            focus_state,
+            DisplayState::Shown,
+        )
+    }
+
+    fn create_monitor(workspace: Workspace, focus_state: FocusState) -> Monitor {
+        Monitor::new(
+            MonitorId::new("monitor-1".to_string()),
+            Rectangle::from_coords(0, 0, 1920, 1080),
+            vec![workspace],
+            focus_state,
+            96,
+            1.0,
+        )
+    }
+
+    #[test]
+    fn should_build_path_and_fallback_through_split_containers() {
+        let mut workspace = Workspace::new(
+            WorkspaceId::new("ws-1".to_string()),
+            "1".to_string(),
+            vec![
+                create_window("w1", FocusState::Unfocused),
+                create_window("w2", FocusState::Focused),
+                create_window("w3", FocusState::Unfocused),
+            ],
+            TilingDirection::Horizontal,
+            FocusState::Focused,
+            DisplayState::Shown,
+        );
+        let mut split = SplitContainer::new(
+            ContainerId::new("c1".to_string()),
+            TilingDirection::Vertical,
+            vec![
+                LayoutNode::Window(WindowId::new("w2".to_string())),
+                LayoutNode::Window(WindowId::new("w3".to_string())),
+            ],
+        );
+        split.set_focus_order(vec!["w2".to_string(), "w3".to_string()]);
+        workspace.set_layout(vec![
+            LayoutNode::Window(WindowId::new("w1".to_string())),
+            LayoutNode::Split(split),
+        ]);
+        workspace.set_focus_order(vec!["c1".to_string(), "w1".to_string()]);
+
+        let monitors = vec![create_monitor(workspace, FocusState::Focused)];
+        let stack = FocusStack::from_monitors(&monitors).unwrap();
+
+        assert_eq!(stack.workspace().unwrap().name(), "1");
+        assert_eq!(stack.containers().len(), 1);
+        assert_eq!(stack.containers()[0].id().as_str(), "c1");
+        assert_eq!(stack.window().unwrap().id().as_str(), "w2");
+
+        let fallback: Vec<&str> = stack.fallback().iter().map(|w| w.id().as_str()).collect();
+        assert_eq!(fallback, vec!["w3", "w1"]);
+    }
+
+    #[test]
+    fn should_return_none_without_focused_monitor() {
+        let workspace = Workspace::new(
+            WorkspaceId::new("ws-1".to_string()),
+            "1".to_string(),
+            vec![],
+            TilingDirection::Horizontal,
+            FocusState::Unfocused,
+            DisplayState::Shown,
+        );
+        let monitors = vec![create_monitor(workspace, FocusState::Unfocused)];
+
+        assert!(FocusStack::from_monitors(&monitors).is_none());
+    }
+}
@@ -0,0 +1,479 @@
+// Geometry analysis
+// Answers "where did my window go": overlapping tiles, floating windows
+// hiding tiled ones, windows off every monitor or straddling two, and how
+// much of each window can actually be seen
+
+use serde::Serialize;
+use std::fmt;
+
+use crate::domain::{Monitor, MonitorId, Rectangle, Window, WindowId, WindowState};
+
+/// A window placed where it is likely to be lost or hidden
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum GeometryIssue {
+    /// Window sharing area with a tiled window
+    Overlap {
+        window_id: WindowId,
+        display_name: String,
+        other_id: WindowId,
+        other_name: String,
+        overlap: Rectangle,
+    },
+    /// Floating or fullscreen window hiding all of a tiled window
+    CoversTiled {
+        window_id: WindowId,
+        display_name: String,
+        other_id: WindowId,
+        other_name: String,
+    },
+    /// Window outside every monitor
+    Offscreen {
+        window_id: WindowId,
+        display_name: String,
+        geometry: Rectangle,
+    },
+    /// Window partly outside every monitor
+    PartlyOffscreen {
+        window_id: WindowId,
+        display_name: String,
+        on_screen_percent: f64,
+    },
+    /// Window spread over more than one monitor
+    StraddlesMonitors {
+        window_id: WindowId,
+        display_name: String,
+        monitors: Vec<MonitorId>,
+    },
+}
+
+impl GeometryIssue {
+    /// Id of the window the issue refers to
+    pub fn window_id(&self) -> &WindowId {
+        match self {
+            GeometryIssue::Overlap { window_id, .. }
+            | GeometryIssue::CoversTiled { window_id, .. }
+            | GeometryIssue::Offscreen { window_id, .. }
+            | GeometryIssue::PartlyOffscreen { window_id, .. }
+            | GeometryIssue::StraddlesMonitors { window_id, .. } => window_id,
+        }
+    }
+}
+
+impl fmt::Display for GeometryIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeometryIssue::Overlap {
+                window_id,
+                display_name,
+                other_id,
+                other_name,
+                overlap,
+            } => write!(
+                f,
+                "{} ({}) overlaps tiled {} ({}) at {}",
+                window_id, display_name, other_id, other_name, overlap
+            ),
+            GeometryIssue::CoversTiled {
+                window_id,
+                display_name,
+                other_id,
+                other_name,
+            } => write!(
+                f,
+                "{} ({}) covers all of tiled {} ({})",
+                window_id, display_name, other_id, other_name
+            ),
+            GeometryIssue::Offscreen {
+                window_id,
+                display_name,
+                geometry,
+            } => write!(
+                f,
+                "{} ({}) is off every monitor at {}",
+                window_id, display_name, geometry
+            ),
+            GeometryIssue::PartlyOffscreen {
+                window_id,
+                display_name,
+                on_screen_percent,
+            } => write!(
+                f,
+                "{} ({}) is only {:.0}% on screen",
+                window_id, display_name, on_screen_percent
+            ),
+            GeometryIssue::StraddlesMonitors {
+                window_id,
+                display_name,
+                monitors,
+            } => {
+                let monitors: Vec<String> = monitors.iter().map(ToString::to_string).collect();
+                write!(
+                    f,
+                    "{} ({}) straddles monitors {}",
+                    window_id,
+                    display_name,
+                    monitors.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// Share of a window that is on a monitor and not hidden by floating or
+/// fullscreen windows
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowVisibility {
+    pub window_id: WindowId,
+    pub visible_percent: f64,
+}
+
+/// Outcome of one analysis pass
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GeometryReport {
+    pub issues: Vec<GeometryIssue>,
+    /// One entry per shown window with a non-empty geometry
+    pub visibility: Vec<WindowVisibility>,
+}
+
+impl GeometryReport {
+    /// Visible share of a window, if it was analyzed
+    pub fn visible_percent(&self, window_id: &WindowId) -> Option<f64> {
+        self.visibility
+            .iter()
+            .find(|visibility| &visibility.window_id == window_id)
+            .map(|visibility| visibility.visible_percent)
+    }
+}
+
+/// Checks where shown windows are relative to monitors and each other
+pub struct GeometryAnalyzer;
+
+impl GeometryAnalyzer {
+    /// Analyze the windows on displayed workspaces; hidden and minimized
+    /// windows are parked off-screen by glazewm on purpose
+    pub fn analyze(monitors: &[Monitor]) -> GeometryReport {
+        let windows: Vec<&Window> = monitors
+            .iter()
+            .flat_map(Monitor::all_windows)
+            .filter(|window| window.is_visible() && *window.state() != WindowState::Minimized)
+            .filter(|window| window.geometry().area() > 0)
+            .collect();
+        let tiled: Vec<&Window> = windows
+            .iter()
+            .copied()
+            .filter(|window| *window.state() == WindowState::Tiling)
+            .collect();
+        let above: Vec<&Window> = windows
+            .iter()
+            .copied()
+            .filter(|window| {
+                matches!(
+                    window.state(),
+                    WindowState::Floating | WindowState::Fullscreen
+                )
+            })
+            .collect();
+
+        let mut report = GeometryReport::default();
+
+        // Tiles should never share area; report each pair once
+        for (index, window) in tiled.iter().enumerate() {
+            for other in &tiled[index + 1..] {
+                if let Some(overlap) = window.geometry().intersection(other.geometry()) {
+                    report.issues.push(overlap_issue(window, other, overlap));
+                }
+            }
+        }
+
+        for window in &above {
+            for other in &tiled {
+                if window.geometry().contains(other.geometry()) {
+                    report.issues.push(GeometryIssue::CoversTiled {
+                        window_id: window.id().clone(),
+                        display_name: window.display_name(),
+                        other_id: other.id().clone(),
+                        other_name: other.display_name(),
+                    });
+                } else if let Some(overlap) = window.geometry().intersection(other.geometry()) {
+                    report.issues.push(overlap_issue(window, other, overlap));
+                }
+            }
+        }
+
+        for window in &windows {
+            let geometry = window.geometry();
+            let on_monitors: Vec<(&MonitorId, Rectangle)> = monitors
+                .iter()
+                .filter_map(|monitor| {
+                    geometry
+                        .intersection(monitor.geometry())
+                        .map(|part| (monitor.id(), part))
+                })
+                .collect();
+            let parts: Vec<Rectangle> = on_monitors.iter().map(|(_, part)| *part).collect();
+
+            let on_screen = percent(covered_area(geometry, &parts), geometry.area());
+            if on_monitors.is_empty() {
+                report.issues.push(GeometryIssue::Offscreen {
+                    window_id: window.id().clone(),
+                    display_name: window.display_name(),
+                    geometry: *geometry,
+                });
+            } else if on_screen < 100.0 {
+                report.issues.push(GeometryIssue::PartlyOffscreen {
+                    window_id: window.id().clone(),
+                    display_name: window.display_name(),
+                    on_screen_percent: on_screen,
+                });
+            }
+            if on_monitors.len() > 1 {
+                report.issues.push(GeometryIssue::StraddlesMonitors {
+                    window_id: window.id().clone(),
+                    display_name: window.display_name(),
+                    monitors: on_monitors.iter().map(|(id, _)| (*id).clone()).collect(),
+                });
+            }
+
+            // Floating and fullscreen windows stay above tiled ones; their
+            // order among each other is unknown, so they only lose area to
+            // the monitor edges
+            let hidden_by: Vec<Rectangle> = if *window.state() == WindowState::Tiling {
+                above
+                    .iter()
+                    .filter_map(|other| geometry.intersection(other.geometry()))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            report.visibility.push(WindowVisibility {
+                window_id: window.id().clone(),
+                visible_percent: percent(
+                    visible_area(geometry, &parts, &hidden_by),
+                    geometry.area(),
+                ),
+            });
+        }
+
+        report
+    }
+}
+
+fn overlap_issue(window: &Window, other: &Window, overlap: Rectangle) -> GeometryIssue {
+    GeometryIssue::Overlap {
+        window_id: window.id().clone(),
+        display_name: window.display_name(),
+        other_id: other.id().clone(),
+        other_name: other.display_name(),
+        overlap,
+    }
+}
+
+fn percent(part: u64, whole: u64) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / whole as f64
+    }
+}
+
+/// Area of `bounds` covered by any of `rects`
+fn covered_area(bounds: &Rectangle, rects: &[Rectangle]) -> u64 {
+    visible_area(bounds, rects, &[])
+}
+
+/// Area of `bounds` inside one of `shown` and outside all of `hidden`.
+/// The rectangles may overlap, so the area is summed over the grid their
+/// edges cut `bounds` into.
+fn visible_area(bounds: &Rectangle, shown: &[Rectangle], hidden: &[Rectangle]) -> u64 {
+    let edges = |start: fn(&Rectangle) -> i32, end: fn(&Rectangle) -> i32| {
+        let mut edges: Vec<i32> = std::iter::once(bounds)
+            .chain(shown)
+            .chain(hidden)
+            .flat_map(|rect| {
+                let clipped = rect.clip(bounds);
+                [start(&clipped), end(&clipped)]
+            })
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    };
+    let xs = edges(|rect| rect.position.x, Rectangle::right);
+    let ys = edges(|rect| rect.position.y, Rectangle::bottom);
+
+    let mut area = 0;
+    for x in xs.windows(2) {
+        for y in ys.windows(2) {
+            let cell =
+                Rectangle::from_coords(x[0], y[0], (x[1] - x[0]) as u32, (y[1] - y[0]) as u32);
+            let inside = |rect: &Rectangle| rect.contains(&cell);
+            if shown.iter().any(inside) && !hidden.iter().any(inside) {
+                area += cell.area();
+            }
+        }
+    }
+    area
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{DisplayState, FocusState, TilingDirection, Workspace, WorkspaceId};
+
+    fn window(id: &str, geometry: Rectangle, state: WindowState) -> Window {
+        Window::new(
+            WindowId::new(id.to_string()),
+            id.to_string(),
+            "app".to_string(),
+            geometry,
+            state,
+            FocusState::Unfocused,
+            DisplayState::Shown,
+        )
+    }
+
+    fn monitor(id: &str, geometry: Rectangle, windows: Vec<Window>) -> Monitor {
+        let workspace = Workspace::new(
+            WorkspaceId::new(format!("{}-ws", id)),
+            "1".to_string(),
+            windows,
+            TilingDirection::Horizontal,
+            FocusState::Unfocused,
+            DisplayState::Shown,
+        );
+        Monitor::new(
+            MonitorId::new(id.to_string()),
+            geometry,
+            vec![workspace],
+            FocusState::Unfocused,
+            96,
+            1.0,
+        )
+    }
+
+    fn kinds(report: &GeometryReport) -> Vec<(String, &str)> {
+        report
+            .issues
+            .iter()
+            .map(|issue| {
+                let kind = match issue {
+                    GeometryIssue::Overlap { .. } => "overlap",
+                    GeometryIssue::CoversTiled { .. } => "covers",
+                    GeometryIssue::Offscreen { .. } => "offscreen",
+                    GeometryIssue::PartlyOffscreen { .. } => "partly",
+                    GeometryIssue::StraddlesMonitors { .. } => "straddles",
+                };
+                (issue.window_id().to_string(), kind)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_report_overlapping_and_covered_tiles() {
+        let monitors = vec![monitor(
+            "m1",
+            Rectangle::from_coords(0, 0, 1920, 1080),
+            vec![
+                window(
+                    "t1",
+                    Rectangle::from_coords(0, 0, 1000, 1080),
+                    WindowState::Tiling,
+                ),
+                window(
+                    "t2",
+                    Rectangle::from_coords(960, 0, 960, 1080),
+                    WindowState::Tiling,
+                ),
+                window(
+                    "f1",
+                    Rectangle::from_coords(900, -10, 1100, 1100),
+                    WindowState::Floating,
+                ),
+                window(
+                    "min",
+                    Rectangle::from_coords(0, 0, 1920, 1080),
+                    WindowState::Minimized,
+                ),
+            ],
+        )];
+
+        let report = GeometryAnalyzer::analyze(&monitors);
+
+        assert_eq!(
+            kinds(&report),
+            vec![
+                ("t1".to_string(), "overlap"),
+                ("f1".to_string(), "overlap"),
+                ("f1".to_string(), "covers"),
+                ("f1".to_string(), "partly"),
+            ]
+        );
+        assert_eq!(
+            report.issues[0].to_string(),
+            "t1 (app: t1) overlaps tiled t2 (app: t2) at (960, 0) 40x1080"
+        );
+        // t1 keeps the 900px left of the floating window, t2 nothing
+        assert_eq!(
+            report.visible_percent(&WindowId::new("t1".to_string())),
+            Some(90.0)
+        );
+        assert_eq!(
+            report.visible_percent(&WindowId::new("t2".to_string())),
+            Some(0.0)
+        );
+        assert_eq!(
+            report.visible_percent(&WindowId::new("min".to_string())),
+            None
+        );
+    }
+
+    #[test]
+    fn should_report_offscreen_and_straddling_windows() {
+        let monitors = vec![
+            monitor(
+                "m1",
+                Rectangle::from_coords(0, 0, 1920, 1080),
+                vec![
+                    window(
+                        "lost",
+                        Rectangle::from_coords(-32000, -32000, 160, 28),
+                        WindowState::Floating,
+                    ),
+                    window(
+                        "wide",
+                        Rectangle::from_coords(1420, 0, 1000, 1000),
+                        WindowState::Floating,
+                    ),
+                ],
+            ),
+            monitor(
+                "m2",
+                Rectangle::from_coords(1920, 0, 1920, 1080),
+                Vec::new(),
+            ),
+        ];
+
+        let report = GeometryAnalyzer::analyze(&monitors);
+
+        assert_eq!(
+            kinds(&report),
+            vec![
+                ("lost".to_string(), "offscreen"),
+                ("wide".to_string(), "straddles")
+            ]
+        );
+        assert_eq!(
+            report.issues[1].to_string(),
+            "wide (app: wide) straddles monitors m1, m2"
+        );
+        assert_eq!(
+            report.visible_percent(&WindowId::new("lost".to_string())),
+            Some(0.0)
+        );
+        assert_eq!(
+            report.visible_percent(&WindowId::new("wide".to_string())),
+            Some(100.0)
+        );
+    }
+}
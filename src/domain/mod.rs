@@ -1,21 +1,43 @@
 // Domain layer module
 // Contains pure business logic with no external dependencies
 
+pub mod changes;
+pub mod consistency;
+pub mod container;
 pub mod errors;
+pub mod focus;
+pub mod geometry;
 pub mod monitor;
+pub mod placeholder;
+pub mod snapshot_diff;
+pub mod tiling;
 pub mod values;
 pub mod window;
 pub mod workspace;
 
+use serde::Serialize;
+use std::fmt;
+
 // Re-export public types
+pub use changes::Change;
+pub use consistency::{Inconsistency, WindowReconciler};
+pub use container::{LayoutNode, SplitContainer};
 pub use errors::DomainError;
+pub use focus::FocusStack;
+pub use geometry::{GeometryAnalyzer, GeometryIssue, GeometryReport, WindowVisibility};
 pub use monitor::Monitor;
-pub use values::{MonitorId, Position, Rectangle, Size, WindowId, WorkspaceId};
+pub use placeholder::{NodeKind, Placeholder};
+pub use snapshot_diff::SnapshotDiff;
+pub use tiling::{Gaps, LayoutDeviation, TilingModel};
+pub use values::{
+    ContainerId, Insets, MonitorId, Position, Rectangle, Size, WindowId, WorkspaceId,
+};
 pub use window::Window;
 pub use workspace::Workspace;
 
 /// Focus state of windows, workspaces, and monitors
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FocusState {
     /// Currently has focus (receiving input)
     Focused,
@@ -30,7 +52,8 @@ impl FocusState {
 }
 
 /// Window state in the tiling system
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum WindowState {
     /// Window is part of tiling layout
     Tiling,
@@ -40,10 +63,25 @@ pub enum WindowState {
     Minimized,
     /// Window is fullscreen
     Fullscreen,
+    /// State reported by glazewm that this debugger does not recognize
+    Unknown(String),
+}
+
+impl fmt::Display for WindowState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowState::Tiling => write!(f, "tiling"),
+            WindowState::Floating => write!(f, "floating"),
+            WindowState::Minimized => write!(f, "minimized"),
+            WindowState::Fullscreen => write!(f, "fullscreen"),
+            WindowState::Unknown(value) => write!(f, "{}", value),
+        }
+    }
 }
 
 /// Display state of windows and workspaces
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DisplayState {
     /// Currently visible on screen
     Shown,
@@ -51,25 +89,46 @@ pub enum DisplayState {
     Hidden,
     /// Transitioning to hidden state
     Hiding,
+    /// Display state reported by glazewm that this debugger does not recognize
+    Unknown(String),
 }
 
 impl DisplayState {
-    pub fn is_visible(self) -> bool {
+    pub fn is_visible(&self) -> bool {
         matches!(self, DisplayState::Shown)
     }
 }
 
+impl fmt::Display for DisplayState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplayState::Shown => write!(f, "shown"),
+            DisplayState::Hidden => write!(f, "hidden"),
+            DisplayState::Hiding => write!(f, "hiding"),
+            DisplayState::Unknown(value) => write!(f, "{}", value),
+        }
+    }
+}
+
 /// Tiling direction for workspace layout
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TilingDirection {
     /// Windows arranged side-by-side
+    #[default]
     Horizontal,
     /// Windows arranged vertically stacked
     Vertical,
+    /// Direction reported by glazewm that this debugger does not recognize
+    Unknown(String),
 }
 
-impl Default for TilingDirection {
-    fn default() -> Self {
-        TilingDirection::Horizontal
+impl fmt::Display for TilingDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TilingDirection::Horizontal => write!(f, "horizontal"),
+            TilingDirection::Vertical => write!(f, "vertical"),
+            TilingDirection::Unknown(value) => write!(f, "{}", value),
+        }
     }
 }
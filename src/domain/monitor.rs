@@ -1,15 +1,22 @@
 // Monitor aggregate root implementation
 // Represents a physical monitor containing workspaces
 
-use crate::domain::values::Rectangle;
-use crate::domain::{DomainError, FocusState, MonitorId, Window, Workspace, WorkspaceId};
+use serde::Serialize;
+
+use crate::domain::values::{Insets, Rectangle};
+use crate::domain::{
+    DomainError, FocusState, MonitorId, Placeholder, Window, Workspace, WorkspaceId,
+};
 
 /// Device information for a monitor
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct DeviceInfo {
     pub dpi: i32,
     pub scale_factor: f64,
     pub device_name: String,
+    pub device_path: Option<String>,
+    pub hardware_id: Option<String>,
+    pub handle: Option<i64>,
 }
 
 impl DeviceInfo {
@@ -18,18 +25,24 @@ impl DeviceInfo {
             dpi,
             scale_factor,
             device_name,
+            device_path: None,
+            hardware_id: None,
+            handle: None,
         }
     }
 }
 
 /// Monitor aggregate root containing workspaces and windows
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Monitor {
     id: MonitorId,
     geometry: Rectangle,
     workspaces: Vec<Workspace>,
     focus_state: FocusState,
     device_info: DeviceInfo,
+    working_area: Option<Rectangle>,
+    focus_order: Vec<String>,
+    placeholders: Vec<Placeholder>,
 }
 
 impl Monitor {
@@ -50,6 +63,9 @@ impl Monitor {
             workspaces,
             focus_state,
             device_info,
+            working_area: None,
+            focus_order: Vec::new(),
+            placeholders: Vec::new(),
         }
     }
 
@@ -121,6 +137,36 @@ impl Monitor {
         &self.device_info.device_name
     }
 
+    pub fn device_info(&self) -> &DeviceInfo {
+        &self.device_info
+    }
+
+    pub fn hardware_id(&self) -> Option<&str> {
+        self.device_info.hardware_id.as_deref()
+    }
+
+    /// Area available to windows, excluding space reserved by the taskbar
+    pub fn working_area(&self) -> Option<&Rectangle> {
+        self.working_area.as_ref()
+    }
+
+    /// Space reserved at each edge of the monitor (e.g. by the taskbar)
+    pub fn reserved_insets(&self) -> Option<Insets> {
+        self.working_area
+            .as_ref()
+            .map(|working_area| self.geometry.insets_to(working_area))
+    }
+
+    /// Workspace ids, most recently focused first
+    pub fn focus_order(&self) -> &[String] {
+        &self.focus_order
+    }
+
+    /// Workspaces glazewm reported on this monitor that could not be parsed
+    pub fn placeholders(&self) -> &[Placeholder] {
+        &self.placeholders
+    }
+
     // Computed properties
     pub fn is_focused(&self) -> bool {
         self.focus_state.is_focused()
@@ -134,6 +180,16 @@ impl Monitor {
         self.workspaces.iter().map(|ws| ws.window_count()).sum()
     }
 
+    /// Count invalid nodes on this monitor, including those inside workspaces
+    pub fn total_placeholder_count(&self) -> usize {
+        self.placeholders.len()
+            + self
+                .workspaces
+                .iter()
+                .map(|ws| ws.placeholders().len())
+                .sum::<usize>()
+    }
+
     /// Get all active workspaces on this monitor
     pub fn active_workspaces(&self) -> Vec<&Workspace> {
         self.workspaces
@@ -142,6 +198,25 @@ impl Monitor {
             .collect()
     }
 
+    /// Workspaces, most recently focused first; unordered ones come last
+    pub fn workspaces_by_focus_order(&self) -> Vec<&Workspace> {
+        let mut ordered: Vec<&Workspace> = self.workspaces.iter().collect();
+        ordered.sort_by_key(|workspace| {
+            self.focus_order
+                .iter()
+                .position(|id| id == workspace.id().as_str())
+                .unwrap_or(usize::MAX)
+        });
+        ordered
+    }
+
+    /// Iterate over every window in every workspace on this monitor
+    pub fn all_windows(&self) -> impl Iterator<Item = &Window> {
+        self.workspaces
+            .iter()
+            .flat_map(|workspace| workspace.windows())
+    }
+
     /// Find the focused window across all workspaces on this monitor
     pub fn focused_window(&self) -> Option<&Window> {
         self.workspaces
@@ -182,6 +257,11 @@ impl Monitor {
         Ok(self.workspaces.remove(position))
     }
 
+    /// Record a workspace that could not be parsed
+    pub fn add_placeholder(&mut self, placeholder: Placeholder) {
+        self.placeholders.push(placeholder);
+    }
+
     /// Deactivate all workspaces (used when adding new active workspace)
     fn deactivate_all_workspaces(&mut self) {
         for workspace in &mut self.workspaces {
@@ -192,6 +272,18 @@ impl Monitor {
     pub fn set_focus_state(&mut self, focus_state: FocusState) {
         self.focus_state = focus_state;
     }
+
+    pub fn set_device_info(&mut self, device_info: DeviceInfo) {
+        self.device_info = device_info;
+    }
+
+    pub fn set_working_area(&mut self, working_area: Rectangle) {
+        self.working_area = Some(working_area);
+    }
+
+    pub fn set_focus_order(&mut self, focus_order: Vec<String>) {
+        self.focus_order = focus_order;
+    }
 }
 
 #[cfg(test)]
@@ -259,6 +351,26 @@ mod tests {
         assert_eq!(monitor.scale_factor(), 1.0);
     }
 
+    #[test]
+    fn should_report_reserved_insets_from_working_area() {
+        let mut monitor = Monitor::new(
+            MonitorId::new("monitor-1".to_string()),
+            Rectangle::from_coords(0, 0, 1920, 1080),
+            Vec::new(),
+            FocusState::Focused,
+            96,
+            1.0,
+        );
+
+        assert!(monitor.reserved_insets().is_none());
+
+        monitor.set_working_area(Rectangle::from_coords(0, 0, 1920, 1040));
+
+        let insets = monitor.reserved_insets().unwrap();
+        assert_eq!(insets.bottom, 40);
+        assert_eq!(insets.top, 0);
+    }
+
     #[test]
     fn should_validate_geometry() {
         let result = Monitor::try_new(
@@ -349,6 +461,30 @@ mod tests {
         assert_eq!(focused.unwrap().title(), "VS Code");
     }
 
+    #[test]
+    fn should_order_workspaces_by_focus() {
+        let mut monitor = Monitor::new(
+            MonitorId::new("monitor-1".to_string()),
+            Rectangle::from_coords(0, 0, 1920, 1080),
+            vec![
+                create_test_workspace("ws-1", "1", false),
+                create_test_workspace("ws-2", "2", true),
+                create_test_workspace("ws-3", "3", false),
+            ],
+            FocusState::Focused,
+            96,
+            1.0,
+        );
+        monitor.set_focus_order(vec!["ws-2".to_string(), "ws-3".to_string()]);
+
+        let names: Vec<&str> = monitor
+            .workspaces_by_focus_order()
+            .iter()
+            .map(|ws| ws.name())
+            .collect();
+        assert_eq!(names, vec!["2", "3", "1"]);
+    }
+
     #[test]
     fn should_calculate_total_window_count() {
         let ws1_windows = vec![
@@ -0,0 +1,98 @@
+// Placeholder for invalid nodes
+// Stands in for a monitor, workspace or window that glazewm reported but
+// that could not be turned into a domain entity
+
+use serde::Serialize;
+use std::fmt;
+
+/// Kind of node in glazewm's container tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Monitor,
+    Workspace,
+    Split,
+    Window,
+}
+
+impl fmt::Display for NodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NodeKind::Monitor => "monitor",
+            NodeKind::Workspace => "workspace",
+            NodeKind::Split => "split",
+            NodeKind::Window => "window",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A node that failed to parse or validate, kept so it can still be inspected
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Placeholder {
+    kind: NodeKind,
+    id: Option<String>,
+    error: String,
+    raw_json: String,
+}
+
+impl Placeholder {
+    /// Create a placeholder for a node that could not be converted
+    pub fn new(kind: NodeKind, id: Option<String>, error: String, raw_json: String) -> Self {
+        Self {
+            kind,
+            id,
+            error,
+            raw_json,
+        }
+    }
+
+    // Getters
+    pub fn kind(&self) -> NodeKind {
+        self.kind
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+
+    pub fn raw_json(&self) -> &str {
+        &self.raw_json
+    }
+
+    /// Generate display name for UI (kind and id, if glazewm sent one)
+    pub fn display_name(&self) -> String {
+        match &self.id {
+            Some(id) => format!("{} {}", self.kind, id),
+            None => format!("{} <no id>", self.kind),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_display_placeholder_name() {
+        let with_id = Placeholder::new(
+            NodeKind::Window,
+            Some("window-1".to_string()),
+            "missing field `title`".to_string(),
+            "{}".to_string(),
+        );
+        let without_id = Placeholder::new(
+            NodeKind::Monitor,
+            None,
+            "missing field `id`".to_string(),
+            "{}".to_string(),
+        );
+
+        assert_eq!(with_id.display_name(), "window window-1");
+        assert_eq!(without_id.display_name(), "monitor <no id>");
+    }
+}
@@ -0,0 +1,681 @@
+// Snapshot diff
+// Structural comparison of two captures of the monitor tree, e.g. the
+// "before" and "after" attached to an issue. Unlike `changes::compare`, the
+// captures may come from different glazewm sessions with different ids, so
+// nodes without a common id are matched by weaker keys.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+use crate::domain::{Monitor, MonitorId, Window, WindowId, Workspace};
+
+/// Which key paired a node of the second capture with one of the first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchedBy {
+    Id,
+    /// Monitor hardware id
+    HardwareId,
+    /// Monitor device name
+    DeviceName,
+    /// Workspace name
+    Name,
+    /// Native window handle
+    Handle,
+    /// Window process name and title
+    ProcessAndTitle,
+}
+
+impl fmt::Display for MatchedBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = match self {
+            MatchedBy::Id => "id",
+            MatchedBy::HardwareId => "hardware id",
+            MatchedBy::DeviceName => "device name",
+            MatchedBy::Name => "name",
+            MatchedBy::Handle => "handle",
+            MatchedBy::ProcessAndTitle => "process and title",
+        };
+        write!(f, "{}", key)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    Added,
+    Removed,
+    /// On another parent; may also have changed
+    Moved,
+    Changed,
+}
+
+/// A property that differs between the two captures
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonitorDiff {
+    pub kind: DiffKind,
+    /// Id in the second capture, or in the first for removed monitors
+    pub monitor: MonitorId,
+    /// Id in the first capture, when it differs
+    pub previous_id: Option<MonitorId>,
+    pub matched_by: Option<MatchedBy>,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkspaceDiff {
+    pub kind: DiffKind,
+    pub workspace: String,
+    /// Monitor in the second capture, or in the first for removed workspaces
+    pub monitor: MonitorId,
+    /// Monitor in the first capture, for moved workspaces
+    pub from_monitor: Option<MonitorId>,
+    pub matched_by: Option<MatchedBy>,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowDiff {
+    pub kind: DiffKind,
+    /// `process: title`
+    pub window: String,
+    /// Id in the second capture, or in the first for removed windows
+    pub id: WindowId,
+    /// Id in the first capture, when it differs
+    pub previous_id: Option<WindowId>,
+    pub matched_by: Option<MatchedBy>,
+    /// Workspace in the second capture, or in the first for removed windows
+    pub workspace: String,
+    /// Workspace in the first capture, for moved windows
+    pub from_workspace: Option<String>,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Everything that differs between two captures, in tree order: removed
+/// nodes first, then added, moved and changed ones
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SnapshotDiff {
+    pub monitors: Vec<MonitorDiff>,
+    pub workspaces: Vec<WorkspaceDiff>,
+    pub windows: Vec<WindowDiff>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.monitors.is_empty() && self.workspaces.is_empty() && self.windows.is_empty()
+    }
+
+    /// Number of entries of a kind, across monitors, workspaces and windows
+    pub fn count(&self, kind: DiffKind) -> usize {
+        self.monitors.iter().filter(|d| d.kind == kind).count()
+            + self.workspaces.iter().filter(|d| d.kind == kind).count()
+            + self.windows.iter().filter(|d| d.kind == kind).count()
+    }
+}
+
+/// A key that may pair nodes; `None` when a node has no value for it
+type MatchKey<T> = (MatchedBy, fn(&T) -> Option<String>);
+
+/// For each node of `after`, the index of its node in `before` and the key
+/// that paired them. Keys are tried in order, each only on nodes that are
+/// still unpaired; equal values pair up in tree order.
+fn match_nodes<T>(
+    before: &[T],
+    after: &[T],
+    keys: &[MatchKey<T>],
+) -> Vec<Option<(usize, MatchedBy)>> {
+    let mut matches = vec![None; after.len()];
+    let mut taken = vec![false; before.len()];
+
+    for (matched_by, key) in keys {
+        let mut candidates: HashMap<String, VecDeque<usize>> = HashMap::new();
+        for (index, node) in before.iter().enumerate() {
+            if let (false, Some(value)) = (taken[index], key(node)) {
+                candidates.entry(value).or_default().push_back(index);
+            }
+        }
+        for (slot, node) in matches.iter_mut().zip(after) {
+            if slot.is_some() {
+                continue;
+            }
+            let Some(value) = key(node) else { continue };
+            if let Some(index) = candidates.get_mut(&value).and_then(VecDeque::pop_front) {
+                taken[index] = true;
+                *slot = Some((index, *matched_by));
+            }
+        }
+    }
+    matches
+}
+
+/// Indices of `before` that no node of `after` was paired with
+fn unmatched(before_len: usize, matches: &[Option<(usize, MatchedBy)>]) -> Vec<usize> {
+    inverse(before_len, matches)
+        .iter()
+        .enumerate()
+        .filter(|(_, after)| after.is_none())
+        .map(|(index, _)| index)
+        .collect()
+}
+
+fn field(changes: &mut Vec<FieldChange>, field: &'static str, from: String, to: String) {
+    if from != to {
+        changes.push(FieldChange { field, from, to });
+    }
+}
+
+fn focus(focused: bool) -> String {
+    if focused { "focused" } else { "unfocused" }.to_string()
+}
+
+struct WorkspaceNode<'a> {
+    workspace: &'a Workspace,
+    /// Index of the monitor in its capture
+    monitor: usize,
+}
+
+struct WindowNode<'a> {
+    window: &'a Window,
+    /// Index into the capture's `WorkspaceNode`s
+    workspace: usize,
+}
+
+fn workspace_nodes(monitors: &[Monitor]) -> Vec<WorkspaceNode<'_>> {
+    monitors
+        .iter()
+        .enumerate()
+        .flat_map(|(monitor, m)| {
+            m.workspaces()
+                .iter()
+                .map(move |workspace| WorkspaceNode { workspace, monitor })
+        })
+        .collect()
+}
+
+fn window_nodes<'a>(workspaces: &[WorkspaceNode<'a>]) -> Vec<WindowNode<'a>> {
+    workspaces
+        .iter()
+        .enumerate()
+        .flat_map(|(workspace, node)| {
+            node.workspace
+                .windows()
+                .iter()
+                .map(move |window| WindowNode { window, workspace })
+        })
+        .collect()
+}
+
+/// For each node of the first capture, the index of its node in the second
+fn inverse(before_len: usize, matches: &[Option<(usize, MatchedBy)>]) -> Vec<Option<usize>> {
+    let mut inverse = vec![None; before_len];
+    for (after_index, matched) in matches.iter().enumerate() {
+        if let Some((before_index, _)) = matched {
+            inverse[*before_index] = Some(after_index);
+        }
+    }
+    inverse
+}
+
+/// Compare two captures of the monitor tree
+pub fn diff(before: &[Monitor], after: &[Monitor]) -> SnapshotDiff {
+    let mut result = SnapshotDiff::default();
+
+    let monitor_after = diff_monitors(before, after, &mut result.monitors);
+
+    let old_workspaces = workspace_nodes(before);
+    let new_workspaces = workspace_nodes(after);
+    let workspace_after = diff_workspaces(
+        (before, &old_workspaces),
+        (after, &new_workspaces),
+        &monitor_after,
+        &mut result.workspaces,
+    );
+
+    diff_windows(
+        &old_workspaces,
+        &new_workspaces,
+        &workspace_after,
+        &mut result.windows,
+    );
+    result
+}
+
+/// Returns where each monitor of `before` went
+fn diff_monitors(
+    before: &[Monitor],
+    after: &[Monitor],
+    out: &mut Vec<MonitorDiff>,
+) -> Vec<Option<usize>> {
+    let keys: [MatchKey<Monitor>; 3] = [
+        (MatchedBy::Id, |m| Some(m.id().to_string())),
+        (MatchedBy::HardwareId, |m| {
+            m.hardware_id().map(str::to_string)
+        }),
+        (MatchedBy::DeviceName, |m| Some(m.device_name().to_string())),
+    ];
+    let matches = match_nodes(before, after, &keys);
+
+    for index in unmatched(before.len(), &matches) {
+        out.push(MonitorDiff {
+            kind: DiffKind::Removed,
+            monitor: before[index].id().clone(),
+            previous_id: None,
+            matched_by: None,
+            changes: Vec::new(),
+        });
+    }
+    for (monitor, matched) in after.iter().zip(&matches) {
+        let Some((index, matched_by)) = *matched else {
+            out.push(MonitorDiff {
+                kind: DiffKind::Added,
+                monitor: monitor.id().clone(),
+                previous_id: None,
+                matched_by: None,
+                changes: Vec::new(),
+            });
+            continue;
+        };
+        let old = &before[index];
+        let mut changes = Vec::new();
+        field(
+            &mut changes,
+            "geometry",
+            old.geometry().to_string(),
+            monitor.geometry().to_string(),
+        );
+        field(
+            &mut changes,
+            "focus",
+            focus(old.is_focused()),
+            focus(monitor.is_focused()),
+        );
+        if !changes.is_empty() {
+            out.push(MonitorDiff {
+                kind: DiffKind::Changed,
+                monitor: monitor.id().clone(),
+                previous_id: (old.id() != monitor.id()).then(|| old.id().clone()),
+                matched_by: Some(matched_by),
+                changes,
+            });
+        }
+    }
+    inverse(before.len(), &matches)
+}
+
+/// Returns where each workspace of `before` went
+fn diff_workspaces(
+    (before, old_nodes): (&[Monitor], &[WorkspaceNode]),
+    (after, new_nodes): (&[Monitor], &[WorkspaceNode]),
+    monitor_after: &[Option<usize>],
+    out: &mut Vec<WorkspaceDiff>,
+) -> Vec<Option<usize>> {
+    let keys: [MatchKey<WorkspaceNode>; 2] = [
+        (MatchedBy::Id, |n| Some(n.workspace.id().to_string())),
+        (MatchedBy::Name, |n| Some(n.workspace.name().to_string())),
+    ];
+    let matches = match_nodes(old_nodes, new_nodes, &keys);
+
+    for index in unmatched(old_nodes.len(), &matches) {
+        let node = &old_nodes[index];
+        out.push(WorkspaceDiff {
+            kind: DiffKind::Removed,
+            workspace: node.workspace.label().to_string(),
+            monitor: before[node.monitor].id().clone(),
+            from_monitor: None,
+            matched_by: None,
+            changes: Vec::new(),
+        });
+    }
+    for (node, matched) in new_nodes.iter().zip(&matches) {
+        let workspace = node.workspace;
+        let monitor = after[node.monitor].id().clone();
+        let Some((index, matched_by)) = *matched else {
+            out.push(WorkspaceDiff {
+                kind: DiffKind::Added,
+                workspace: workspace.label().to_string(),
+                monitor,
+                from_monitor: None,
+                matched_by: None,
+                changes: Vec::new(),
+            });
+            continue;
+        };
+        let old = &old_nodes[index];
+        let moved = monitor_after[old.monitor] != Some(node.monitor);
+        let mut changes = Vec::new();
+        field(
+            &mut changes,
+            "label",
+            old.workspace.label().to_string(),
+            workspace.label().to_string(),
+        );
+        field(
+            &mut changes,
+            "tiling_direction",
+            old.workspace.tiling_direction().to_string(),
+            workspace.tiling_direction().to_string(),
+        );
+        field(
+            &mut changes,
+            "focus",
+            focus(old.workspace.is_focused()),
+            focus(workspace.is_focused()),
+        );
+        if moved || !changes.is_empty() {
+            out.push(WorkspaceDiff {
+                kind: if moved {
+                    DiffKind::Moved
+                } else {
+                    DiffKind::Changed
+                },
+                workspace: workspace.label().to_string(),
+                monitor,
+                from_monitor: moved.then(|| before[old.monitor].id().clone()),
+                matched_by: Some(matched_by),
+                changes,
+            });
+        }
+    }
+    inverse(old_nodes.len(), &matches)
+}
+
+fn diff_windows(
+    old_workspaces: &[WorkspaceNode],
+    new_workspaces: &[WorkspaceNode],
+    workspace_after: &[Option<usize>],
+    out: &mut Vec<WindowDiff>,
+) {
+    let old_nodes = window_nodes(old_workspaces);
+    let new_nodes = window_nodes(new_workspaces);
+    let keys: [MatchKey<WindowNode>; 3] = [
+        (MatchedBy::Id, |n| Some(n.window.id().to_string())),
+        (MatchedBy::Handle, |n| {
+            n.window.handle().map(|h| h.to_string())
+        }),
+        (MatchedBy::ProcessAndTitle, |n| {
+            Some(format!("{}\0{}", n.window.process_name(), n.window.title()))
+        }),
+    ];
+    let matches = match_nodes(&old_nodes, &new_nodes, &keys);
+    let label = |nodes: &[WorkspaceNode], index: usize| nodes[index].workspace.label().to_string();
+
+    for index in unmatched(old_nodes.len(), &matches) {
+        let node = &old_nodes[index];
+        out.push(WindowDiff {
+            kind: DiffKind::Removed,
+            window: node.window.display_name(),
+            id: node.window.id().clone(),
+            previous_id: None,
+            matched_by: None,
+            workspace: label(old_workspaces, node.workspace),
+            from_workspace: None,
+            changes: Vec::new(),
+        });
+    }
+    for (node, matched) in new_nodes.iter().zip(&matches) {
+        let window = node.window;
+        let Some((index, matched_by)) = *matched else {
+            out.push(WindowDiff {
+                kind: DiffKind::Added,
+                window: window.display_name(),
+                id: window.id().clone(),
+                previous_id: None,
+                matched_by: None,
+                workspace: label(new_workspaces, node.workspace),
+                from_workspace: None,
+                changes: Vec::new(),
+            });
+            continue;
+        };
+        let old = &old_nodes[index];
+        let moved = workspace_after[old.workspace] != Some(node.workspace);
+        let mut changes = Vec::new();
+        field(
+            &mut changes,
+            "process",
+            old.window.process_name().to_string(),
+            window.process_name().to_string(),
+        );
+        field(
+            &mut changes,
+            "title",
+            old.window.title().to_string(),
+            window.title().to_string(),
+        );
+        field(
+            &mut changes,
+            "state",
+            old.window.state().to_string(),
+            window.state().to_string(),
+        );
+        field(
+            &mut changes,
+            "geometry",
+            old.window.geometry().to_string(),
+            window.geometry().to_string(),
+        );
+        field(
+            &mut changes,
+            "focus",
+            focus(old.window.is_focused()),
+            focus(window.is_focused()),
+        );
+        if moved || !changes.is_empty() {
+            out.push(WindowDiff {
+                kind: if moved {
+                    DiffKind::Moved
+                } else {
+                    DiffKind::Changed
+                },
+                window: window.display_name(),
+                id: window.id().clone(),
+                previous_id: (old.window.id() != window.id()).then(|| old.window.id().clone()),
+                matched_by: Some(matched_by),
+                workspace: label(new_workspaces, node.workspace),
+                from_workspace: moved.then(|| label(old_workspaces, old.workspace)),
+                changes,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::values::Rectangle;
+    use crate::domain::{DisplayState, FocusState, TilingDirection, WindowState, WorkspaceId};
+
+    fn window(id: &str, title: &str, handle: Option<i64>, state: WindowState) -> Window {
+        let mut window = Window::new(
+            WindowId::new(id.to_string()),
+            title.to_string(),
+            "app".to_string(),
+            Rectangle::from_coords(0, 0, 800, 600),
+            state,
+            FocusState::Unfocused,
+            DisplayState::Shown,
+        );
+        if let Some(handle) = handle {
+            window.set_handle(handle);
+        }
+        window
+    }
+
+    fn workspace(id: &str, name: &str, windows: Vec<Window>) -> Workspace {
+        Workspace::new(
+            WorkspaceId::new(id.to_string()),
+            name.to_string(),
+            windows,
+            TilingDirection::Horizontal,
+            FocusState::Unfocused,
+            DisplayState::Shown,
+        )
+    }
+
+    fn monitor(id: &str, workspaces: Vec<Workspace>) -> Monitor {
+        Monitor::new(
+            MonitorId::new(id.to_string()),
+            Rectangle::from_coords(0, 0, 1920, 1080),
+            workspaces,
+            FocusState::Unfocused,
+            96,
+            1.0,
+        )
+    }
+
+    #[test]
+    fn should_report_nothing_for_identical_captures() {
+        let capture = vec![monitor(
+            "m1",
+            vec![workspace(
+                "w1",
+                "1",
+                vec![window("a", "A", Some(1), WindowState::Tiling)],
+            )],
+        )];
+        assert!(diff(&capture, &capture).is_empty());
+    }
+
+    #[test]
+    fn should_match_nodes_by_handle_and_name_when_ids_change() {
+        let before = vec![monitor(
+            "m1",
+            vec![workspace(
+                "w1",
+                "1",
+                vec![window("a", "A", Some(1), WindowState::Tiling)],
+            )],
+        )];
+        // After a restart, with a new title as well
+        let after = vec![monitor(
+            "m1",
+            vec![workspace(
+                "w9",
+                "1",
+                vec![window("z", "A*", Some(1), WindowState::Floating)],
+            )],
+        )];
+
+        let result = diff(&before, &after);
+
+        assert!(result.workspaces.is_empty());
+        assert_eq!(result.windows.len(), 1);
+        let changed = &result.windows[0];
+        assert_eq!(changed.kind, DiffKind::Changed);
+        assert_eq!(changed.matched_by, Some(MatchedBy::Handle));
+        assert_eq!(changed.previous_id, Some(WindowId::new("a".to_string())));
+        let fields: Vec<&str> = changed.changes.iter().map(|c| c.field).collect();
+        assert_eq!(fields, ["title", "state"]);
+    }
+
+    #[test]
+    fn should_keep_the_previous_id_of_monitors_matched_by_hardware_id() {
+        let mut old_monitor = monitor("m1", Vec::new());
+        let mut new_monitor = Monitor::new(
+            MonitorId::new("m9".to_string()),
+            Rectangle::from_coords(0, 0, 2560, 1440),
+            Vec::new(),
+            FocusState::Unfocused,
+            96,
+            1.0,
+        );
+        let mut device_info = old_monitor.device_info().clone();
+        device_info.hardware_id = Some("DEL4321".to_string());
+        old_monitor.set_device_info(device_info.clone());
+        new_monitor.set_device_info(device_info);
+
+        let result = diff(&[old_monitor], &[new_monitor]);
+
+        assert_eq!(result.monitors.len(), 1);
+        let changed = &result.monitors[0];
+        assert_eq!(changed.kind, DiffKind::Changed);
+        assert_eq!(changed.monitor, MonitorId::new("m9".to_string()));
+        assert_eq!(changed.previous_id, Some(MonitorId::new("m1".to_string())));
+        assert_eq!(changed.matched_by, Some(MatchedBy::HardwareId));
+        let fields: Vec<&str> = changed.changes.iter().map(|c| c.field).collect();
+        assert_eq!(fields, ["geometry"]);
+    }
+
+    #[test]
+    fn should_fall_back_to_process_and_title() {
+        let before = vec![monitor(
+            "m1",
+            vec![workspace(
+                "w1",
+                "1",
+                vec![
+                    window("a", "Same", None, WindowState::Tiling),
+                    window("b", "Same", None, WindowState::Tiling),
+                ],
+            )],
+        )];
+        let after = vec![monitor(
+            "m1",
+            vec![workspace(
+                "w1",
+                "1",
+                vec![
+                    window("x", "Same", None, WindowState::Tiling),
+                    window("y", "New", None, WindowState::Tiling),
+                ],
+            )],
+        )];
+
+        let result = diff(&before, &after);
+
+        let kinds: Vec<(DiffKind, &str)> = result
+            .windows
+            .iter()
+            .map(|d| (d.kind, d.id.as_str()))
+            .collect();
+        // `x` pairs with `a`, the first unpaired window with its title
+        assert_eq!(kinds, [(DiffKind::Removed, "b"), (DiffKind::Added, "y")]);
+        assert_eq!(result.count(DiffKind::Changed), 0);
+    }
+
+    #[test]
+    fn should_report_moved_workspaces_and_windows() {
+        let before = vec![
+            monitor(
+                "m1",
+                vec![workspace(
+                    "w1",
+                    "1",
+                    vec![window("a", "A", None, WindowState::Tiling)],
+                )],
+            ),
+            monitor("m2", vec![workspace("w2", "2", Vec::new())]),
+        ];
+        let after = vec![monitor(
+            "m1",
+            vec![
+                workspace("w1", "1", Vec::new()),
+                workspace("w2", "2", vec![window("a", "A", None, WindowState::Tiling)]),
+            ],
+        )];
+
+        let result = diff(&before, &after);
+
+        assert_eq!(result.monitors[0].kind, DiffKind::Removed);
+        assert_eq!(result.workspaces.len(), 1);
+        assert_eq!(result.workspaces[0].kind, DiffKind::Moved);
+        assert_eq!(
+            result.workspaces[0].from_monitor,
+            Some(MonitorId::new("m2".to_string()))
+        );
+        assert_eq!(result.windows[0].kind, DiffKind::Moved);
+        assert_eq!(result.windows[0].from_workspace.as_deref(), Some("1"));
+        assert_eq!(
+            serde_json::to_value(&result.windows[0]).unwrap()["kind"],
+            "moved"
+        );
+    }
+}
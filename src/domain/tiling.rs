@@ -0,0 +1,490 @@
+// Expected tiling layout
+// Recomputes where glazewm should put each tiling window from the tiling
+// tree, the reported tiling sizes, the configured gaps and the workspace
+// area, and flags windows whose actual geometry is elsewhere (e.g. apps that
+// enforce a minimum size)
+
+use serde::Serialize;
+use std::fmt;
+
+use crate::domain::{
+    Insets, LayoutNode, Monitor, Rectangle, TilingDirection, Window, WindowId, WindowState,
+    Workspace,
+};
+
+/// Pixels a window may be off by before it counts as a deviation
+pub const DEFAULT_TOLERANCE: u32 = 2;
+
+/// glazewm's `gaps` config section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gaps {
+    /// Space between tiling siblings
+    pub inner: u32,
+    /// Space around the tiling area; only applied when glazewm does not
+    /// report workspace geometry, which already excludes it
+    pub outer: Insets,
+    /// Multiply gaps by the monitor's scale factor (`scale_with_dpi`)
+    pub scale_with_dpi: bool,
+}
+
+impl Default for Gaps {
+    fn default() -> Self {
+        Self {
+            inner: 0,
+            outer: Insets {
+                left: 0,
+                top: 0,
+                right: 0,
+                bottom: 0,
+            },
+            scale_with_dpi: true,
+        }
+    }
+}
+
+/// A tiling window whose geometry differs from the computed one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LayoutDeviation {
+    pub window_id: WindowId,
+    pub display_name: String,
+    pub expected: Rectangle,
+    pub actual: Rectangle,
+}
+
+impl LayoutDeviation {
+    /// Non-zero differences from the expected geometry, e.g. `x+12 w-24`
+    pub fn delta(&self) -> String {
+        let parts: Vec<String> = [
+            (
+                "x",
+                self.actual.position.x as i64 - self.expected.position.x as i64,
+            ),
+            (
+                "y",
+                self.actual.position.y as i64 - self.expected.position.y as i64,
+            ),
+            (
+                "w",
+                self.actual.size.width as i64 - self.expected.size.width as i64,
+            ),
+            (
+                "h",
+                self.actual.size.height as i64 - self.expected.size.height as i64,
+            ),
+        ]
+        .iter()
+        .filter(|(_, delta)| *delta != 0)
+        .map(|(axis, delta)| format!("{}{:+}", axis, delta))
+        .collect();
+        parts.join(" ")
+    }
+}
+
+impl fmt::Display for LayoutDeviation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) is at {}, expected {} ({})",
+            self.window_id,
+            self.display_name,
+            self.actual,
+            self.expected,
+            self.delta()
+        )
+    }
+}
+
+/// Computes expected tiling geometry and compares it with the actual one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TilingModel {
+    pub gaps: Gaps,
+    pub tolerance: u32,
+}
+
+impl Default for TilingModel {
+    fn default() -> Self {
+        Self {
+            gaps: Gaps::default(),
+            tolerance: DEFAULT_TOLERANCE,
+        }
+    }
+}
+
+impl TilingModel {
+    pub fn new(gaps: Gaps, tolerance: u32) -> Self {
+        Self { gaps, tolerance }
+    }
+
+    /// Area the tiling windows of `workspace` share: the workspace geometry
+    /// glazewm reports, else the monitor's working area minus the outer gap
+    pub fn tiling_area(&self, monitor: &Monitor, workspace: &Workspace) -> Rectangle {
+        if let Some(geometry) = workspace.geometry() {
+            return *geometry;
+        }
+        let area = *monitor.working_area().unwrap_or(monitor.geometry());
+        let scale = self.scale(monitor);
+        let outer = self.gaps.outer;
+        let [left, top, right, bottom] =
+            [outer.left, outer.top, outer.right, outer.bottom].map(|gap| gap as f64 * scale);
+        rounded(
+            area.position.x as f64 + left,
+            area.position.y as f64 + top,
+            area.size.width as f64 - left - right,
+            area.size.height as f64 - top - bottom,
+        )
+    }
+
+    /// Where each tiling window of `workspace` should be, in tree order
+    pub fn expected_rects(
+        &self,
+        monitor: &Monitor,
+        workspace: &Workspace,
+    ) -> Vec<(WindowId, Rectangle)> {
+        let inner_gap = self.gaps.inner as f64 * self.scale(monitor);
+        layout(workspace, self.tiling_area(monitor, workspace), inner_gap)
+    }
+
+    /// Tiling windows further than the tolerance from where they should be
+    pub fn deviations(&self, monitors: &[Monitor]) -> Vec<LayoutDeviation> {
+        let mut deviations = Vec::new();
+        for monitor in monitors {
+            for workspace in monitor.workspaces() {
+                for (window_id, expected) in self.expected_rects(monitor, workspace) {
+                    let Some(window) = find_window(workspace, &window_id) else {
+                        continue;
+                    };
+                    let actual = *window.geometry();
+                    if self.exceeds_tolerance(&expected, &actual) {
+                        deviations.push(LayoutDeviation {
+                            window_id,
+                            display_name: window.display_name(),
+                            expected,
+                            actual,
+                        });
+                    }
+                }
+            }
+        }
+        deviations
+    }
+
+    fn exceeds_tolerance(&self, expected: &Rectangle, actual: &Rectangle) -> bool {
+        let tolerance = self.tolerance as i64;
+        [
+            actual.position.x as i64 - expected.position.x as i64,
+            actual.position.y as i64 - expected.position.y as i64,
+            actual.size.width as i64 - expected.size.width as i64,
+            actual.size.height as i64 - expected.size.height as i64,
+        ]
+        .iter()
+        .any(|delta| delta.abs() > tolerance)
+    }
+
+    fn scale(&self, monitor: &Monitor) -> f64 {
+        if self.gaps.scale_with_dpi {
+            monitor.scale_factor()
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Lay out the tiling windows of `workspace` in `area`, with `inner_gap`
+/// pixels between siblings
+pub fn layout(
+    workspace: &Workspace,
+    area: Rectangle,
+    inner_gap: f64,
+) -> Vec<(WindowId, Rectangle)> {
+    let mut out = Vec::new();
+    let area = FloatRect {
+        x: area.position.x as f64,
+        y: area.position.y as f64,
+        width: area.size.width as f64,
+        height: area.size.height as f64,
+    };
+    place(
+        workspace,
+        workspace.layout(),
+        workspace.tiling_direction(),
+        area,
+        inner_gap,
+        &mut out,
+    );
+    out
+}
+
+/// Share of the workspace's tiling area each tiling window gets, from 0 to 1
+pub fn shares(workspace: &Workspace) -> Vec<(WindowId, f64)> {
+    let mut out = Vec::new();
+    collect_shares(workspace, workspace.layout(), 1.0, &mut out);
+    out
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FloatRect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+fn rounded(x: f64, y: f64, width: f64, height: f64) -> Rectangle {
+    let (left, top) = (x.round(), y.round());
+    let (right, bottom) = ((x + width).round(), (y + height).round());
+    Rectangle::from_coords(
+        left as i32,
+        top as i32,
+        (right - left).max(0.0) as u32,
+        (bottom - top).max(0.0) as u32,
+    )
+}
+
+fn find_window<'a>(workspace: &'a Workspace, id: &WindowId) -> Option<&'a Window> {
+    workspace.windows().iter().find(|window| window.id() == id)
+}
+
+/// Nodes that take part in tiling, with their share of the parent's length.
+/// Reported tiling sizes are normalized; without them siblings split evenly.
+fn tiling_children<'a>(
+    workspace: &Workspace,
+    nodes: &'a [LayoutNode],
+) -> Vec<(&'a LayoutNode, f64)> {
+    let tiling: Vec<(&LayoutNode, Option<f64>)> = nodes
+        .iter()
+        .filter_map(|node| match node {
+            LayoutNode::Window(id) => find_window(workspace, id)
+                .filter(|window| window.state() == &WindowState::Tiling)
+                .map(|window| (node, window.tiling_size())),
+            LayoutNode::Split(container) => (!tiling_children(workspace, container.children())
+                .is_empty())
+            .then_some((node, container.tiling_size())),
+        })
+        .collect();
+
+    let total: f64 = tiling.iter().filter_map(|(_, size)| *size).sum();
+    let all_reported = tiling.iter().all(|(_, size)| size.is_some_and(|s| s > 0.0));
+    let count = tiling.len() as f64;
+    tiling
+        .into_iter()
+        .map(|(node, size)| match size {
+            Some(size) if all_reported && total > 0.0 => (node, size / total),
+            _ => (node, 1.0 / count),
+        })
+        .collect()
+}
+
+fn place(
+    workspace: &Workspace,
+    nodes: &[LayoutNode],
+    direction: &TilingDirection,
+    area: FloatRect,
+    inner_gap: f64,
+    out: &mut Vec<(WindowId, Rectangle)>,
+) {
+    let children = tiling_children(workspace, nodes);
+    if children.is_empty() {
+        return;
+    }
+    let vertical = matches!(direction, TilingDirection::Vertical);
+    let length = if vertical { area.height } else { area.width };
+    let gaps = inner_gap * (children.len() - 1) as f64;
+    let available = (length - gaps).max(0.0);
+
+    let mut offset = 0.0;
+    for (node, share) in children {
+        let extent = available * share;
+        let rect = if vertical {
+            FloatRect {
+                y: area.y + offset,
+                height: extent,
+                ..area
+            }
+        } else {
+            FloatRect {
+                x: area.x + offset,
+                width: extent,
+                ..area
+            }
+        };
+        offset += extent + inner_gap;
+
+        match node {
+            LayoutNode::Window(id) => {
+                out.push((id.clone(), rounded(rect.x, rect.y, rect.width, rect.height)))
+            }
+            LayoutNode::Split(container) => place(
+                workspace,
+                container.children(),
+                container.tiling_direction(),
+                rect,
+                inner_gap,
+                out,
+            ),
+        }
+    }
+}
+
+fn collect_shares(
+    workspace: &Workspace,
+    nodes: &[LayoutNode],
+    parent_share: f64,
+    out: &mut Vec<(WindowId, f64)>,
+) {
+    for (node, share) in tiling_children(workspace, nodes) {
+        match node {
+            LayoutNode::Window(id) => out.push((id.clone(), parent_share * share)),
+            LayoutNode::Split(container) => {
+                collect_shares(workspace, container.children(), parent_share * share, out)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        ContainerId, DisplayState, FocusState, MonitorId, SplitContainer, WorkspaceId,
+    };
+
+    fn window(id: &str, geometry: Rectangle, tiling_size: f64) -> Window {
+        let mut window = Window::new(
+            WindowId::new(id.to_string()),
+            id.to_string(),
+            "app".to_string(),
+            geometry,
+            WindowState::Tiling,
+            FocusState::Unfocused,
+            DisplayState::Shown,
+        );
+        window.set_tiling_size(tiling_size);
+        window
+    }
+
+    /// w1 takes 0.6 of a horizontal workspace; w2 and w3 split the rest
+    /// vertically, 0.25/0.75
+    fn nested_monitor(w3: Rectangle, scale_factor: f64) -> Monitor {
+        let windows = vec![
+            window("w1", Rectangle::from_coords(0, 0, 1140, 1040), 0.6),
+            window("w2", Rectangle::from_coords(1160, 0, 760, 255), 0.25),
+            window("w3", w3, 0.75),
+        ];
+        let mut split = SplitContainer::new(
+            ContainerId::new("c1".to_string()),
+            TilingDirection::Vertical,
+            vec![
+                LayoutNode::Window(WindowId::new("w2".to_string())),
+                LayoutNode::Window(WindowId::new("w3".to_string())),
+            ],
+        );
+        split.set_tiling_size(0.4);
+
+        let mut workspace = Workspace::new(
+            WorkspaceId::new("ws".to_string()),
+            "1".to_string(),
+            windows,
+            TilingDirection::Horizontal,
+            FocusState::Focused,
+            DisplayState::Shown,
+        );
+        workspace.set_layout(vec![
+            LayoutNode::Window(WindowId::new("w1".to_string())),
+            LayoutNode::Split(split),
+        ]);
+
+        let mut monitor = Monitor::new(
+            MonitorId::new("m".to_string()),
+            Rectangle::from_coords(0, 0, 1920, 1080),
+            vec![workspace],
+            FocusState::Focused,
+            96,
+            scale_factor,
+        );
+        monitor.set_working_area(Rectangle::from_coords(0, 0, 1920, 1040));
+        monitor
+    }
+
+    fn model(inner: u32) -> TilingModel {
+        TilingModel::new(
+            Gaps {
+                inner,
+                ..Gaps::default()
+            },
+            DEFAULT_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn should_lay_out_nested_containers_with_tiling_sizes_and_gaps() {
+        let monitor = nested_monitor(Rectangle::from_coords(1160, 275, 760, 765), 1.0);
+        let rects = model(20).expected_rects(&monitor, &monitor.workspaces()[0]);
+
+        assert_eq!(
+            rects,
+            vec![
+                (
+                    WindowId::new("w1".to_string()),
+                    Rectangle::from_coords(0, 0, 1140, 1040)
+                ),
+                (
+                    WindowId::new("w2".to_string()),
+                    Rectangle::from_coords(1160, 0, 760, 255)
+                ),
+                (
+                    WindowId::new("w3".to_string()),
+                    Rectangle::from_coords(1160, 275, 760, 765)
+                ),
+            ]
+        );
+        assert!(model(20).deviations(&[monitor]).is_empty());
+    }
+
+    #[test]
+    fn should_flag_windows_beyond_the_tolerance() {
+        // w3 refuses to shrink below 800px wide and 1px off is tolerated
+        let monitor = nested_monitor(Rectangle::from_coords(1121, 276, 800, 765), 1.0);
+        let deviations = model(20).deviations(&[monitor]);
+
+        assert_eq!(deviations.len(), 1);
+        assert_eq!(deviations[0].window_id.as_str(), "w3");
+        assert_eq!(deviations[0].delta(), "x-39 y+1 w+40");
+        assert_eq!(
+            deviations[0].to_string(),
+            "w3 (app: w3) is at (1121, 276) 800x765, expected (1160, 275) 760x765 (x-39 y+1 w+40)"
+        );
+    }
+
+    #[test]
+    fn should_scale_gaps_and_fall_back_to_the_working_area() {
+        let monitor = nested_monitor(Rectangle::from_coords(0, 0, 1, 1), 1.5);
+        let model = TilingModel::new(
+            Gaps {
+                inner: 10,
+                outer: Insets {
+                    left: 10,
+                    top: 20,
+                    right: 10,
+                    bottom: 10,
+                },
+                scale_with_dpi: true,
+            },
+            DEFAULT_TOLERANCE,
+        );
+        let workspace = &monitor.workspaces()[0];
+
+        assert_eq!(
+            model.tiling_area(&monitor, workspace),
+            Rectangle::from_coords(15, 30, 1890, 995)
+        );
+        // 1890 - 15 of inner gap, split 0.6/0.4
+        assert_eq!(
+            model.expected_rects(&monitor, workspace)[0].1,
+            Rectangle::from_coords(15, 30, 1125, 995)
+        );
+
+        let shares = shares(workspace);
+        assert_eq!(shares[0].1, 0.6);
+        assert!((shares[2].1 - 0.3).abs() < 1e-9);
+    }
+}
@@ -84,6 +84,77 @@ impl Rectangle {
             && point.y >= self.position.y
             && point.y < (self.position.y + self.size.height as i32)
     }
+
+    /// Whether the two rectangles share any area; touching edges do not count
+    pub fn intersects(&self, other: &Rectangle) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Distance from each edge of this rectangle to the matching edge of `inner`
+    pub fn insets_to(&self, inner: &Rectangle) -> Insets {
+        Insets {
+            left: inner.position.x - self.position.x,
+            top: inner.position.y - self.position.y,
+            right: self.right() - inner.right(),
+            bottom: self.bottom() - inner.bottom(),
+        }
+    }
+
+    /// Exclusive x coordinate of the right edge
+    pub fn right(&self) -> i32 {
+        self.position.x + self.size.width as i32
+    }
+
+    /// Exclusive y coordinate of the bottom edge
+    pub fn bottom(&self) -> i32 {
+        self.position.y + self.size.height as i32
+    }
+
+    /// Whether `other` lies entirely within this rectangle
+    pub fn contains(&self, other: &Rectangle) -> bool {
+        other.position.x >= self.position.x
+            && other.position.y >= self.position.y
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    /// The area both rectangles cover, if they share any
+    pub fn intersection(&self, other: &Rectangle) -> Option<Rectangle> {
+        let left = self.position.x.max(other.position.x);
+        let top = self.position.y.max(other.position.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+
+        (left < right && top < bottom).then(|| {
+            Rectangle::from_coords(left, top, (right - left) as u32, (bottom - top) as u32)
+        })
+    }
+
+    /// Smallest rectangle containing both
+    pub fn union(&self, other: &Rectangle) -> Rectangle {
+        let left = self.position.x.min(other.position.x);
+        let top = self.position.y.min(other.position.y);
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+
+        Rectangle::from_coords(left, top, (right - left) as u32, (bottom - top) as u32)
+    }
+
+    /// This rectangle cut down to `bounds`; outside of them it collapses to
+    /// an empty rectangle at the nearest point of `bounds`
+    pub fn clip(&self, bounds: &Rectangle) -> Rectangle {
+        let clamp_x = |x: i32| x.clamp(bounds.position.x, bounds.right());
+        let clamp_y = |y: i32| y.clamp(bounds.position.y, bounds.bottom());
+        let (left, top) = (clamp_x(self.position.x), clamp_y(self.position.y));
+        let (right, bottom) = (clamp_x(self.right()), clamp_y(self.bottom()));
+
+        Rectangle::from_coords(
+            left,
+            top,
+            (right - left).max(0) as u32,
+            (bottom - top).max(0) as u32,
+        )
+    }
 }
 
 impl fmt::Display for Rectangle {
@@ -92,6 +163,44 @@ impl fmt::Display for Rectangle {
     }
 }
 
+/// Space between the edges of an outer and an inner rectangle
+/// (e.g. the area a taskbar reserves on a monitor)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Insets {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl Insets {
+    pub fn is_zero(&self) -> bool {
+        self.left == 0 && self.top == 0 && self.right == 0 && self.bottom == 0
+    }
+}
+
+impl fmt::Display for Insets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let edges = [
+            ("left", self.left),
+            ("top", self.top),
+            ("right", self.right),
+            ("bottom", self.bottom),
+        ];
+        let non_zero: Vec<String> = edges
+            .iter()
+            .filter(|(_, value)| *value != 0)
+            .map(|(edge, value)| format!("{} {}px", edge, value))
+            .collect();
+
+        if non_zero.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", non_zero.join(", "))
+        }
+    }
+}
+
 /// Unique identifier for monitors
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct MonitorId(String);
@@ -152,6 +261,26 @@ impl fmt::Display for WindowId {
     }
 }
 
+/// Unique identifier for split containers
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ContainerId(String);
+
+impl ContainerId {
+    pub fn new(id: String) -> Self {
+        Self(id)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ContainerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -177,6 +306,57 @@ mod tests {
         assert!(!rect.contains_point(Position::new(150, 150))); // Outside
     }
 
+    #[test]
+    fn rectangle_should_detect_overlap() {
+        let monitor = Rectangle::from_coords(0, 0, 1920, 1080);
+
+        assert!(monitor.intersects(&Rectangle::from_coords(1900, 1000, 800, 600)));
+        assert!(!monitor.intersects(&Rectangle::from_coords(1920, 0, 800, 600))); // Touching
+        assert!(!monitor.intersects(&Rectangle::from_coords(-32000, -32000, 160, 28)));
+    }
+
+    #[test]
+    fn rectangle_should_intersect_union_and_clip() {
+        let monitor = Rectangle::from_coords(0, 0, 1920, 1080);
+        let window = Rectangle::from_coords(1800, 900, 400, 300);
+
+        assert_eq!(
+            monitor.intersection(&window),
+            Some(Rectangle::from_coords(1800, 900, 120, 180))
+        );
+        assert_eq!(
+            monitor.intersection(&Rectangle::from_coords(1920, 0, 800, 600)),
+            None
+        );
+        assert_eq!(
+            monitor.union(&window),
+            Rectangle::from_coords(0, 0, 2200, 1200)
+        );
+        assert_eq!(
+            window.clip(&monitor),
+            Rectangle::from_coords(1800, 900, 120, 180)
+        );
+        assert_eq!(
+            Rectangle::from_coords(-32000, -32000, 160, 28).clip(&monitor),
+            Rectangle::from_coords(0, 0, 0, 0)
+        );
+        assert!(monitor.contains(&window.clip(&monitor)));
+        assert!(!monitor.contains(&window));
+    }
+
+    #[test]
+    fn rectangle_should_calculate_insets_to_inner_area() {
+        let monitor = Rectangle::from_coords(1920, 0, 2560, 1440);
+        let working_area = Rectangle::from_coords(1920, 0, 2560, 1392);
+
+        let insets = monitor.insets_to(&working_area);
+
+        assert_eq!(insets.bottom, 48);
+        assert!(!insets.is_zero());
+        assert_eq!(format!("{}", insets), "bottom 48px");
+        assert!(monitor.insets_to(&monitor).is_zero());
+    }
+
     #[test]
     fn identifiers_should_display_correctly() {
         let monitor_id = MonitorId::new("mon-1".to_string());
@@ -1,11 +1,13 @@
 // Window entity implementation
 // Represents an individual application window in the window management domain
 
+use serde::Serialize;
+
 use crate::domain::values::Rectangle;
 use crate::domain::{DisplayState, DomainError, FocusState, WindowId, WindowState};
 
 /// Window entity representing an individual application window
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Window {
     id: WindowId,
     title: String,
@@ -14,6 +16,12 @@ pub struct Window {
     state: WindowState,
     focus_state: FocusState,
     display_state: DisplayState,
+    /// Native window handle, when glazewm reports it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    handle: Option<i64>,
+    /// Share of its parent's length among tiling siblings, when reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tiling_size: Option<f64>,
 }
 
 impl Window {
@@ -35,6 +43,8 @@ impl Window {
             state,
             focus_state,
             display_state,
+            handle: None,
+            tiling_size: None,
         }
     }
 
@@ -67,6 +77,14 @@ impl Window {
         &self.display_state
     }
 
+    pub fn handle(&self) -> Option<i64> {
+        self.handle
+    }
+
+    pub fn tiling_size(&self) -> Option<f64> {
+        self.tiling_size
+    }
+
     // Behavior methods
     pub fn is_focused(&self) -> bool {
         self.focus_state.is_focused()
@@ -87,10 +105,11 @@ impl Window {
         TextWidthCalculator::truncate_to_width(&self.display_name(), max_len)
     }
 
-    /// Get state indicator for display ([T], [F], [M], [H])
+    /// Get state indicator for display ([T], [F], [M], [H], [?])
     pub fn state_indicator(&self) -> &'static str {
         match (&self.state, &self.display_state) {
             (_, DisplayState::Hidden) => "[H]",
+            (WindowState::Unknown(_), _) => "[?]",
             (WindowState::Minimized, _) => "[M]",
             (WindowState::Floating, _) => "[F]",
             (WindowState::Fullscreen, _) => "[F]", // Fullscreen treated as floating for display
@@ -117,6 +136,14 @@ impl Window {
     pub fn set_display_state(&mut self, display_state: DisplayState) {
         self.display_state = display_state;
     }
+
+    pub fn set_handle(&mut self, handle: i64) {
+        self.handle = Some(handle);
+    }
+
+    pub fn set_tiling_size(&mut self, tiling_size: f64) {
+        self.tiling_size = Some(tiling_size);
+    }
 }
 
 #[cfg(test)]
@@ -207,5 +234,10 @@ mod tests {
         window.state = WindowState::Tiling;
         window.display_state = DisplayState::Hidden;
         assert_eq!(window.state_indicator(), "[H]");
+
+        // Test unrecognized state
+        window.state = WindowState::Unknown("snapped".to_string());
+        window.display_state = DisplayState::Shown;
+        assert_eq!(window.state_indicator(), "[?]");
     }
 }
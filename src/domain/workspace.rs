@@ -1,13 +1,18 @@
 // Workspace entity implementation
 // Represents a logical workspace containing windows
 
-use crate::domain::values::{Position, Size};
+use serde::Serialize;
+
+use crate::domain::container::{self, LayoutNode, SplitContainer};
+use crate::domain::tiling;
+use crate::domain::values::{Position, Rectangle, Size};
 use crate::domain::{
-    DisplayState, DomainError, FocusState, TilingDirection, Window, WindowId, WorkspaceId,
+    DisplayState, DomainError, FocusState, Placeholder, TilingDirection, Window, WindowId,
+    WindowState, WorkspaceId,
 };
 
 /// Layout information for a window within a workspace
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct WindowLayout {
     pub window_id: WindowId,
     pub position: Position,
@@ -25,7 +30,7 @@ impl WindowLayout {
 }
 
 /// Workspace entity containing windows and layout information
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Workspace {
     id: WorkspaceId,
     name: String,
@@ -33,10 +38,18 @@ pub struct Workspace {
     tiling_direction: TilingDirection,
     focus_state: FocusState,
     display_state: DisplayState,
+    display_name: Option<String>,
+    geometry: Option<Rectangle>,
+    layout: Vec<LayoutNode>,
+    focus_order: Vec<String>,
+    placeholders: Vec<Placeholder>,
 }
 
 impl Workspace {
     /// Create a new workspace
+    ///
+    /// Windows start out as direct children of the workspace; use
+    /// `set_layout` to describe nested split containers.
     pub fn new(
         id: WorkspaceId,
         name: String,
@@ -45,6 +58,11 @@ impl Workspace {
         focus_state: FocusState,
         display_state: DisplayState,
     ) -> Self {
+        let layout = windows
+            .iter()
+            .map(|window| LayoutNode::Window(window.id().clone()))
+            .collect();
+
         Self {
             id,
             name,
@@ -52,6 +70,11 @@ impl Workspace {
             tiling_direction,
             focus_state,
             display_state,
+            display_name: None,
+            geometry: None,
+            layout,
+            focus_order: Vec::new(),
+            placeholders: Vec::new(),
         }
     }
 
@@ -64,6 +87,21 @@ impl Workspace {
         &self.name
     }
 
+    /// Name configured for display in glazewm, if any
+    pub fn display_name(&self) -> Option<&str> {
+        self.display_name.as_deref()
+    }
+
+    /// Name to show in the UI: the display name when set, otherwise the name
+    pub fn label(&self) -> &str {
+        self.display_name().unwrap_or(&self.name)
+    }
+
+    /// Area glazewm assigned to this workspace, if reported
+    pub fn geometry(&self) -> Option<&Rectangle> {
+        self.geometry.as_ref()
+    }
+
     pub fn windows(&self) -> &[Window] {
         &self.windows
     }
@@ -80,6 +118,21 @@ impl Workspace {
         &self.display_state
     }
 
+    /// Tiling tree of this workspace's direct children
+    pub fn layout(&self) -> &[LayoutNode] {
+        &self.layout
+    }
+
+    /// Direct child ids, most recently focused first
+    pub fn focus_order(&self) -> &[String] {
+        &self.focus_order
+    }
+
+    /// Windows glazewm reported in this workspace that could not be parsed
+    pub fn placeholders(&self) -> &[Placeholder] {
+        &self.placeholders
+    }
+
     // Computed properties
     pub fn window_count(&self) -> usize {
         self.windows.len()
@@ -102,6 +155,55 @@ impl Workspace {
         self.windows.iter().find(|window| window.is_focused())
     }
 
+    /// All windows, most recently focused first
+    ///
+    /// Windows missing from the layout tree keep their order and come last.
+    pub fn windows_by_focus_order(&self) -> Vec<&Window> {
+        let mut ids = Vec::new();
+        container::windows_by_focus(&self.layout, &self.focus_order, &mut ids);
+
+        let mut ordered: Vec<&Window> = ids
+            .into_iter()
+            .filter_map(|id| self.windows.iter().find(|window| window.id() == id))
+            .collect();
+        for window in &self.windows {
+            if !ordered.iter().any(|w| w.id() == window.id()) {
+                ordered.push(window);
+            }
+        }
+        ordered
+    }
+
+    /// Split containers between the workspace and a window, outermost first
+    pub fn container_path(&self, window_id: &WindowId) -> Vec<&SplitContainer> {
+        container::path_to_window(&self.layout, window_id).unwrap_or_default()
+    }
+
+    /// Windows glazewm would fall back to, in order, if `window_id` closed
+    ///
+    /// Mirrors glazewm's focus target after removal: the most recently
+    /// focused window of the same kind (tiling or not) wins, then any other
+    /// window by recency. An empty result means focus falls to the workspace.
+    pub fn focus_fallback_order(&self, window_id: &WindowId) -> Vec<&Window> {
+        let is_tiling = self
+            .windows
+            .iter()
+            .find(|window| window.id() == window_id)
+            .map(|window| window.state() == &WindowState::Tiling);
+
+        let mut candidates: Vec<&Window> = self
+            .windows_by_focus_order()
+            .into_iter()
+            .filter(|window| window.id() != window_id)
+            .collect();
+
+        if let Some(is_tiling) = is_tiling {
+            // Stable sort keeps recency order within each group
+            candidates.sort_by_key(|window| (window.state() == &WindowState::Tiling) != is_tiling);
+        }
+        candidates
+    }
+
     // Window management
     pub fn add_window(&mut self, window: Window) -> Result<(), DomainError> {
         // Check for duplicate window ID
@@ -111,10 +213,16 @@ impl Workspace {
             });
         }
 
+        self.layout.push(LayoutNode::Window(window.id().clone()));
         self.windows.push(window);
         Ok(())
     }
 
+    /// Record a window that could not be parsed
+    pub fn add_placeholder(&mut self, placeholder: Placeholder) {
+        self.placeholders.push(placeholder);
+    }
+
     pub fn remove_window(&mut self, window_id: &WindowId) -> Result<Window, DomainError> {
         let position = self
             .windows
@@ -124,67 +232,30 @@ impl Workspace {
                 id: window_id.clone(),
             })?;
 
+        container::remove_window(&mut self.layout, window_id);
+        self.focus_order.retain(|id| id != window_id.as_str());
         Ok(self.windows.remove(position))
     }
 
-    /// Calculate layout for all windows in this workspace
+    /// Calculate where the tiling windows of this workspace go in a
+    /// container of the given size, without gaps
+    ///
+    /// Uses the tiling sizes glazewm reports and the nested split
+    /// containers; see `TilingModel` for gaps and the monitor working area.
     pub fn calculate_layout(&self, container_size: Size) -> Vec<WindowLayout> {
-        if self.windows.is_empty() {
-            return Vec::new();
-        }
-
-        match self.tiling_direction {
-            TilingDirection::Horizontal => self.calculate_horizontal_layout(container_size),
-            TilingDirection::Vertical => self.calculate_vertical_layout(container_size),
-        }
-    }
-
-    fn calculate_horizontal_layout(&self, container_size: Size) -> Vec<WindowLayout> {
-        let window_count = self.windows.len() as u32;
-        let window_width = container_size.width / window_count;
-
-        self.windows
-            .iter()
-            .enumerate()
-            .map(|(index, window)| {
-                let x = (index as u32) * window_width;
-                let position = Position::new(x as i32, 0);
-                let size = Size::new(window_width, container_size.height);
-
-                WindowLayout::new(window.id().clone(), position, size)
-            })
+        let area = Rectangle::new(Position::origin(), container_size);
+        tiling::layout(self, area, 0.0)
+            .into_iter()
+            .map(|(window_id, rect)| WindowLayout::new(window_id, rect.position, rect.size))
             .collect()
     }
 
-    /// Calculate the percentage of workspace each window occupies
+    /// Calculate the percentage of the tiling area each tiling window
+    /// occupies; floating, minimized and fullscreen windows are left out
     pub fn calculate_window_percentages(&self) -> Vec<(WindowId, f32)> {
-        if self.windows.is_empty() {
-            return Vec::new();
-        }
-
-        // For now, equal distribution - in future could use actual tiling_size from glazewm
-        let percentage = 100.0 / self.windows.len() as f32;
-
-        self.windows
-            .iter()
-            .map(|window| (window.id().clone(), percentage))
-            .collect()
-    }
-
-    fn calculate_vertical_layout(&self, container_size: Size) -> Vec<WindowLayout> {
-        let window_count = self.windows.len() as u32;
-        let window_height = container_size.height / window_count;
-
-        self.windows
-            .iter()
-            .enumerate()
-            .map(|(index, window)| {
-                let y = (index as u32) * window_height;
-                let position = Position::new(0, y as i32);
-                let size = Size::new(container_size.width, window_height);
-
-                WindowLayout::new(window.id().clone(), position, size)
-            })
+        tiling::shares(self)
+            .into_iter()
+            .map(|(window_id, share)| (window_id, (share * 100.0) as f32))
             .collect()
     }
 
@@ -196,6 +267,22 @@ impl Workspace {
     pub fn set_tiling_direction(&mut self, direction: TilingDirection) {
         self.tiling_direction = direction;
     }
+
+    pub fn set_layout(&mut self, layout: Vec<LayoutNode>) {
+        self.layout = layout;
+    }
+
+    pub fn set_focus_order(&mut self, focus_order: Vec<String>) {
+        self.focus_order = focus_order;
+    }
+
+    pub fn set_display_name(&mut self, display_name: String) {
+        self.display_name = Some(display_name);
+    }
+
+    pub fn set_geometry(&mut self, geometry: Rectangle) {
+        self.geometry = Some(geometry);
+    }
 }
 
 #[cfg(test)]
@@ -247,6 +334,24 @@ mod tests {
         assert!(!workspace.is_focused());
     }
 
+    #[test]
+    fn should_prefer_display_name_for_label() {
+        let mut workspace = Workspace::new(
+            WorkspaceId::new("test".to_string()),
+            "1".to_string(),
+            Vec::new(),
+            TilingDirection::Horizontal,
+            FocusState::Unfocused,
+            DisplayState::Shown,
+        );
+
+        assert_eq!(workspace.label(), "1");
+
+        workspace.set_display_name("Code".to_string());
+        assert_eq!(workspace.label(), "Code");
+        assert_eq!(workspace.name(), "1");
+    }
+
     #[test]
     fn should_add_window() {
         let mut workspace = Workspace::new(
@@ -325,6 +430,41 @@ mod tests {
         assert_eq!(focused.unwrap().title(), "VS Code");
     }
 
+    #[test]
+    fn should_order_windows_by_focus_and_compute_fallback() {
+        let mut floating = create_test_window("w3", "Calculator", "calc");
+        floating.change_state(WindowState::Floating).unwrap();
+
+        let mut workspace = Workspace::new(
+            WorkspaceId::new("test".to_string()),
+            "Test".to_string(),
+            vec![
+                create_focused_window("w1", "VS Code", "Code", true),
+                create_test_window("w2", "Chrome", "chrome"),
+                floating,
+            ],
+            TilingDirection::Horizontal,
+            FocusState::Focused,
+            DisplayState::Shown,
+        );
+        workspace.set_focus_order(vec!["w1".to_string(), "w3".to_string(), "w2".to_string()]);
+
+        let ordered: Vec<&str> = workspace
+            .windows_by_focus_order()
+            .iter()
+            .map(|w| w.id().as_str())
+            .collect();
+        assert_eq!(ordered, vec!["w1", "w3", "w2"]);
+
+        // Tiling windows are preferred over the more recent floating one
+        let fallback: Vec<&str> = workspace
+            .focus_fallback_order(&WindowId::new("w1".to_string()))
+            .iter()
+            .map(|w| w.id().as_str())
+            .collect();
+        assert_eq!(fallback, vec!["w2", "w3"]);
+    }
+
     #[test]
     fn should_calculate_horizontal_layout() {
         let window1 = create_test_window("w1", "Window 1", "app1");
@@ -3,37 +3,218 @@
 // Application bootstrap following composition root pattern.
 // Handles CLI argument parsing and dependency injection.
 
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 use crossterm::{
     execute,
     style::{Color, Print, ResetColor, SetForegroundColor},
 };
-use glazewm_debug::{AppState, TuiApp, UpdateConfig, UpdateLoop};
-use std::io;
-use std::path::PathBuf;
-use std::time::Duration;
+use glazewm_debug::app::assertions::{self, LayoutSpec};
+use glazewm_debug::app::dump::{self, Validation};
+use glazewm_debug::app::watch::{self, WatchFormat, Watcher};
+use glazewm_debug::app::{self, record, report};
+use glazewm_debug::app::{Agent, HttpServer};
+use glazewm_debug::cli::{self, FaultConfig, FaultKind, InputSource, RedactionMode, Scenario};
+use glazewm_debug::domain::{snapshot_diff, tiling, Gaps, Insets, TilingModel};
+use glazewm_debug::tui::export::{self, FrameFormat, FrameSnapshot};
+use glazewm_debug::tui::DisplayMode;
+use glazewm_debug::utils::log_buffer::LogBuffer;
+use glazewm_debug::{AppState, GlazewmParser, TuiApp, UpdateConfig, UpdateLoop};
+use std::fs::File;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime};
 use tokio::select;
 use tracing::{error, info};
 
+type MainResult = Result<ExitCode, Box<dyn std::error::Error>>;
+
 #[derive(Parser, Debug)]
 #[command(
     name = "glazewm-debug",
     about = "A CLI+JSON TUI debugger for glazewm window manager state visualization",
-    version
+    version,
+    // Without a command the TUI runs, taking its options directly
+    args_conflicts_with_subcommands = true
 )]
-struct Args {
-    /// Color test mode - test terminal color support with crossterm
-    #[arg(long)]
-    color_test: bool,
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
 
-    /// Refresh interval in milliseconds
-    #[arg(short, long, default_value = "1000")]
-    refresh_rate: u64,
+    #[command(flatten)]
+    tui: TuiArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Show the state in the terminal UI (the default)
+    Tui(TuiArgs),
+    /// Query glazewm once, print the state as plain text and exit
+    Dump(ConnectionArgs),
+    /// Poll glazewm and print one timestamped line per change
+    Watch {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+
+        /// Print newline-delimited JSON events
+        #[arg(long)]
+        json: bool,
+    },
+    /// Save every polled `query monitors` response as newline-delimited JSON
+    Record {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+
+        /// File to write to; stdout by default
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Stop after this many responses
+        #[arg(long, value_name = "N")]
+        count: Option<usize>,
+    },
+    /// Show a recording made with `record` in the terminal UI
+    Replay {
+        /// Recording to play
+        file: PathBuf,
+
+        /// Milliseconds each recorded response is shown
+        #[arg(short, long, default_value = "1000")]
+        refresh_rate: u64,
+
+        /// Minimal output mode
+        #[arg(short, long)]
+        quiet: bool,
+    },
+    /// Print what changed between two captured `query monitors` documents;
+    /// exits non-zero if anything did
+    Diff {
+        /// State before
+        before: PathBuf,
+        /// State after
+        after: PathBuf,
+
+        /// Print the differences as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Query glazewm once and report parse warnings and inconsistencies;
+    /// exits non-zero if there are any
+    Validate {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+
+        /// Print the findings as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Query glazewm once and check the layout expectations in SPEC; exits
+    /// non-zero if any fails
+    Assert {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+
+        /// JSON file of assertions
+        spec: PathBuf,
+
+        /// Print the results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Write a bug report archive with raw query responses, recent
+    /// snapshots, findings, log lines and versions
+    Report {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+
+        /// Archive to write (default: glazewm-report-<time>.tar)
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Query glazewm once and render the TUI off-screen, as plain text, ANSI,
+    /// SVG or HTML
+    Frame {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+
+        /// text, ansi, svg or html
+        #[arg(long, value_name = "FORMAT", default_value = "text")]
+        format: FrameFormat,
+
+        /// Terminal size in cells
+        #[arg(long, value_name = "COLSxROWS", default_value = "120x40", value_parser = parse_size)]
+        size: (u16, u16),
+
+        /// View to render
+        #[arg(long, default_value = "detailed", value_parser = ["detailed", "compact", "focus-stack"])]
+        mode: String,
+
+        /// File to write to; stdout by default
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Serve glazewm state to remote viewers (`--connect`) on ADDR, e.g.
+    /// 127.0.0.1:7878
+    Agent {
+        #[command(flatten)]
+        connection: ConnectionArgs,
+
+        /// Address to listen on
+        address: String,
+    },
+    /// Test terminal color support with crossterm
+    ColorTest,
+}
+
+impl Command {
+    /// Whether logging is limited to errors; `diff` has nothing to log
+    fn is_quiet(&self) -> bool {
+        match self {
+            Command::Tui(TuiArgs { connection, .. })
+            | Command::Dump(connection)
+            | Command::Watch { connection, .. }
+            | Command::Record { connection, .. }
+            | Command::Validate { connection, .. }
+            | Command::Assert { connection, .. }
+            | Command::Report { connection, .. }
+            | Command::Frame { connection, .. }
+            | Command::Agent { connection, .. } => connection.quiet,
+            Command::Replay { quiet, .. } => *quiet,
+            Command::Diff { .. } | Command::ColorTest => true,
+        }
+    }
+}
+
+/// Options of the terminal UI
+#[derive(Args, Debug)]
+struct TuiArgs {
+    #[command(flatten)]
+    connection: ConnectionArgs,
+
+    /// Serve the state as JSON on http://127.0.0.1:PORT while the TUI runs
+    /// (`/state`, `/windows`, `/validate`, `/geometry`, `/events`, `/metrics`,
+    /// `/graph.dot`, `/graph.mmd`)
+    #[arg(long, value_name = "PORT")]
+    http: Option<u16>,
+
+    /// Keep polling and serving `--http` without showing the TUI, e.g. to
+    /// collect metrics over days
+    #[arg(long, requires = "http")]
+    headless: bool,
+}
 
+/// Where glazewm state comes from and how it is polled, shared by every
+/// command that talks to glazewm
+#[derive(Args, Debug)]
+struct ConnectionArgs {
     /// Minimal output mode
     #[arg(short, long)]
     quiet: bool,
 
+    /// Refresh interval in milliseconds
+    #[arg(short, long, default_value = "1000")]
+    refresh_rate: u64,
+
     /// Path to glazewm executable
     #[arg(long, default_value = "glazewm")]
     glazewm_path: PathBuf,
@@ -42,47 +223,339 @@ struct Args {
     #[arg(long, default_value = "10000")]
     timeout: u64,
 
-    /// Run in demo mode with sample data (no glazewm required)
+    /// Run against the built-in glazewm simulator (no glazewm required),
+    /// optionally playing a JSON scenario timeline
+    #[arg(long, value_name = "SCENARIO", num_args = 0..=1)]
+    demo: Option<Option<PathBuf>>,
+
+    /// Show captured `glazewm query monitors` JSON instead of running
+    /// glazewm: `-` reads a stream of documents from stdin, a path is
+    /// re-read whenever the file changes
+    #[arg(long, value_name = "FILE", conflicts_with = "demo")]
+    input: Option<PathBuf>,
+
+    /// Show the state served by an agent (`agent`) on another machine
+    #[arg(long, value_name = "ADDR", conflicts_with_all = ["demo", "input"])]
+    connect: Option<String>,
+
+    #[command(flatten)]
+    faults: FaultArgs,
+
+    #[command(flatten)]
+    redaction: RedactionArgs,
+
+    #[command(flatten)]
+    layout: LayoutArgs,
+}
+
+/// Faults injected into glazewm calls
+#[derive(Args, Debug)]
+struct FaultArgs {
+    /// Share of glazewm calls that fail on purpose (0.0-1.0), for testing
+    /// error handling
+    #[arg(long, value_name = "RATE", default_value = "0")]
+    fault_rate: f64,
+
+    /// Faults to inject, comma separated: timeout, exit, truncated, schema,
+    /// unsuccessful [default: all]
+    #[arg(long, value_name = "KINDS", value_delimiter = ',')]
+    faults: Vec<FaultKind>,
+
+    /// Delay in milliseconds added to every glazewm call
+    #[arg(long, value_name = "MS", default_value = "0")]
+    fault_latency: u64,
+
+    /// Seed for the injected fault sequence, to replay a run
+    #[arg(long, value_name = "SEED")]
+    fault_seed: Option<u64>,
+}
+
+/// Rewriting of window titles and process names before anything sees them
+#[derive(Args, Debug)]
+struct RedactionArgs {
+    /// Redact window titles: `hash` (titles and process names) or
+    /// `process` (`Code #1`, `Code #2`, ...) [default: off]
+    #[arg(long, value_name = "MODE", value_parser = ["off", "hash", "process"])]
+    redact: Option<String>,
+
+    /// Redact with the regex rules in a JSON file
+    #[arg(long, value_name = "FILE", conflicts_with = "redact")]
+    redact_rules: Option<PathBuf>,
+}
+
+impl RedactionArgs {
+    fn mode(&self) -> RedactionMode {
+        if let Some(path) = &self.redact_rules {
+            return RedactionMode::rules_from_file(path).unwrap_or_else(|e| exit_with_error(e));
+        }
+        match self.redact.as_deref() {
+            Some("hash") => RedactionMode::Hash,
+            Some("process") => RedactionMode::Process,
+            _ => RedactionMode::Off,
+        }
+    }
+}
+
+/// glazewm's gap settings, for the expected tiling layout
+#[derive(Args, Debug)]
+struct LayoutArgs {
+    /// `gaps.inner_gap` from the glazewm config, in pixels
+    #[arg(long, value_name = "PX", default_value = "0")]
+    inner_gap: u32,
+
+    /// `gaps.outer_gap` from the glazewm config: one value for every side or
+    /// `TOP,RIGHT,BOTTOM,LEFT`, in pixels
+    #[arg(long, value_name = "PX", value_parser = parse_gap)]
+    outer_gap: Option<Insets>,
+
+    /// Do not scale gaps with the monitor DPI (`gaps.scale_with_dpi: false`)
     #[arg(long)]
-    demo: bool,
+    no_gap_scaling: bool,
+
+    /// Pixels a tiling window may be off from the expected layout before it
+    /// is flagged
+    #[arg(long, value_name = "PX", default_value_t = tiling::DEFAULT_TOLERANCE)]
+    layout_tolerance: u32,
+}
+
+impl LayoutArgs {
+    fn model(&self) -> TilingModel {
+        let defaults = Gaps::default();
+        let gaps = Gaps {
+            inner: self.inner_gap,
+            outer: self.outer_gap.unwrap_or(defaults.outer),
+            scale_with_dpi: !self.no_gap_scaling,
+        };
+        TilingModel::new(gaps, self.layout_tolerance)
+    }
 }
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> MainResult {
     // Parse command line arguments
-    let args = Args::parse();
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or(Command::Tui(cli.tui));
+
+    // Color test mode talks to the terminal directly, without logging
+    if !matches!(command, Command::ColorTest) {
+        init_logging(command.is_quiet());
+        info!(
+            "Starting glazewm-debug v{} (CLI+JSON architecture)",
+            env!("CARGO_PKG_VERSION")
+        );
+    }
 
-    // Handle color test mode
-    if args.color_test {
-        test_colors().await?;
-        return Ok(());
+    match command {
+        Command::Tui(args) => run_tui(args).await,
+        Command::Dump(connection) => run_dump(connection).await,
+        Command::Watch { connection, json } => run_watch(connection, json).await,
+        Command::Record {
+            connection,
+            output,
+            count,
+        } => run_record(connection, output, count).await,
+        Command::Replay {
+            file, refresh_rate, ..
+        } => run_replay(&file, refresh_rate).await,
+        Command::Diff {
+            before,
+            after,
+            json,
+        } => run_diff(&before, &after, json),
+        Command::Validate { connection, json } => run_validate(connection, json).await,
+        Command::Assert {
+            connection,
+            spec,
+            json,
+        } => run_assert(connection, &spec, json).await,
+        Command::Report { connection, output } => run_report(connection, output).await,
+        Command::Frame {
+            connection,
+            format,
+            size,
+            mode,
+            output,
+        } => run_frame(connection, format, size, &mode, output).await,
+        Command::Agent {
+            connection,
+            address,
+        } => run_agent(connection, &address).await,
+        Command::ColorTest => {
+            test_colors().await?;
+            Ok(ExitCode::SUCCESS)
+        }
     }
+}
 
-    // Initialize logging
-    init_logging(args.quiet);
+/// Print an error and exit, for failures that happen before anything runs
+fn exit_with_error(e: impl std::fmt::Display) -> ! {
+    eprintln!("Error: {}", e);
+    std::process::exit(1);
+}
 
-    info!(
-        "Starting glazewm-debug v{} (CLI+JSON architecture)",
-        env!("CARGO_PKG_VERSION")
-    );
+/// `120x40` as columns and rows
+fn parse_size(value: &str) -> Result<(u16, u16), String> {
+    let parse = |part: Option<&str>| {
+        part.and_then(|part| part.parse::<u16>().ok())
+            .filter(|cells| *cells > 0)
+    };
+    let mut parts = value.split('x');
+    match (parse(parts.next()), parse(parts.next()), parts.next()) {
+        (Some(columns), Some(rows), None) => Ok((columns, rows)),
+        _ => Err(format!("expected COLSxROWS, e.g. 120x40, got '{}'", value)),
+    }
+}
 
-    // Create application state
-    let state = AppState::new();
+/// `10` for every side, or `TOP,RIGHT,BOTTOM,LEFT` like glazewm's config
+fn parse_gap(value: &str) -> Result<Insets, String> {
+    let sides: Vec<i32> = value
+        .split(',')
+        .map(|side| side.trim().trim_end_matches("px").parse::<i32>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("expected PX or TOP,RIGHT,BOTTOM,LEFT, got '{}'", value))?;
+    match sides[..] {
+        [all] => Ok(Insets {
+            left: all,
+            top: all,
+            right: all,
+            bottom: all,
+        }),
+        [top, right, bottom, left] => Ok(Insets {
+            left,
+            top,
+            right,
+            bottom,
+        }),
+        _ => Err(format!(
+            "expected PX or TOP,RIGHT,BOTTOM,LEFT, got '{}'",
+            value
+        )),
+    }
+}
 
-    // Create update loop configuration
+/// Create the update loop for the chosen input source, with faults if asked
+fn build_update_loop(connection: ConnectionArgs, state: AppState) -> UpdateLoop {
     let update_config = UpdateConfig {
-        refresh_interval: Duration::from_millis(args.refresh_rate),
-        command_timeout: Duration::from_millis(args.timeout),
-        glazewm_path: args.glazewm_path,
+        refresh_interval: Duration::from_millis(connection.refresh_rate),
+        command_timeout: Duration::from_millis(connection.timeout),
+        glazewm_path: connection.glazewm_path,
     };
 
     // Create update loop (demo mode or real mode)
-    let update_loop = if args.demo {
-        info!("Running in demo mode with sample data");
-        UpdateLoop::new_demo(update_config, state.clone())
+    let update_loop = match (connection.connect, connection.input, connection.demo) {
+        (Some(address), _, _) => {
+            info!("Following agent at {}", address);
+            UpdateLoop::new_remote(update_config, state, address)
+        }
+        (None, Some(input), _) => {
+            let source = InputSource::from_arg(&input);
+            info!("Reading glazewm state from {:?}", source);
+            UpdateLoop::new_input(update_config, state, source)
+        }
+        (None, None, Some(Some(path))) => {
+            info!("Running in demo mode with scenario {}", path.display());
+            Scenario::load(&path)
+                .and_then(|scenario| UpdateLoop::new_scenario(update_config, state, scenario))
+                .unwrap_or_else(|e| exit_with_error(e))
+        }
+        (None, None, Some(None)) => {
+            info!("Running in demo mode with sample data");
+            UpdateLoop::new_demo(update_config, state)
+        }
+        (None, None, None) => UpdateLoop::new(update_config, state),
+    };
+
+    let redaction = connection.redaction.mode();
+    if !matches!(redaction, RedactionMode::Off) {
+        info!("Redacting window titles ({})", redaction.name());
+    }
+    let update_loop = update_loop
+        .with_redaction(redaction)
+        .with_tiling_model(connection.layout.model());
+
+    // Optionally break the client on purpose
+    let faults = connection.faults;
+    if faults.fault_rate > 0.0 || faults.fault_latency > 0 {
+        let mut config = FaultConfig {
+            rate: faults.fault_rate.clamp(0.0, 1.0),
+            kinds: faults.faults,
+            latency: Duration::from_millis(faults.fault_latency),
+            ..Default::default()
+        };
+        if let Some(seed) = faults.fault_seed {
+            config.seed = seed;
+        }
+        info!("Injecting faults: {:?}", config);
+        update_loop.with_faults(config)
     } else {
-        UpdateLoop::new(update_config, state.clone())
+        update_loop
+    }
+}
+
+/// Treat a closed stdout (e.g. `| head`) as a normal end
+fn ignore_broken_pipe<T: Default>(result: io::Result<T>) -> io::Result<T> {
+    match result {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(T::default()),
+        result => result,
+    }
+}
+
+async fn run_tui(args: TuiArgs) -> MainResult {
+    let state = AppState::new();
+    let update_loop = build_update_loop(args.connection, state.clone());
+    run_with_tui(update_loop, state, args.http, args.headless).await
+}
+
+async fn run_replay(file: &Path, refresh_rate: u64) -> MainResult {
+    let documents = cli::read_recording(file).unwrap_or_else(|e| exit_with_error(e));
+    info!(
+        "Replaying {} response(s) from {}",
+        documents.len(),
+        file.display()
+    );
+
+    let state = AppState::new();
+    let config = UpdateConfig {
+        refresh_interval: Duration::from_millis(refresh_rate),
+        ..Default::default()
     };
+    let update_loop = UpdateLoop::new_replay(config, state.clone(), documents);
+    run_with_tui(update_loop, state, None, false).await
+}
+
+/// Run the update loop with the TUI, an HTTP API, or both
+async fn run_with_tui(
+    update_loop: UpdateLoop,
+    state: AppState,
+    http: Option<u16>,
+    headless: bool,
+) -> MainResult {
+    // Perform initial data load before serving it
+    info!("Loading initial glazewm state...");
+    if let Err(e) = update_loop.update_now().await {
+        error!("Failed to load initial state: {}", e);
+        // Continue anyway - TUI will show "No Data" message
+    } else {
+        info!("Initial state loaded successfully");
+    }
+
+    // Optional HTTP API reading the same state as the TUI
+    if let Some(port) = http {
+        let server = HttpServer::bind(port, state.clone()).await?;
+        if headless {
+            let _ = writeln!(
+                io::stderr(),
+                "Serving the HTTP API on http://{}",
+                server.local_addr()?
+            );
+        }
+        tokio::spawn(server.run());
+    }
+
+    if headless {
+        update_loop.run().await?;
+        return Ok(ExitCode::SUCCESS);
+    }
 
     // Create TUI application
     let mut tui_app = match TuiApp::new() {
@@ -95,22 +568,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info!("Application started successfully");
 
-    // Perform initial data load before starting TUI
-    info!("Loading initial glazewm state...");
-    if let Err(e) = update_loop.update_now().await {
-        error!("Failed to load initial state: {}", e);
-        // Continue anyway - TUI will show "No Data" message
-    } else {
-        info!("Initial state loaded successfully");
-    }
-
     // Run both the update loop and TUI concurrently
     let result = select! {
         update_result = update_loop.run() => {
             match update_result {
                 Ok(()) => {
                     info!("Update loop finished normally");
-                    Ok(())
+                    Ok(ExitCode::SUCCESS)
                 }
                 Err(e) => {
                     error!("Update loop error: {}", e);
@@ -122,7 +586,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             match tui_result {
                 Ok(()) => {
                     info!("TUI finished normally");
-                    Ok(())
+                    Ok(ExitCode::SUCCESS)
                 }
                 Err(e) => {
                     error!("TUI error: {}", e);
@@ -136,6 +600,172 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     result
 }
 
+/// A single update, printed to stdout
+async fn run_dump(connection: ConnectionArgs) -> MainResult {
+    let state = AppState::new();
+    let update_loop = build_update_loop(connection, state.clone());
+    if let Err(e) = update_loop.update_now().await {
+        exit_with_error(e);
+    }
+    print!(
+        "{}",
+        dump::format_state(
+            &state.get_monitors().await,
+            &state.get_parse_warnings().await,
+            &state.get_inconsistencies().await,
+        )
+    );
+    Ok(ExitCode::SUCCESS)
+}
+
+/// A change log on stdout instead of the TUI
+async fn run_watch(connection: ConnectionArgs, json: bool) -> MainResult {
+    let update_loop = build_update_loop(connection, AppState::new());
+    let format = if json {
+        WatchFormat::Json
+    } else {
+        WatchFormat::Text
+    };
+    let watcher = Watcher::new(format, io::stdout().lock());
+    ignore_broken_pipe(watch::run(&update_loop, watcher).await)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn run_record(
+    connection: ConnectionArgs,
+    output: Option<PathBuf>,
+    count: Option<usize>,
+) -> MainResult {
+    let update_loop = build_update_loop(connection, AppState::new());
+    let written = match output {
+        Some(path) => record::run(&update_loop, File::create(path)?, count).await?,
+        None => ignore_broken_pipe(record::run(&update_loop, io::stdout().lock(), count).await)?,
+    };
+    info!("Recorded {} response(s)", written);
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Like diff(1), exits with 1 when the documents differ
+fn run_diff(before: &Path, after: &Path, json: bool) -> MainResult {
+    let parse = |path: &Path| {
+        cli::read_capture(path)
+            .and_then(|document| GlazewmParser::parse_monitors(&document))
+            .unwrap_or_else(|e| exit_with_error(format!("{}: {}", path.display(), e)))
+            .monitors
+    };
+    let diff = snapshot_diff::diff(&parse(before), &parse(after));
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        let color = io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        print!("{}", app::diff::format_diff(&diff, color));
+    }
+    Ok(if diff.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    })
+}
+
+/// Exits with 1 when the update failed or found problems
+async fn run_validate(connection: ConnectionArgs, json: bool) -> MainResult {
+    let state = AppState::new();
+    let update_loop = build_update_loop(connection, state.clone());
+    // A failure is part of the report
+    let _ = update_loop.update_now().await;
+
+    let validation = Validation::from_state(&state).await;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&validation)?);
+    } else {
+        print!("{}", validation.to_text());
+    }
+    Ok(if validation.valid {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    })
+}
+
+/// Exits with 1 when any assertion fails
+async fn run_assert(connection: ConnectionArgs, spec: &Path, json: bool) -> MainResult {
+    let spec = LayoutSpec::load(spec).unwrap_or_else(|e| exit_with_error(e));
+    let state = AppState::new();
+    let update_loop = build_update_loop(connection, state.clone());
+    if let Err(e) = update_loop.update_now().await {
+        exit_with_error(e);
+    }
+
+    let outcomes = spec.evaluate(&state.get_monitors().await);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&outcomes)?);
+    } else {
+        if let Some(name) = &spec.name {
+            println!("{}", name);
+        }
+        print!("{}", assertions::format_outcomes(&outcomes));
+    }
+    Ok(if outcomes.iter().all(|outcome| outcome.passed) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    })
+}
+
+/// A failing update is part of the report rather than a reason to stop
+async fn run_report(connection: ConnectionArgs, output: Option<PathBuf>) -> MainResult {
+    let update_loop = build_update_loop(connection, AppState::new());
+    let _ = update_loop.update_now().await;
+
+    let path =
+        output.unwrap_or_else(|| PathBuf::from(report::default_file_name(SystemTime::now())));
+    report::write(&update_loop, &path).await?;
+    let _ = writeln!(io::stderr(), "Wrote bug report to {}", path.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Like the TUI's first frame, without a terminal; a failed update is shown
+/// in the frame as it would be on screen
+async fn run_frame(
+    connection: ConnectionArgs,
+    format: FrameFormat,
+    (width, height): (u16, u16),
+    mode: &str,
+    output: Option<PathBuf>,
+) -> MainResult {
+    let state = AppState::new();
+    let update_loop = build_update_loop(connection, state.clone());
+    let _ = update_loop.update_now().await;
+
+    let mut snapshot = FrameSnapshot::capture(&state).await;
+    snapshot.mode = match mode {
+        "compact" => DisplayMode::Compact,
+        "focus-stack" => DisplayMode::FocusStack,
+        _ => DisplayMode::Detailed,
+    };
+    let buffer = export::render_frame(&snapshot.view(), width, height);
+    let text = export::export(&buffer, format);
+    match output {
+        Some(path) => std::fs::write(path, text)?,
+        None => ignore_broken_pipe(io::stdout().lock().write_all(text.as_bytes()))?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Serve the state to remote viewers instead of showing it
+async fn run_agent(connection: ConnectionArgs, address: &str) -> MainResult {
+    let update_loop = build_update_loop(connection, AppState::new());
+    let agent = Agent::bind(address, update_loop).await?;
+    let _ = writeln!(
+        io::stderr(),
+        "Serving glazewm state on {}",
+        agent.local_addr()?
+    );
+    agent.run().await?;
+    Ok(ExitCode::SUCCESS)
+}
+
 async fn test_colors() -> Result<(), Box<dyn std::error::Error>> {
     let mut stdout = io::stdout();
 
@@ -259,20 +889,30 @@ async fn test_colors() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 fn init_logging(quiet: bool) {
-    use tracing_subscriber::FmtSubscriber;
+    use tracing::level_filters::LevelFilter;
+    use tracing_subscriber::{fmt, layer::SubscriberExt, Layer};
 
     let level = if quiet {
-        tracing::Level::ERROR
+        LevelFilter::ERROR
     } else {
         std::env::var("RUST_LOG")
-            .map(|_| tracing::Level::DEBUG)
-            .unwrap_or(tracing::Level::INFO)
+            .map(|_| LevelFilter::DEBUG)
+            .unwrap_or(LevelFilter::INFO)
     };
 
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(level)
+    let stderr = fmt::layer()
+        .with_target(false)
+        .with_writer(io::stderr)
+        // A closed stderr (e.g. a detached agent) must not abort the program
+        .log_internal_errors(false)
+        .with_filter(level);
+    // Bug reports include recent lines even when stderr shows none
+    let recent = fmt::layer()
         .with_target(false)
-        .finish();
+        .with_ansi(false)
+        .with_writer(LogBuffer::global)
+        .with_filter(LevelFilter::DEBUG);
+    let subscriber = tracing_subscriber::registry().with(stderr).with(recent);
 
     tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
 }
@@ -4,16 +4,25 @@
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event},
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{
+        self, disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+    },
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io::{self, Stdout};
-use std::time::Duration;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
 use tokio::time::sleep;
-use tracing::{debug, error};
+use tracing::debug;
 
+use crate::app::graph;
 use crate::app::AppState;
+use crate::tui::export::{self, FrameSnapshot};
 use crate::tui::{InputHandler, Renderer};
+use crate::utils::stamped_files;
+
+/// Shown instead of sending window manager keys to a client that rejects them
+const COMMANDS_NEED_DEMO: &str = "Window manager keys only work with --demo";
 
 /// Display mode for the TUI
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,6 +31,17 @@ pub enum DisplayMode {
     Detailed,
     /// Compact tree-style view
     Compact,
+    /// Focus path of the focused window and its fallback order
+    FocusStack,
+}
+
+/// Order in which workspaces and windows are listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Order reported by glazewm
+    Natural,
+    /// Most recently focused first, from glazewm's `childFocusOrder`
+    MostRecent,
 }
 
 /// Main TUI application that manages the terminal interface
@@ -112,6 +132,50 @@ impl TuiApp {
                                 debug!("Toggle ignored (debounce active)");
                             }
                         }
+                        InputAction::ToggleSort => {
+                            state.toggle_sort_order().await;
+                            debug!("Sort order toggled to: {:?}", state.get_sort_order().await);
+                        }
+                        InputAction::ToggleFocusStack => {
+                            state.toggle_focus_stack().await;
+                            debug!(
+                                "Display mode toggled to: {:?}",
+                                state.get_display_mode().await
+                            );
+                        }
+                        InputAction::TogglePerfHud => {
+                            state.toggle_perf_hud().await;
+                        }
+                        InputAction::WriteReport => {
+                            state.set_notice("Writing bug report...".to_string()).await;
+                            state.request_report().await;
+                        }
+                        InputAction::ExportGraph => {
+                            let monitors = state.get_monitors().await;
+                            let written =
+                                graph::write_files(&monitors, Path::new("."), SystemTime::now());
+                            state
+                                .set_notice(stamped_files::notice(&written, "export the tree"))
+                                .await;
+                        }
+                        InputAction::SaveFrame => {
+                            let (width, height) = terminal::size()?;
+                            let snapshot = FrameSnapshot::capture(&state).await;
+                            let buffer = export::render_frame(&snapshot.view(), width, height);
+                            let written =
+                                export::write_files(&buffer, Path::new("."), SystemTime::now());
+                            state
+                                .set_notice(stamped_files::notice(&written, "save the frame"))
+                                .await;
+                        }
+                        InputAction::Command(command) => {
+                            if state.commands_enabled().await {
+                                debug!("User queued command: {}", command);
+                                state.queue_command(command).await;
+                            } else {
+                                state.set_notice(COMMANDS_NEED_DEMO.to_string()).await;
+                            }
+                        }
                         InputAction::None => {
                             // No action needed
                         }
@@ -139,13 +203,17 @@ impl TuiApp {
             }
 
             // Get current state
-            let monitors = state.get_monitors().await;
-            let display_mode = state.get_display_mode().await;
+            let snapshot = FrameSnapshot::capture(&state).await;
 
             // Render frame
+            let draw_started = Instant::now();
             self.terminal.draw(|frame| {
-                self.renderer.render(frame, &monitors, display_mode);
+                self.renderer.render(frame, &snapshot.view());
             })?;
+            let frame_time = draw_started.elapsed();
+            state
+                .update_perf(|perf| perf.frame_time.record_duration(frame_time))
+                .await;
 
             // 60fps rendering (16ms per frame)
             sleep(Duration::from_millis(16)).await;
@@ -170,7 +238,7 @@ impl Drop for TuiApp {
 }
 
 /// Actions that can be triggered by user input
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum InputAction {
     /// Exit the application
     Quit,
@@ -178,6 +246,20 @@ pub enum InputAction {
     Refresh,
     /// Toggle display mode between detailed and compact
     ToggleMode,
+    /// Toggle between natural and most-recently-focused ordering
+    ToggleSort,
+    /// Show or hide the focus stack view
+    ToggleFocusStack,
+    /// Show or hide the performance HUD
+    TogglePerfHud,
+    /// Write a bug report bundle into the working directory
+    WriteReport,
+    /// Write the container tree as DOT and Mermaid into the working directory
+    ExportGraph,
+    /// Write the current frame as text and HTML into the working directory
+    SaveFrame,
+    /// Send a glazewm command, e.g. `focus --direction left`
+    Command(String),
     /// No action
     None,
 }
@@ -0,0 +1,552 @@
+// Frame export
+// Renders a frame into an off-screen buffer and writes it as plain text,
+// ANSI-colored text, SVG or standalone HTML, instead of sharing screenshots
+
+use ratatui::{
+    backend::TestBackend,
+    buffer::Buffer,
+    style::{Color, Modifier},
+    Terminal,
+};
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::SystemTime;
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::perf::PerfStats;
+use crate::app::AppState;
+use crate::cli::ParseWarning;
+use crate::domain::{GeometryReport, Inconsistency, LayoutDeviation, Monitor, Placeholder};
+use crate::tui::{DisplayMode, RenderState, Renderer, SortOrder};
+use crate::utils::stamped_files;
+
+/// Colors of `Color::Reset`, i.e. the terminal's own
+const FOREGROUND: &str = "#d0d0d0";
+const BACKGROUND: &str = "#1c1c1c";
+
+/// The 16 named colors, in ANSI order (xterm defaults)
+const PALETTE: [&str; 16] = [
+    "#000000", "#cd0000", "#00cd00", "#cdcd00", "#0000ee", "#cd00cd", "#00cdcd", "#e5e5e5",
+    "#7f7f7f", "#ff0000", "#00ff00", "#ffff00", "#5c5cff", "#ff00ff", "#00ffff", "#ffffff",
+];
+
+/// Cell size of SVG exports, in pixels
+const CELL_WIDTH: usize = 9;
+const CELL_HEIGHT: usize = 18;
+
+/// Output format of an exported frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameFormat {
+    /// Characters only, trailing spaces trimmed
+    Text,
+    /// Text with ANSI escape sequences, for `cat` or `less -R`
+    Ansi,
+    Svg,
+    Html,
+}
+
+impl FrameFormat {
+    pub const ALL: [FrameFormat; 4] = [
+        FrameFormat::Text,
+        FrameFormat::Ansi,
+        FrameFormat::Svg,
+        FrameFormat::Html,
+    ];
+
+    /// Name on the command line
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FrameFormat::Text => "text",
+            FrameFormat::Ansi => "ansi",
+            FrameFormat::Svg => "svg",
+            FrameFormat::Html => "html",
+        }
+    }
+
+    /// File extension of exported files
+    pub fn extension(self) -> &'static str {
+        match self {
+            FrameFormat::Text => "txt",
+            FrameFormat::Ansi => "ans",
+            FrameFormat::Svg => "svg",
+            FrameFormat::Html => "html",
+        }
+    }
+}
+
+impl FromStr for FrameFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        FrameFormat::ALL
+            .into_iter()
+            .find(|format| format.as_str() == value)
+            .ok_or_else(|| {
+                let names: Vec<&str> = FrameFormat::ALL.iter().map(|f| f.as_str()).collect();
+                format!(
+                    "unknown format '{}', expected one of: {}",
+                    value,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Owned copy of everything a frame shows, so it can be rendered away from
+/// the `AppState` locks
+#[derive(Debug, Clone)]
+pub struct FrameSnapshot {
+    pub monitors: Vec<Monitor>,
+    pub placeholders: Vec<Placeholder>,
+    pub warnings: Vec<ParseWarning>,
+    pub inconsistencies: Vec<Inconsistency>,
+    pub deviations: Vec<LayoutDeviation>,
+    pub geometry: GeometryReport,
+    pub mode: DisplayMode,
+    pub sort_order: SortOrder,
+    pub last_error: Option<String>,
+    pub perf: Option<PerfStats>,
+    pub notice: Option<String>,
+}
+
+impl FrameSnapshot {
+    /// Read what the TUI would show right now
+    pub async fn capture(state: &AppState) -> Self {
+        let perf = if state.is_perf_hud_visible().await {
+            Some(state.get_perf().await)
+        } else {
+            None
+        };
+        Self {
+            monitors: state.get_monitors().await,
+            placeholders: state.get_monitor_placeholders().await,
+            warnings: state.get_parse_warnings().await,
+            inconsistencies: state.get_inconsistencies().await,
+            deviations: state.get_layout_deviations().await,
+            geometry: state.get_geometry().await,
+            mode: state.get_display_mode().await,
+            sort_order: state.get_sort_order().await,
+            last_error: state.get_last_error().await,
+            perf,
+            notice: state.get_notice().await,
+        }
+    }
+
+    pub fn view(&self) -> RenderState<'_> {
+        RenderState {
+            monitors: &self.monitors,
+            placeholders: &self.placeholders,
+            warnings: &self.warnings,
+            inconsistencies: &self.inconsistencies,
+            deviations: &self.deviations,
+            geometry: &self.geometry,
+            mode: self.mode,
+            sort_order: self.sort_order,
+            last_error: self.last_error.as_deref(),
+            perf: self.perf.as_ref(),
+            notice: self.notice.as_deref(),
+        }
+    }
+}
+
+/// Draw one frame of `width` x `height` cells off-screen
+pub fn render_frame(view: &RenderState, width: u16, height: u16) -> Buffer {
+    let mut terminal =
+        Terminal::new(TestBackend::new(width, height)).expect("the test backend cannot fail");
+    let renderer = Renderer::new();
+    terminal
+        .draw(|frame| renderer.render(frame, view))
+        .expect("the test backend cannot fail");
+    terminal.backend().buffer().clone()
+}
+
+/// Write `buffer` in `format`
+pub fn export(buffer: &Buffer, format: FrameFormat) -> String {
+    let rows = rows(buffer);
+    match format {
+        FrameFormat::Text => to_text(&rows),
+        FrameFormat::Ansi => to_ansi(&rows),
+        FrameFormat::Svg => to_svg(&rows, buffer.area.width, buffer.area.height),
+        FrameFormat::Html => to_html(&rows),
+    }
+}
+
+/// Write the frame as `glazewm-frame-<time>.txt` and `.html` into `dir`
+pub fn write_files(buffer: &Buffer, dir: &Path, time: SystemTime) -> io::Result<Vec<PathBuf>> {
+    let files = [FrameFormat::Text, FrameFormat::Html]
+        .map(|format| (format.extension(), export(buffer, format)));
+    stamped_files::write(dir, "glazewm-frame", time, files)
+}
+
+/// Cell attributes shared by a run of characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CellStyle {
+    fg: Color,
+    bg: Color,
+    modifier: Modifier,
+}
+
+/// Characters of one style, starting at `column`
+#[derive(Debug)]
+struct Run {
+    column: usize,
+    text: String,
+    style: CellStyle,
+}
+
+/// Group each row into runs, leaving out the cells hidden behind wide
+/// characters
+fn rows(buffer: &Buffer) -> Vec<Vec<Run>> {
+    let width = buffer.area.width as usize;
+    buffer
+        .content
+        .chunks(width.max(1))
+        .map(|cells| {
+            let mut runs: Vec<Run> = Vec::new();
+            let mut hidden = 0;
+            for (column, cell) in cells.iter().enumerate() {
+                if hidden > 0 {
+                    hidden -= 1;
+                    continue;
+                }
+                if cell.skip {
+                    continue;
+                }
+                let symbol = cell.symbol();
+                hidden = symbol.width().saturating_sub(1);
+                let style = CellStyle {
+                    fg: cell.fg,
+                    bg: cell.bg,
+                    modifier: cell.modifier,
+                };
+                match runs.last_mut() {
+                    Some(run) if run.style == style => run.text.push_str(symbol),
+                    _ => runs.push(Run {
+                        column,
+                        text: symbol.to_string(),
+                        style,
+                    }),
+                }
+            }
+            runs
+        })
+        .collect()
+}
+
+fn to_text(rows: &[Vec<Run>]) -> String {
+    let mut out = String::new();
+    for runs in rows {
+        let line: String = runs.iter().map(|run| run.text.as_str()).collect();
+        let _ = writeln!(out, "{}", line.trim_end());
+    }
+    out
+}
+
+fn to_ansi(rows: &[Vec<Run>]) -> String {
+    let mut out = String::new();
+    for runs in rows {
+        for run in runs {
+            let _ = write!(out, "\x1b[{}m{}", sgr(run.style), run.text);
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// Select Graphic Rendition parameters, starting from a reset
+fn sgr(style: CellStyle) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (modifier, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if style.modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    if let Some(code) = ansi_color(style.fg, 30) {
+        codes.push(code);
+    }
+    if let Some(code) = ansi_color(style.bg, 40) {
+        codes.push(code);
+    }
+    codes.join(";")
+}
+
+/// Foreground (`base` 30) or background (`base` 40) color code
+fn ansi_color(color: Color, base: u8) -> Option<String> {
+    Some(match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+        Color::Indexed(index) => format!("{};5;{}", base + 8, index),
+        named => {
+            let index = named_index(named)?;
+            if index < 8 {
+                (base + index).to_string()
+            } else {
+                (base + 60 + index - 8).to_string()
+            }
+        }
+    })
+}
+
+/// ANSI index of a named color
+fn named_index(color: Color) -> Option<u8> {
+    Some(match color {
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+        _ => return None,
+    })
+}
+
+/// CSS color, or None for the terminal default
+fn css_color(color: Color) -> Option<String> {
+    match color {
+        Color::Reset => None,
+        Color::Rgb(r, g, b) => Some(format!("#{:02x}{:02x}{:02x}", r, g, b)),
+        Color::Indexed(index) => Some(indexed_color(index)),
+        named => named_index(named).map(|index| PALETTE[index as usize].to_string()),
+    }
+}
+
+/// xterm 256-color palette: 16 named colors, a 6x6x6 cube, 24 grays
+fn indexed_color(index: u8) -> String {
+    match index {
+        0..=15 => PALETTE[index as usize].to_string(),
+        16..=231 => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let cube = index - 16;
+            format!(
+                "#{:02x}{:02x}{:02x}",
+                level(cube / 36),
+                level(cube / 6 % 6),
+                level(cube % 6)
+            )
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            format!("#{:02x}{:02x}{:02x}", gray, gray, gray)
+        }
+    }
+}
+
+/// Foreground and background as CSS colors, swapped for reversed cells
+fn resolved_colors(style: CellStyle) -> (String, String) {
+    let fg = css_color(style.fg).unwrap_or_else(|| FOREGROUND.to_string());
+    let bg = css_color(style.bg).unwrap_or_else(|| BACKGROUND.to_string());
+    if style.modifier.contains(Modifier::REVERSED) {
+        (bg, fg)
+    } else {
+        (fg, bg)
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn to_svg(rows: &[Vec<Run>], width: u16, height: u16) -> String {
+    let (width, height) = (width as usize * CELL_WIDTH, height as usize * CELL_HEIGHT);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" \
+         font-family=\"monospace\" font-size=\"14\">",
+        w = width,
+        h = height
+    );
+    let _ = writeln!(
+        out,
+        "  <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+        BACKGROUND
+    );
+
+    for (row, runs) in rows.iter().enumerate() {
+        let y = row * CELL_HEIGHT;
+        for run in runs {
+            let (fg, bg) = resolved_colors(run.style);
+            let x = run.column * CELL_WIDTH;
+            let columns = run.text.width();
+            if bg != BACKGROUND {
+                let _ = writeln!(
+                    out,
+                    "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    x,
+                    y,
+                    columns * CELL_WIDTH,
+                    CELL_HEIGHT,
+                    bg
+                );
+            }
+            if run.text.trim().is_empty() {
+                continue;
+            }
+            let mut attributes = format!("fill=\"{}\"", fg);
+            if run.style.modifier.contains(Modifier::BOLD) {
+                attributes.push_str(" font-weight=\"bold\"");
+            }
+            if run.style.modifier.contains(Modifier::ITALIC) {
+                attributes.push_str(" font-style=\"italic\"");
+            }
+            if run.style.modifier.contains(Modifier::UNDERLINED) {
+                attributes.push_str(" text-decoration=\"underline\"");
+            }
+            // Stretched to the cells it covers, so columns line up whatever
+            // the font's advance width
+            let _ = writeln!(
+                out,
+                "  <text x=\"{}\" y=\"{}\" textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\" \
+                 xml:space=\"preserve\" {}>{}</text>",
+                x,
+                y + CELL_HEIGHT - 5,
+                columns * CELL_WIDTH,
+                attributes,
+                xml_escape(&run.text)
+            );
+        }
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+fn to_html(rows: &[Vec<Run>]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>glazewm-debug</title>\n<style>\n\
+         body {{ margin: 0; background: {bg}; }}\n\
+         pre {{ margin: 0; padding: 8px; color: {fg}; background: {bg}; \
+         font-family: monospace; line-height: 1.2; }}\n\
+         </style>\n</head>\n<body>\n<pre>",
+        fg = FOREGROUND,
+        bg = BACKGROUND
+    );
+
+    for runs in rows {
+        for run in runs {
+            let (fg, bg) = resolved_colors(run.style);
+            let mut css = Vec::new();
+            if fg != FOREGROUND {
+                css.push(format!("color:{}", fg));
+            }
+            if bg != BACKGROUND {
+                css.push(format!("background:{}", bg));
+            }
+            for (modifier, rule) in [
+                (Modifier::BOLD, "font-weight:bold"),
+                (Modifier::DIM, "opacity:0.6"),
+                (Modifier::ITALIC, "font-style:italic"),
+                (Modifier::UNDERLINED, "text-decoration:underline"),
+            ] {
+                if run.style.modifier.contains(modifier) {
+                    css.push(rule.to_string());
+                }
+            }
+            let text = xml_escape(&run.text);
+            if css.is_empty() {
+                out.push_str(&text);
+            } else {
+                let _ = write!(out, "<span style=\"{}\">{}</span>", css.join(";"), text);
+            }
+        }
+        out.push('\n');
+    }
+
+    out.push_str("</pre>\n</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::update::UpdateConfig;
+    use crate::app::UpdateLoop;
+    use ratatui::{layout::Rect, style::Style};
+
+    fn styled_buffer() -> Buffer {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 8, 2));
+        buffer.set_string(
+            0,
+            0,
+            "<b>",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        );
+        buffer.set_string(0, 1, "開x", Style::default().bg(Color::Indexed(16)));
+        buffer
+    }
+
+    #[tokio::test]
+    async fn should_render_the_demo_frame_off_screen() {
+        let state = AppState::new();
+        let update_loop = UpdateLoop::new_demo(UpdateConfig::default(), state.clone());
+        update_loop.update_now().await.unwrap();
+        let snapshot = FrameSnapshot::capture(&state).await;
+
+        let buffer = render_frame(&snapshot.view(), 100, 30);
+        let text = export(&buffer, FrameFormat::Text);
+
+        assert_eq!(text.lines().count(), 30);
+        assert!(text.contains("Firefox"));
+        assert!(text.lines().all(|line| line == line.trim_end()));
+        assert!(export(&buffer, FrameFormat::Ansi).contains("\x1b[0;1;31m"));
+    }
+
+    #[test]
+    fn should_skip_cells_behind_wide_characters() {
+        let buffer = styled_buffer();
+
+        assert_eq!(export(&buffer, FrameFormat::Text), "<b>\n開x\n");
+        assert_eq!(
+            export(&buffer, FrameFormat::Ansi).lines().nth(1),
+            Some("\x1b[0;48;5;16m開x\x1b[0m     \x1b[0m")
+        );
+    }
+
+    #[test]
+    fn should_write_svg_and_html_with_theme_colors() {
+        let buffer = styled_buffer();
+
+        let svg = export(&buffer, FrameFormat::Svg);
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"72\" height=\"36\"")
+        );
+        assert!(svg.contains("fill=\"#cd0000\" font-weight=\"bold\">&lt;b&gt;</text>"));
+        assert!(
+            svg.contains("<rect x=\"0\" y=\"18\" width=\"27\" height=\"18\" fill=\"#000000\"/>")
+        );
+
+        let html = export(&buffer, FrameFormat::Html);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<span style=\"color:#cd0000;font-weight:bold\">&lt;b&gt;</span>"));
+        assert!(html.contains("<span style=\"background:#000000\">開x</span>"));
+        assert!(html.ends_with("</pre>\n</body>\n</html>\n"));
+
+        assert_eq!("svg".parse(), Ok(FrameFormat::Svg));
+        assert!("png".parse::<FrameFormat>().is_err());
+    }
+}
@@ -34,11 +34,48 @@ impl InputHandler {
                 InputAction::ToggleMode
             }
 
-            // Future: Navigation keys could be added here
-            // KeyCode::Up => InputAction::NavigateUp,
-            // KeyCode::Down => InputAction::NavigateDown,
-            // KeyCode::Left => InputAction::NavigateLeft,
-            // KeyCode::Right => InputAction::NavigateRight,
+            // Toggle most-recently-focused ordering
+            KeyCode::Char('m') => InputAction::ToggleSort,
+
+            // Toggle focus stack view
+            KeyCode::Char('f') => InputAction::ToggleFocusStack,
+
+            // Toggle performance HUD
+            KeyCode::Char('p') => InputAction::TogglePerfHud,
+
+            // Bug report bundle
+            KeyCode::Char('b') => InputAction::WriteReport,
+
+            // Container tree as DOT and Mermaid files
+            KeyCode::Char('e') => InputAction::ExportGraph,
+
+            // Current frame as text and HTML files
+            KeyCode::Char('s') => InputAction::SaveFrame,
+
+            // Window manager commands; only the demo simulator accepts them
+            KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {
+                let direction = match key.code {
+                    KeyCode::Left => "left",
+                    KeyCode::Right => "right",
+                    KeyCode::Up => "up",
+                    _ => "down",
+                };
+                let verb = if key.modifiers.contains(KeyModifiers::SHIFT) {
+                    "move"
+                } else {
+                    "focus"
+                };
+                InputAction::Command(format!("{} --direction {}", verb, direction))
+            }
+            KeyCode::Char(digit @ '1'..='9') => {
+                InputAction::Command(format!("focus --workspace {}", digit))
+            }
+            KeyCode::Char('n') => InputAction::Command("shell-exec notepad".to_string()),
+            KeyCode::Char('w') => InputAction::Command("close".to_string()),
+            KeyCode::Char('t') => InputAction::Command("toggle-floating".to_string()),
+            KeyCode::Char('v') => InputAction::Command("toggle-tiling-direction".to_string()),
+            KeyCode::Char('+') => InputAction::Command("resize --width +5%".to_string()),
+            KeyCode::Char('-') => InputAction::Command("resize --width -5%".to_string()),
 
             // Unknown key
             _ => InputAction::None,
@@ -110,6 +147,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_handle_focus_order_keys() {
+        let handler = InputHandler::new();
+
+        assert_eq!(
+            handler.handle_key(key_event(KeyCode::Char('m'))),
+            InputAction::ToggleSort
+        );
+        assert_eq!(
+            handler.handle_key(key_event(KeyCode::Char('f'))),
+            InputAction::ToggleFocusStack
+        );
+    }
+
+    #[test]
+    fn should_handle_perf_hud_key() {
+        let handler = InputHandler::new();
+
+        assert_eq!(
+            handler.handle_key(key_event(KeyCode::Char('p'))),
+            InputAction::TogglePerfHud
+        );
+    }
+
+    #[test]
+    fn should_handle_file_writing_keys() {
+        let handler = InputHandler::new();
+
+        assert_eq!(
+            handler.handle_key(key_event(KeyCode::Char('b'))),
+            InputAction::WriteReport
+        );
+        assert_eq!(
+            handler.handle_key(key_event(KeyCode::Char('e'))),
+            InputAction::ExportGraph
+        );
+        assert_eq!(
+            handler.handle_key(key_event(KeyCode::Char('s'))),
+            InputAction::SaveFrame
+        );
+    }
+
+    #[test]
+    fn should_map_window_manager_keys_to_commands() {
+        let handler = InputHandler::new();
+
+        assert_eq!(
+            handler.handle_key(key_event(KeyCode::Left)),
+            InputAction::Command("focus --direction left".to_string())
+        );
+        assert_eq!(
+            handler.handle_key(KeyEvent::new(KeyCode::Down, KeyModifiers::SHIFT)),
+            InputAction::Command("move --direction down".to_string())
+        );
+        assert_eq!(
+            handler.handle_key(key_event(KeyCode::Char('3'))),
+            InputAction::Command("focus --workspace 3".to_string())
+        );
+        assert_eq!(
+            handler.handle_key(key_event(KeyCode::Char('w'))),
+            InputAction::Command("close".to_string())
+        );
+    }
+
     #[test]
     fn should_handle_unknown_keys() {
         let handler = InputHandler::new();
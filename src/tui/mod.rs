@@ -2,9 +2,10 @@
 // Handles terminal user interface using ratatui
 
 pub mod app;
+pub mod export;
 pub mod input;
 pub mod render;
 
-pub use app::{DisplayMode, TuiApp};
+pub use app::{DisplayMode, SortOrder, TuiApp};
 pub use input::InputHandler;
-pub use render::Renderer;
+pub use render::{RenderState, Renderer};
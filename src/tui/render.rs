@@ -5,15 +5,42 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
     Frame,
 };
 
-use crate::domain::{Monitor, Workspace};
-use crate::tui::DisplayMode;
+use crate::app::perf::{PerfStats, RollingStats};
+use crate::cli::ParseWarning;
+use crate::domain::{
+    FocusStack, GeometryReport, Inconsistency, LayoutDeviation, Monitor, Placeholder, Window,
+    WindowId, Workspace,
+};
+use crate::tui::{DisplayMode, SortOrder};
 use crate::utils::text_width::{Alignment, TextWidthCalculator};
 use std::collections::HashMap;
 
+/// Everything one frame shows, borrowed from a snapshot of `AppState`
+#[derive(Debug, Clone, Copy)]
+pub struct RenderState<'a> {
+    pub monitors: &'a [Monitor],
+    /// Monitors that failed to parse or validate
+    pub placeholders: &'a [Placeholder],
+    pub warnings: &'a [ParseWarning],
+    pub inconsistencies: &'a [Inconsistency],
+    /// Tiling windows away from where the layout puts them
+    pub deviations: &'a [LayoutDeviation],
+    /// Overlapping and off-screen windows, and how much of each is visible
+    pub geometry: &'a GeometryReport,
+    pub mode: DisplayMode,
+    pub sort_order: SortOrder,
+    /// Error of the most recent update, if it failed
+    pub last_error: Option<&'a str>,
+    /// Performance statistics, when the HUD is shown
+    pub perf: Option<&'a PerfStats>,
+    /// Short-lived message shown in place of the shortcuts
+    pub notice: Option<&'a str>,
+}
+
 /// Renders the application state to the terminal
 pub struct Renderer;
 
@@ -56,35 +83,213 @@ impl Renderer {
         }
     }
 
+    /// Maximum number of lines an issue panel shows before it truncates
+    const MAX_ISSUE_LINES: usize = 5;
+
     /// Render the application state to the given frame
-    pub fn render(&self, frame: &mut Frame, monitors: &[Monitor], mode: DisplayMode) {
+    pub fn render(&self, frame: &mut Frame, view: &RenderState) {
+        let RenderState {
+            monitors,
+            // Drawn in the monitor tree; without one, the warnings name them
+            placeholders: _,
+            warnings,
+            inconsistencies,
+            deviations,
+            geometry,
+            mode,
+            sort_order,
+            last_error,
+            perf,
+            notice,
+        } = *view;
         let size = frame.area();
+        let errors: Vec<&str> = last_error.into_iter().collect();
 
-        // Create main layout
+        // Create main layout; issue panels only take space when non-empty
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3), // Header
                 Constraint::Min(0),    // Content
+                Constraint::Length(Self::issue_panel_height(inconsistencies.len())),
+                Constraint::Length(Self::issue_panel_height(deviations.len())),
+                Constraint::Length(Self::issue_panel_height(geometry.issues.len())),
+                Constraint::Length(Self::issue_panel_height(warnings.len())),
+                Constraint::Length(Self::issue_panel_height(errors.len())),
                 Constraint::Length(3), // Footer
             ])
             .split(size);
 
         // Render header
-        self.render_header(frame, chunks[0], monitors, mode);
+        self.render_header(frame, chunks[0], monitors, mode, sort_order);
 
         // Render main content
         if monitors.is_empty() {
             self.render_no_data(frame, chunks[1]);
         } else {
             match mode {
-                DisplayMode::Detailed => self.render_monitors_detailed(frame, chunks[1], monitors),
-                DisplayMode::Compact => self.render_monitors_compact(frame, chunks[1], monitors),
+                DisplayMode::Detailed => self.render_monitors_detailed(frame, chunks[1], view),
+                DisplayMode::Compact => self.render_monitors_compact(frame, chunks[1], view),
+                DisplayMode::FocusStack => self.render_focus_stack(frame, chunks[1], monitors),
             }
         }
 
+        // Render disagreements between the monitor tree and the window list
+        self.render_issue_panel(
+            frame,
+            chunks[2],
+            "Inconsistencies",
+            inconsistencies,
+            Color::Yellow,
+        );
+
+        // Render windows the expected tiling layout disagrees with
+        self.render_issue_panel(
+            frame,
+            chunks[3],
+            "Layout Deviations",
+            deviations,
+            Color::LightRed,
+        );
+
+        // Render overlapping, off-screen and straddling windows
+        self.render_issue_panel(
+            frame,
+            chunks[4],
+            "Geometry",
+            &geometry.issues,
+            Color::Magenta,
+        );
+
+        // Render parse warnings
+        self.render_issue_panel(
+            frame,
+            chunks[5],
+            "Parse Warnings",
+            warnings,
+            Color::LightRed,
+        );
+
+        // Render the error of the last update; the state above is from the
+        // last successful one
+        self.render_issue_panel(frame, chunks[6], "Update Failed", &errors, Color::Red);
+
         // Render footer
-        self.render_footer(frame, chunks[2]);
+        self.render_footer(frame, chunks[7], notice);
+
+        // Performance HUD floats over the top right of the content
+        if let Some(perf) = perf {
+            self.render_perf_hud(frame, chunks[1], perf);
+        }
+    }
+
+    /// Render rolling statistics of the update and render paths
+    fn render_perf_hud(&self, frame: &mut Frame, area: Rect, perf: &PerfStats) {
+        const WIDTH: u16 = 46;
+        const HEIGHT: u16 = 7;
+
+        let milliseconds = |value: f64| format!("{:.1}ms", value);
+        let bytes = |value: f64| {
+            if value >= 1024.0 {
+                format!("{:.1}KB", value / 1024.0)
+            } else {
+                format!("{}B", value)
+            }
+        };
+        let row = |label: &str, stats: &RollingStats, unit: &dyn Fn(f64) -> String| {
+            let values = match stats.summary() {
+                Some(summary) => [summary.last, summary.p50, summary.p95, summary.max]
+                    .map(|value| format!("{:>9}", unit(value)))
+                    .concat(),
+                None => format!("{:>9}", "-").repeat(4),
+            };
+            Line::from(format!("{:<8}{}", label, values))
+        };
+
+        let lines = vec![
+            Line::from(format!(
+                "{:<8}{:>9}{:>9}{:>9}{:>9}",
+                "", "last", "p50", "p95", "max"
+            ))
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+            row("poll", &perf.poll_latency, &milliseconds),
+            row("parse", &perf.parse_time, &milliseconds),
+            row("frame", &perf.frame_time, &milliseconds),
+            row("payload", &perf.payload_size, &bytes),
+        ];
+
+        let hud_area = Rect {
+            x: area.x + area.width.saturating_sub(WIDTH),
+            y: area.y,
+            width: WIDTH.min(area.width),
+            height: HEIGHT.min(area.height),
+        };
+        let hud = Paragraph::new(lines)
+            .style(Style::default().fg(Color::White).bg(Color::Black))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Performance")
+                    .border_style(Style::default().fg(Color::Cyan)),
+            );
+
+        frame.render_widget(Clear, hud_area);
+        frame.render_widget(hud, hud_area);
+    }
+
+    /// Height of an issue panel listing `count` entries (zero hides it)
+    fn issue_panel_height(count: usize) -> u16 {
+        if count == 0 {
+            0
+        } else {
+            count.min(Self::MAX_ISSUE_LINES) as u16 + 2
+        }
+    }
+
+    /// Render a bordered list of issues, one per line
+    fn render_issue_panel<T: std::fmt::Display>(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        title: &str,
+        issues: &[T],
+        color: Color,
+    ) {
+        if issues.is_empty() {
+            return;
+        }
+
+        let available_width = area.width.saturating_sub(2) as usize;
+        let mut lines: Vec<Line> = issues
+            .iter()
+            .take(Self::MAX_ISSUE_LINES)
+            .map(|issue| {
+                Line::from(TextWidthCalculator::truncate_to_width(
+                    &issue.to_string(),
+                    available_width,
+                ))
+            })
+            .collect();
+
+        // Replace the last line with a summary when issues overflow the panel
+        if issues.len() > Self::MAX_ISSUE_LINES {
+            lines.pop();
+            lines.push(Line::from(format!(
+                "... and {} more",
+                issues.len() - Self::MAX_ISSUE_LINES + 1
+            )));
+        }
+
+        let panel = Paragraph::new(lines)
+            .style(Style::default().fg(color))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("{} ({})", title, issues.len()))
+                    .border_style(Style::default().fg(color)),
+            );
+
+        frame.render_widget(panel, area);
     }
 
     /// Render the header with application title and stats
@@ -94,6 +299,7 @@ impl Renderer {
         area: Rect,
         monitors: &[Monitor],
         mode: DisplayMode,
+        sort_order: SortOrder,
     ) {
         let monitor_count = monitors.len();
         let total_windows: usize = monitors.iter().map(|m| m.total_window_count()).sum();
@@ -101,14 +307,21 @@ impl Renderer {
         let mode_text = match mode {
             DisplayMode::Detailed => "Detailed",
             DisplayMode::Compact => "Compact",
+            DisplayMode::FocusStack => "Focus Stack",
+        };
+
+        let sort_text = match sort_order {
+            SortOrder::Natural => "Natural",
+            SortOrder::MostRecent => "Most Recent",
         };
 
         let header_text = format!(
-            "glazewm-debug v{} | Monitors: {} | Windows: {} | Mode: {}",
+            "glazewm-debug v{} | Monitors: {} | Windows: {} | Mode: {} | Sort: {}",
             env!("CARGO_PKG_VERSION"),
             monitor_count,
             total_windows,
-            mode_text
+            mode_text,
+            sort_text
         );
 
         let header = Paragraph::new(header_text)
@@ -127,12 +340,15 @@ impl Renderer {
         frame.render_widget(header, area);
     }
 
-    /// Render the footer with keyboard shortcuts
-    fn render_footer(&self, frame: &mut Frame, area: Rect) {
-        let footer_text = "q/Esc: Quit | r: Refresh | c: Toggle Mode | Ctrl+C: Force Quit";
+    /// Render the footer with keyboard shortcuts, or a notice instead
+    fn render_footer(&self, frame: &mut Frame, area: Rect, notice: Option<&str>) {
+        let (footer_text, color) = match notice {
+            Some(notice) => (notice, Color::Yellow),
+            None => ("q/Esc: Quit | r: Refresh | c: Toggle Mode | m: Toggle Sort | f: Focus Stack | p: Perf HUD | b: Bug Report | e: Export Tree | s: Save Frame | Ctrl+C: Force Quit", Color::Gray),
+        };
 
         let footer = Paragraph::new(footer_text)
-            .style(Style::default().fg(Color::Gray))
+            .style(Style::default().fg(color))
             .block(Block::default().borders(Borders::ALL).title("Controls"));
 
         frame.render_widget(footer, area);
@@ -159,8 +375,32 @@ impl Renderer {
         frame.render_widget(no_data, area);
     }
 
+    /// Workspaces of a monitor in the requested order
+    fn sorted_workspaces(monitor: &Monitor, sort_order: SortOrder) -> Vec<&Workspace> {
+        match sort_order {
+            SortOrder::Natural => monitor.workspaces().iter().collect(),
+            SortOrder::MostRecent => monitor.workspaces_by_focus_order(),
+        }
+    }
+
+    /// Windows of a workspace in the requested order
+    fn sorted_windows(workspace: &Workspace, sort_order: SortOrder) -> Vec<&Window> {
+        match sort_order {
+            SortOrder::Natural => workspace.windows().iter().collect(),
+            SortOrder::MostRecent => workspace.windows_by_focus_order(),
+        }
+    }
+
     /// Render the list of monitors and their workspaces (detailed mode) using proper ratatui layouts
-    fn render_monitors_detailed(&self, frame: &mut Frame, area: Rect, monitors: &[Monitor]) {
+    fn render_monitors_detailed(&self, frame: &mut Frame, area: Rect, view: &RenderState) {
+        let RenderState {
+            monitors,
+            placeholders,
+            deviations,
+            geometry,
+            sort_order,
+            ..
+        } = *view;
         if monitors.is_empty() {
             return;
         }
@@ -172,7 +412,7 @@ impl Renderer {
                 if monitor.workspaces().is_empty() {
                     Constraint::Length(5) // Exact minimal height for empty monitors
                 } else {
-                    let mut total_height = 2; // Monitor border
+                    let mut total_height = 3; // Monitor border + device info line
 
                     for workspace in monitor.workspaces() {
                         if workspace.windows().is_empty() {
@@ -197,21 +437,39 @@ impl Renderer {
 
         for (monitor_idx, monitor) in monitors.iter().enumerate() {
             if monitor_idx < monitor_chunks.len() {
-                self.render_single_monitor_with_layout(frame, monitor_chunks[monitor_idx], monitor);
+                self.render_single_monitor_with_layout(
+                    frame,
+                    monitor_chunks[monitor_idx],
+                    monitor,
+                    deviations,
+                    geometry,
+                    sort_order,
+                );
             }
         }
 
         // Render outer border for the entire area
         let outer_block = Block::default()
             .borders(Borders::ALL)
-            .title("Monitors & Workspaces (Detailed)")
+            .title(format!(
+                "Monitors & Workspaces (Detailed){}",
+                Self::invalid_suffix(placeholders.len())
+            ))
             .border_style(Style::default().fg(Color::Blue)); // Basic blue
 
         frame.render_widget(outer_block, area);
     }
 
     /// Render a single monitor using proper ratatui layout
-    fn render_single_monitor_with_layout(&self, frame: &mut Frame, area: Rect, monitor: &Monitor) {
+    fn render_single_monitor_with_layout(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        monitor: &Monitor,
+        deviations: &[LayoutDeviation],
+        geometry: &GeometryReport,
+        sort_order: SortOrder,
+    ) {
         let monitor_style = Self::get_monitor_style(monitor.is_focused());
 
         // Debug: log the actual color being used
@@ -223,17 +481,26 @@ impl Renderer {
             ""
         };
         let monitor_title = format!(
-            "Monitor {} ({}x{}){}",
+            "Monitor {} ({}x{}){}{}",
             monitor.id(),
             monitor.geometry().size.width,
             monitor.geometry().size.height,
-            monitor_status
+            monitor_status,
+            Self::invalid_suffix(monitor.placeholders().len())
         );
 
+        let info_line = Line::from(Span::styled(
+            TextWidthCalculator::truncate_to_width(
+                &Self::monitor_info_text(monitor),
+                area.width.saturating_sub(2) as usize,
+            ),
+            Style::default().fg(Color::Gray),
+        ));
+
         if monitor.workspaces().is_empty() {
             // Monitor with no workspaces
             let monitor_title_spans = Line::from(Span::styled(monitor_title, monitor_style));
-            let empty_text = Paragraph::new("No workspaces")
+            let empty_text = Paragraph::new(vec![info_line, Line::from("No workspaces")])
                 .style(Style::default().fg(Color::Gray)) // Basic gray
                 .block(
                     Block::default()
@@ -246,11 +513,10 @@ impl Renderer {
             return;
         }
 
-        // Create layout for workspaces within this monitor
-        let workspace_constraints: Vec<Constraint> = monitor
-            .workspaces()
-            .iter()
-            .map(|workspace| {
+        // Create layout for the info line and workspaces within this monitor
+        let workspaces = Self::sorted_workspaces(monitor, sort_order);
+        let workspace_constraints: Vec<Constraint> = std::iter::once(Constraint::Length(1))
+            .chain(workspaces.iter().map(|workspace| {
                 if workspace.windows().is_empty() {
                     Constraint::Length(3) // Exact minimal height for empty workspaces (border + empty text)
                 } else {
@@ -262,10 +528,10 @@ impl Renderer {
                         Constraint::Min(estimated_height as u16)
                     }
                 }
-            })
+            }))
             .collect();
 
-        let workspace_chunks = Layout::default()
+        let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints(workspace_constraints)
             .margin(1) // Leave space for monitor border
@@ -279,25 +545,87 @@ impl Renderer {
             .border_style(monitor_style);
 
         frame.render_widget(monitor_block, area);
+        frame.render_widget(Paragraph::new(info_line), chunks[0]);
 
         // Then render workspaces in the inner area
-        for (ws_idx, workspace) in monitor.workspaces().iter().enumerate() {
+        let workspace_chunks = &chunks[1..];
+        for (ws_idx, workspace) in workspaces.into_iter().enumerate() {
             if ws_idx < workspace_chunks.len() {
                 self.render_single_workspace_with_layout(
                     frame,
                     workspace_chunks[ws_idx],
                     workspace,
+                    deviations,
+                    geometry,
+                    sort_order,
                 );
             }
         }
     }
 
+    /// One-line summary of a monitor's device identity and working area
+    fn monitor_info_text(monitor: &Monitor) -> String {
+        let device = monitor.device_info();
+        let mut parts = vec![format!("Device: {}", device.device_name)];
+
+        if let Some(hardware_id) = &device.hardware_id {
+            parts.push(format!("HW: {}", hardware_id));
+        }
+        if let Some(handle) = device.handle {
+            parts.push(format!("Handle: {:#x}", handle));
+        }
+        parts.push(format!(
+            "DPI: {} ({:.0}%)",
+            device.dpi,
+            device.scale_factor * 100.0
+        ));
+
+        match (monitor.working_area(), monitor.reserved_insets()) {
+            (Some(working_area), Some(insets)) => parts.push(format!(
+                "Work area: {} (reserved: {})",
+                working_area.size, insets
+            )),
+            _ => parts.push("Work area: unknown".to_string()),
+        }
+
+        parts.join(" | ")
+    }
+
+    /// Layout deviation reported for a window, if any
+    fn deviation_for<'a>(
+        deviations: &'a [LayoutDeviation],
+        window_id: &WindowId,
+    ) -> Option<&'a LayoutDeviation> {
+        deviations
+            .iter()
+            .find(|deviation| &deviation.window_id == window_id)
+    }
+
+    /// `40% visible` for windows partly off-screen or under floating ones
+    fn visibility_text(visible_percent: Option<f64>) -> Option<String> {
+        visible_percent
+            .filter(|percent| *percent < 99.5)
+            .map(|percent| format!("{:.0}% visible", percent))
+    }
+
+    /// Title suffix flagging children that failed to parse
+    fn invalid_suffix(count: usize) -> String {
+        if count == 0 {
+            String::new()
+        } else {
+            format!(" [!{} invalid]", count)
+        }
+    }
+
     /// Render a single workspace using proper ratatui layout
     fn render_single_workspace_with_layout(
         &self,
         frame: &mut Frame,
         area: Rect,
         workspace: &Workspace,
+        deviations: &[LayoutDeviation],
+        geometry: &GeometryReport,
+        sort_order: SortOrder,
     ) {
         let workspace_style = Self::get_workspace_style(workspace.is_focused());
 
@@ -314,7 +642,12 @@ impl Renderer {
             ""
         };
 
-        let workspace_title = format!("Workspace {}{}", workspace.name(), workspace_status);
+        let workspace_title = format!(
+            "Workspace {}{}{}",
+            workspace.label(),
+            workspace_status,
+            Self::invalid_suffix(workspace.placeholders().len())
+        );
 
         if workspace.windows().is_empty() {
             // Empty workspace
@@ -362,13 +695,18 @@ impl Renderer {
         frame.render_widget(workspace_block, area);
 
         // Then render windows in the inner area
-        for (win_idx, window) in workspace.windows().iter().enumerate() {
+        for (win_idx, window) in Self::sorted_windows(workspace, sort_order)
+            .into_iter()
+            .enumerate()
+        {
             if win_idx < window_chunks.len() {
                 self.render_single_window_with_layout(
                     frame,
                     window_chunks[win_idx],
                     window,
                     &percentage_map,
+                    Self::deviation_for(deviations, window.id()),
+                    geometry.visible_percent(window.id()),
                 );
             }
         }
@@ -381,21 +719,26 @@ impl Renderer {
         area: Rect,
         window: &crate::domain::Window,
         percentage_map: &HashMap<crate::domain::values::WindowId, f64>,
+        deviation: Option<&LayoutDeviation>,
+        visible_percent: Option<f64>,
     ) {
         let window_style = Self::get_window_style(window.is_focused());
 
         // Debug: log the actual color being used
         tracing::debug!("Window {} style: {:?}", window.process_name(), window_style);
 
-        let percentage = percentage_map.get(window.id()).unwrap_or(&0.0);
         let focus_indicator = if window.is_focused() { "*" } else { "" };
 
-        let window_title = format!(
-            "{}{} ({:.0}%)",
-            window.process_name(),
-            focus_indicator,
-            percentage
-        );
+        // Only tiling windows have a share of the workspace
+        let window_title = match percentage_map.get(window.id()) {
+            Some(percentage) => format!(
+                "{}{} ({:.0}%)",
+                window.process_name(),
+                focus_indicator,
+                percentage
+            ),
+            None => format!("{}{}", window.process_name(), focus_indicator),
+        };
 
         // Create compact content for the window - combine title and state in one line
         let available_width = area.width.saturating_sub(4) as usize; // minus borders and padding
@@ -407,16 +750,27 @@ impl Renderer {
             window.geometry().size.height
         );
 
+        // Pixel delta from the expected layout leads the line when present
+        let delta_text = deviation
+            .map(|deviation| format!("Δ {} | ", deviation.delta()))
+            .unwrap_or_default();
+        let delta_text = TextWidthCalculator::truncate_to_width(&delta_text, available_width);
+        let available_width =
+            available_width.saturating_sub(TextWidthCalculator::display_width(&delta_text));
+
         // Combine title and state in one line with separator
-        let combined_text = format!("{} | {}", window.title(), state_text);
+        let mut combined_text = format!("{} | {}", window.title(), state_text);
+        if let Some(visible) = Self::visibility_text(visible_percent) {
+            combined_text.push_str(&format!(" | {}", visible));
+        }
         let truncated_combined =
             TextWidthCalculator::truncate_to_width(&combined_text, available_width);
 
         let window_content = vec![
-            Line::from(Span::styled(
-                truncated_combined,
-                Style::default().fg(Color::Gray),
-            )), // Single line with both info
+            Line::from(vec![
+                Span::styled(delta_text, Style::default().fg(Color::LightRed)),
+                Span::styled(truncated_combined, Style::default().fg(Color::Gray)),
+            ]), // Single line with both info
         ];
 
         let window_title_spans = Line::from(Span::styled(window_title, window_style));
@@ -431,6 +785,7 @@ impl Renderer {
     }
 
     /// Render multiple monitors side by side
+    #[allow(dead_code)] // Alternative layout, not wired to a display mode yet
     fn render_monitors_side_by_side(&self, frame: &mut Frame, area: Rect, monitors: &[Monitor]) {
         // Create horizontal layout for monitors
         let monitor_count = monitors.len();
@@ -450,6 +805,7 @@ impl Renderer {
     }
 
     /// Render a single monitor in detailed mode
+    #[allow(dead_code)] // Used by the side-by-side layout
     fn render_single_monitor_detailed(&self, frame: &mut Frame, area: Rect, monitor: &Monitor) {
         let mut items = Vec::new();
 
@@ -598,7 +954,15 @@ impl Renderer {
     }
 
     /// Render monitors in compact tree-style mode
-    fn render_monitors_compact(&self, frame: &mut Frame, area: Rect, monitors: &[Monitor]) {
+    fn render_monitors_compact(&self, frame: &mut Frame, area: Rect, view: &RenderState) {
+        let RenderState {
+            monitors,
+            placeholders,
+            deviations,
+            geometry,
+            sort_order,
+            ..
+        } = *view;
         let mut items = Vec::new();
 
         for (monitor_idx, monitor) in monitors.iter().enumerate() {
@@ -632,9 +996,10 @@ impl Renderer {
             ))));
 
             // Workspaces for this monitor
-            let workspaces = monitor.workspaces();
+            let workspaces = Self::sorted_workspaces(monitor, sort_order);
             for (ws_idx, workspace) in workspaces.iter().enumerate() {
-                let is_last_workspace = ws_idx == workspaces.len() - 1;
+                let is_last_workspace =
+                    ws_idx == workspaces.len() - 1 && monitor.placeholders().is_empty();
 
                 let workspace_style = if workspace.is_focused() {
                     Style::default()
@@ -644,9 +1009,7 @@ impl Renderer {
                     Style::default().fg(Color::Gray)
                 };
 
-                let ws_prefix = if is_last_workspace && is_last_monitor {
-                    "└─ "
-                } else if is_last_workspace {
+                let ws_prefix = if is_last_workspace {
                     "└─ "
                 } else {
                     "├─ "
@@ -655,7 +1018,7 @@ impl Renderer {
                 let workspace_info = format!(
                     "{}WS {} [{}] ({} windows)",
                     ws_prefix,
-                    workspace.name(),
+                    workspace.label(),
                     if workspace.is_focused() {
                         "Active"
                     } else {
@@ -670,9 +1033,10 @@ impl Renderer {
                 ))));
 
                 // Windows in this workspace
-                let windows = workspace.windows();
+                let windows = Self::sorted_windows(workspace, sort_order);
                 for (win_idx, window) in windows.iter().enumerate() {
-                    let is_last_window = win_idx == windows.len() - 1;
+                    let is_last_window =
+                        win_idx == windows.len() - 1 && workspace.placeholders().is_empty();
 
                     let window_style = if window.is_focused() {
                         Style::default()
@@ -682,13 +1046,7 @@ impl Renderer {
                         Style::default().fg(Color::LightBlue)
                     };
 
-                    let win_prefix = if is_last_workspace && is_last_monitor {
-                        if is_last_window {
-                            "    └─ "
-                        } else {
-                            "    ├─ "
-                        }
-                    } else if is_last_workspace {
+                    let win_prefix = if is_last_workspace {
                         if is_last_window {
                             "    └─ "
                         } else {
@@ -708,11 +1066,45 @@ impl Renderer {
                         if window.is_focused() { "(Focused)" } else { "" }
                     );
 
-                    items.push(ListItem::new(Line::from(Span::styled(
-                        window_info,
+                    let visibility = Self::visibility_text(geometry.visible_percent(window.id()))
+                        .map(|visible| (format!("({})", visible), Color::Magenta));
+                    let deviation = Self::deviation_for(deviations, window.id())
+                        .map(|deviation| (format!("Δ {}", deviation.delta()), Color::LightRed));
+
+                    let mut spans = vec![Span::styled(
+                        window_info.trim_end().to_string(),
                         window_style,
-                    ))));
+                    )];
+                    for (marker, color) in visibility.into_iter().chain(deviation) {
+                        spans.push(Span::raw(" "));
+                        spans.push(Span::styled(marker, Style::default().fg(color)));
+                    }
+                    items.push(ListItem::new(Line::from(spans)));
                 }
+
+                // Windows that failed to parse
+                let placeholders = workspace.placeholders();
+                for (idx, placeholder) in placeholders.iter().enumerate() {
+                    let is_last = idx == placeholders.len() - 1;
+                    let prefix = match (is_last_workspace, is_last) {
+                        (true, true) => "    └─ ",
+                        (true, false) => "    ├─ ",
+                        (false, true) => "│   └─ ",
+                        (false, false) => "│   ├─ ",
+                    };
+                    items.push(Self::placeholder_item(prefix, placeholder));
+                }
+            }
+
+            // Workspaces that failed to parse
+            let placeholders = monitor.placeholders();
+            for (idx, placeholder) in placeholders.iter().enumerate() {
+                let prefix = if idx == placeholders.len() - 1 {
+                    "└─ "
+                } else {
+                    "├─ "
+                };
+                items.push(Self::placeholder_item(prefix, placeholder));
             }
 
             // Add spacing between monitors
@@ -721,17 +1113,151 @@ impl Renderer {
             }
         }
 
+        // Monitors that failed to parse or validate
+        if !placeholders.is_empty() {
+            items.push(ListItem::new(Line::from("")));
+        }
+        for placeholder in placeholders {
+            items.push(Self::placeholder_item("", placeholder));
+        }
+
         let list = List::new(items)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Monitors & Workspaces (Compact)")
+                    .title(format!(
+                        "Monitors & Workspaces (Compact){}",
+                        Self::invalid_suffix(placeholders.len())
+                    ))
                     .style(Style::default().fg(Color::White)),
             )
             .style(Style::default().fg(Color::White));
 
         frame.render_widget(list, area);
     }
+
+    /// Render the focus path of the focused monitor and glazewm's fallback order
+    fn render_focus_stack(&self, frame: &mut Frame, area: Rect, monitors: &[Monitor]) {
+        let heading = Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD);
+        let mut lines = Vec::new();
+
+        match FocusStack::from_monitors(monitors) {
+            Some(stack) => {
+                lines.push(Line::from(Span::styled("Focus path", heading)));
+                lines.extend(Self::focus_path_lines(&stack));
+
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    "Fallback if the focused window closes",
+                    heading,
+                )));
+                if stack.window().is_none() {
+                    lines.push(Line::from("  (no focused window)"));
+                } else if stack.fallback().is_empty() {
+                    lines.push(Line::from("  (none, focus falls to the workspace)"));
+                }
+                for (idx, window) in stack.fallback().iter().enumerate() {
+                    lines.push(Line::from(Span::styled(
+                        format!(
+                            "  {}. {} {}",
+                            idx + 1,
+                            window.state_indicator(),
+                            window.display_name_truncated(60)
+                        ),
+                        Self::get_window_style(false),
+                    )));
+                }
+            }
+            None => lines.push(Line::from("No focused monitor")),
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Workspaces by recent focus",
+            heading,
+        )));
+        for monitor in monitors {
+            let names: Vec<String> = monitor
+                .workspaces_by_focus_order()
+                .iter()
+                .map(|workspace| workspace.label().to_string())
+                .collect();
+            lines.push(Line::from(Span::styled(
+                format!("  Monitor {}: {}", monitor.id(), names.join(" > ")),
+                Self::get_monitor_style(monitor.is_focused()),
+            )));
+        }
+
+        let paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Focus Stack")
+                .border_style(Style::default().fg(Color::Blue)),
+        );
+
+        frame.render_widget(paragraph, area);
+    }
+
+    /// One indented line per level of the focus path
+    fn focus_path_lines(stack: &FocusStack) -> Vec<Line<'static>> {
+        let mut lines = vec![Line::from(Span::styled(
+            format!("  Monitor {}", stack.monitor().id()),
+            Self::get_monitor_style(true),
+        ))];
+        let mut depth = 1;
+
+        if let Some(workspace) = stack.workspace() {
+            lines.push(Line::from(Span::styled(
+                format!("{}└─ Workspace {}", "   ".repeat(depth), workspace.label()),
+                Self::get_workspace_style(true),
+            )));
+            depth += 1;
+        }
+
+        for container in stack.containers() {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{}└─ Split {} ({:?})",
+                    "   ".repeat(depth),
+                    container.id(),
+                    container.tiling_direction()
+                ),
+                Style::default().fg(Color::Gray),
+            )));
+            depth += 1;
+        }
+
+        if let Some(window) = stack.window() {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{}└─ {} {}",
+                    "   ".repeat(depth),
+                    window.state_indicator(),
+                    window.display_name_truncated(60)
+                ),
+                Self::get_window_style(true),
+            )));
+        }
+
+        lines
+    }
+
+    /// Build a compact tree line for a node that failed to parse
+    fn placeholder_item(prefix: &str, placeholder: &Placeholder) -> ListItem<'static> {
+        let text = format!(
+            "{}[!] invalid {}: {}",
+            prefix,
+            placeholder.display_name(),
+            TextWidthCalculator::truncate_to_width(placeholder.error(), 60)
+        );
+
+        ListItem::new(Line::from(Span::styled(
+            text,
+            Style::default().fg(Color::LightRed),
+        )))
+    }
 }
 
 impl Default for Renderer {
@@ -784,6 +1310,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::default_constructed_unit_structs)]
     fn should_use_default() {
         let _renderer = Renderer::default();
     }
@@ -791,7 +1318,7 @@ mod tests {
     #[test]
     fn should_generate_workspace_box_layout() {
         let monitor = create_test_monitor();
-        let renderer = Renderer::new();
+        let _renderer = Renderer::new();
 
         // Test workspace box structure
         // Should generate:
@@ -799,9 +1326,10 @@ mod tests {
         // │ │ [window boxes]                     │ │
         // │ └────────────────────────────────────┘ │
 
-        // This is a structural test - actual rendering would need mock terminal
-        assert!(monitor.workspaces().len() > 0);
-        assert!(monitor.workspaces()[0].windows().len() > 0);
+        // This is a structural test - rendered frames are compared with
+        // golden files in tests/render_golden.rs
+        assert!(!monitor.workspaces().is_empty());
+        assert!(!monitor.workspaces()[0].windows().is_empty());
     }
 
     #[test]
@@ -826,13 +1354,13 @@ mod tests {
 
         // Japanese workspace name
         let workspace_text = "Workspace 開発環境 [Active]";
-        let width = TextWidthCalculator::display_width(&workspace_text);
+        let width = TextWidthCalculator::display_width(workspace_text);
 
         // Should calculate correct width for CJK characters
-        assert!(width > workspace_text.len()); // CJK chars are wider than ASCII
+        assert!(width > workspace_text.chars().count()); // CJK chars are wider than ASCII
 
         // Truncation should not break characters
-        let truncated = TextWidthCalculator::truncate_to_width(&workspace_text, 20);
+        let truncated = TextWidthCalculator::truncate_to_width(workspace_text, 20);
         assert!(truncated.len() <= workspace_text.len());
     }
 
@@ -909,6 +1437,51 @@ mod tests {
         assert_ne!(monitor_unfocused.fg, window_unfocused.fg);
     }
 
+    #[test]
+    fn should_overlay_perf_hud() {
+        let monitors = vec![create_test_monitor()];
+        let mut perf = PerfStats::new();
+        perf.poll_latency.record(12.5);
+        perf.payload_size.record(2048.0);
+
+        let backend = ratatui::backend::TestBackend::new(100, 30);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| {
+                Renderer::new().render(
+                    frame,
+                    &RenderState {
+                        monitors: &monitors,
+                        placeholders: &[],
+                        warnings: &[],
+                        inconsistencies: &[],
+                        deviations: &[],
+                        geometry: &GeometryReport::default(),
+                        mode: DisplayMode::Detailed,
+                        sort_order: SortOrder::Natural,
+                        last_error: None,
+                        perf: Some(&perf),
+                        notice: None,
+                    },
+                )
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let text: String = (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(text.contains("Performance"));
+        assert!(text.contains("poll       12.5ms   12.5ms   12.5ms   12.5ms"));
+        assert!(text.contains("parse           -        -        -        -"));
+        assert!(text.contains("payload     2.0KB"));
+    }
+
     // Note: Full rendering tests would require a mock terminal,
     // which is complex to set up. The rendering logic is tested
     // indirectly through integration tests.
@@ -0,0 +1,192 @@
+// Structural JSON diffs
+// Minimal patches between two JSON documents, used to stream state changes
+// instead of full snapshots
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One step into a JSON document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PathSegment {
+    Index(usize),
+    Key(String),
+}
+
+/// Set (or, without a value, remove) the node at `path`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonPatch {
+    pub path: Vec<PathSegment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+}
+
+/// Patches that turn `old` into `new`
+///
+/// Objects are compared key by key and arrays element by element; an array
+/// whose length changed is replaced whole.
+pub fn diff(old: &Value, new: &Value) -> Vec<JsonPatch> {
+    let mut patches = Vec::new();
+    diff_into(old, new, &mut Vec::new(), &mut patches);
+    patches
+}
+
+fn diff_into(old: &Value, new: &Value, path: &mut Vec<PathSegment>, out: &mut Vec<JsonPatch>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                path.push(PathSegment::Key(key.clone()));
+                match new_map.get(key) {
+                    Some(new_value) => diff_into(old_value, new_value, path, out),
+                    None => out.push(JsonPatch {
+                        path: path.clone(),
+                        value: None,
+                    }),
+                }
+                path.pop();
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    path.push(PathSegment::Key(key.clone()));
+                    out.push(JsonPatch {
+                        path: path.clone(),
+                        value: Some(new_value.clone()),
+                    });
+                    path.pop();
+                }
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items))
+            if old_items.len() == new_items.len() =>
+        {
+            for (index, (old_item, new_item)) in old_items.iter().zip(new_items).enumerate() {
+                path.push(PathSegment::Index(index));
+                diff_into(old_item, new_item, path, out);
+                path.pop();
+            }
+        }
+        _ if old == new => {}
+        _ => out.push(JsonPatch {
+            path: path.clone(),
+            value: Some(new.clone()),
+        }),
+    }
+}
+
+/// Apply patches produced by `diff`
+pub fn apply(target: &mut Value, patches: &[JsonPatch]) -> Result<(), String> {
+    for patch in patches {
+        let Some((last, parents)) = patch.path.split_last() else {
+            *target = patch
+                .value
+                .clone()
+                .ok_or_else(|| "cannot remove the document root".to_string())?;
+            continue;
+        };
+
+        let mut node = &mut *target;
+        for segment in parents {
+            node = child_mut(node, segment)
+                .ok_or_else(|| format!("no node at {}", describe(&patch.path)))?;
+        }
+
+        match (node, last, &patch.value) {
+            (Value::Object(map), PathSegment::Key(key), Some(value)) => {
+                map.insert(key.clone(), value.clone());
+            }
+            (Value::Object(map), PathSegment::Key(key), None) => {
+                map.remove(key);
+            }
+            (Value::Array(items), PathSegment::Index(index), Some(value))
+                if *index < items.len() =>
+            {
+                items[*index] = value.clone();
+            }
+            _ => return Err(format!("cannot patch {}", describe(&patch.path))),
+        }
+    }
+    Ok(())
+}
+
+fn child_mut<'a>(node: &'a mut Value, segment: &PathSegment) -> Option<&'a mut Value> {
+    match segment {
+        PathSegment::Key(key) => node.get_mut(key.as_str()),
+        PathSegment::Index(index) => node.get_mut(*index),
+    }
+}
+
+fn describe(path: &[PathSegment]) -> String {
+    let parts: Vec<String> = path
+        .iter()
+        .map(|segment| match segment {
+            PathSegment::Index(index) => index.to_string(),
+            PathSegment::Key(key) => key.clone(),
+        })
+        .collect();
+    format!("/{}", parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_produce_minimal_patches_that_apply() {
+        let old = json!({
+            "focus": "a",
+            "stale": true,
+            "windows": [{ "id": "a", "width": 100 }, { "id": "b", "width": 100 }],
+            "workspaces": ["1"]
+        });
+        let new = json!({
+            "focus": "b",
+            "added": null,
+            "windows": [{ "id": "a", "width": 50 }, { "id": "b", "width": 100 }],
+            "workspaces": ["1", "2"]
+        });
+
+        let patches = diff(&old, &new);
+        assert_eq!(patches.len(), 5);
+        assert!(patches.contains(&JsonPatch {
+            path: vec![
+                PathSegment::Key("windows".to_string()),
+                PathSegment::Index(0),
+                PathSegment::Key("width".to_string()),
+            ],
+            value: Some(json!(50)),
+        }));
+
+        let mut patched = old.clone();
+        apply(&mut patched, &patches).unwrap();
+        assert_eq!(patched, new);
+    }
+
+    #[test]
+    fn should_round_trip_patches_through_json() {
+        let patches = diff(&json!({ "a": [1, 2] }), &json!({ "a": [1, 3] }));
+        let text = serde_json::to_string(&patches).unwrap();
+        assert_eq!(text, r#"[{"path":["a",1],"value":3}]"#);
+        assert_eq!(
+            serde_json::from_str::<Vec<JsonPatch>>(&text).unwrap(),
+            patches
+        );
+    }
+
+    #[test]
+    fn should_reject_patches_for_missing_nodes() {
+        let patch = JsonPatch {
+            path: vec![
+                PathSegment::Key("missing".to_string()),
+                PathSegment::Index(0),
+            ],
+            value: Some(json!(1)),
+        };
+
+        assert_eq!(
+            apply(&mut json!({}), &[patch]),
+            Err("no node at /missing/0".to_string())
+        );
+        assert!(diff(&json!([1]), &json!([1])).is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+// Recent log lines
+// Keeps the last lines written by the logger in memory so a bug report can
+// include them without a log file
+
+use std::collections::VecDeque;
+use std::io;
+use std::sync::{Mutex, OnceLock};
+
+/// Lines kept by the process-wide buffer
+const CAPACITY: usize = 500;
+
+/// Bounded buffer of log lines, oldest first
+#[derive(Debug)]
+pub struct LogBuffer {
+    lines: Mutex<VecDeque<String>>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    /// Create a buffer that keeps the last `capacity` lines
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Mutex::new(VecDeque::new()),
+            capacity,
+        }
+    }
+
+    /// The buffer the logger writes to, like the logger itself one per process
+    pub fn global() -> &'static LogBuffer {
+        static GLOBAL: OnceLock<LogBuffer> = OnceLock::new();
+        GLOBAL.get_or_init(|| LogBuffer::new(CAPACITY))
+    }
+
+    /// Copy the buffered lines, oldest first
+    pub fn lines(&self) -> Vec<String> {
+        self.lock().iter().cloned().collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<String>> {
+        self.lines
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// The logger formats each event into one write
+impl io::Write for &LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        let mut lines = self.lock();
+        for line in text.lines().filter(|line| !line.is_empty()) {
+            if lines.len() == self.capacity {
+                lines.pop_front();
+            }
+            lines.push_back(line.to_string());
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn should_keep_the_last_lines() {
+        let buffer = LogBuffer::new(2);
+        let mut writer = &buffer;
+
+        writeln!(writer, "first").unwrap();
+        writer.write_all(b"second\nthird\n").unwrap();
+
+        assert_eq!(buffer.lines(), ["second", "third"]);
+    }
+}
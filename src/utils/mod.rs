@@ -1,5 +1,10 @@
 // Utility modules for text processing and display formatting
 
+pub mod json_diff;
+pub mod log_buffer;
+pub mod stamped_files;
+pub mod tar;
 pub mod text_width;
+pub mod timestamp;
 
 pub use text_width::TextWidthCalculator;
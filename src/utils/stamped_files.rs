@@ -0,0 +1,78 @@
+// Timestamped files
+// Writes the files behind the TUI's save keys, named after the time they
+// were taken, and describes the outcome in one footer line
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::utils::timestamp;
+
+/// Write each `(extension, contents)` as `<prefix>-<time>.<extension>` into
+/// `dir`, stopping at the first failure
+pub fn write(
+    dir: &Path,
+    prefix: &str,
+    time: SystemTime,
+    files: impl IntoIterator<Item = (&'static str, String)>,
+) -> io::Result<Vec<PathBuf>> {
+    let stamp = timestamp::file_stamp(time);
+    files
+        .into_iter()
+        .map(|(extension, contents)| {
+            let path = dir.join(format!("{}-{}.{}", prefix, stamp, extension));
+            std::fs::write(&path, contents)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// `Wrote a.dot and a.mmd`, or `Failed to <action>: <error>`
+pub fn notice(written: &io::Result<Vec<PathBuf>>, action: &str) -> String {
+    match written {
+        Ok(paths) => format!(
+            "Wrote {}",
+            paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" and ")
+        ),
+        Err(e) => format!("Failed to {}: {}", action, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_name_files_after_the_time_and_list_them_in_the_notice() {
+        let dir = tempfile::tempdir().unwrap();
+        let written = write(
+            dir.path(),
+            "glazewm-test",
+            std::time::UNIX_EPOCH,
+            [("txt", "a".to_string()), ("html", "b".to_string())],
+        );
+
+        let paths = written.as_ref().unwrap();
+        assert_eq!(
+            paths[0],
+            dir.path().join("glazewm-test-1970-01-01T00-00-00Z.txt")
+        );
+        assert_eq!(std::fs::read_to_string(&paths[1]).unwrap(), "b");
+        assert_eq!(
+            notice(&written, "save"),
+            format!("Wrote {} and {}", paths[0].display(), paths[1].display())
+        );
+
+        let missing = write(
+            &dir.path().join("missing"),
+            "glazewm-test",
+            std::time::UNIX_EPOCH,
+            [("txt", String::new())],
+        );
+        assert!(notice(&missing, "save the frame").starts_with("Failed to save the frame: "));
+    }
+}
@@ -0,0 +1,94 @@
+// Tar archives
+// Writes uncompressed ustar archives of regular files, enough for bug report
+// bundles that any `tar` can unpack, without an archive library
+
+use std::io::{self, Write};
+
+const BLOCK: usize = 512;
+
+/// Writes files into a tar stream; `finish` writes the end-of-archive marker
+pub struct TarWriter<W: Write> {
+    out: W,
+    mtime: u64,
+}
+
+impl<W: Write> TarWriter<W> {
+    /// Start an archive whose files carry `mtime` (seconds since the epoch)
+    pub fn new(out: W, mtime: u64) -> Self {
+        Self { out, mtime }
+    }
+
+    /// Append a regular file; `path` must fit in 100 bytes
+    pub fn append(&mut self, path: &str, contents: &[u8]) -> io::Result<()> {
+        if path.len() > 100 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("path too long for tar: {}", path),
+            ));
+        }
+
+        let mut header = [0u8; BLOCK];
+        header[..path.len()].copy_from_slice(path.as_bytes());
+        octal(&mut header[100..108], 0o644);
+        octal(&mut header[108..116], 0);
+        octal(&mut header[116..124], 0);
+        octal(&mut header[124..136], contents.len() as u64);
+        octal(&mut header[136..148], self.mtime);
+        header[156] = b'0';
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+
+        // The checksum is computed with its own field set to spaces
+        header[148..156].fill(b' ');
+        let checksum: u32 = header.iter().map(|&byte| u32::from(byte)).sum();
+        octal(&mut header[148..155], u64::from(checksum));
+
+        self.out.write_all(&header)?;
+        self.out.write_all(contents)?;
+        let padding = (BLOCK - contents.len() % BLOCK) % BLOCK;
+        self.out.write_all(&[0u8; BLOCK][..padding])
+    }
+
+    /// Write the two empty blocks that end an archive
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.write_all(&[0u8; BLOCK * 2])?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Zero-padded octal digits followed by a NUL, filling `field`
+fn octal(field: &mut [u8], value: u64) {
+    let digits = format!("{:0width$o}", value, width = field.len() - 1);
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+    field[digits.len()] = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_write_headers_contents_and_padding() {
+        let mut tar = TarWriter::new(Vec::new(), 0);
+        tar.append("report/a.txt", b"hello").unwrap();
+        let archive = tar.finish().unwrap();
+
+        assert_eq!(archive.len(), BLOCK * 4);
+        assert!(archive.starts_with(b"report/a.txt\0"));
+        assert_eq!(&archive[124..136], b"00000000005\0");
+        assert_eq!(&archive[257..262], b"ustar");
+        assert_eq!(&archive[BLOCK..BLOCK + 5], b"hello");
+
+        // Header bytes sum to the stored checksum, counting its field as spaces
+        let stored = std::str::from_utf8(&archive[148..154]).unwrap();
+        let mut header = archive[..BLOCK].to_vec();
+        header[148..156].fill(b' ');
+        let sum: u32 = header.iter().map(|&byte| u32::from(byte)).sum();
+        assert_eq!(u32::from_str_radix(stored, 8).unwrap(), sum);
+
+        assert!(TarWriter::new(Vec::new(), 0)
+            .append(&"x".repeat(101), b"")
+            .is_err());
+    }
+}
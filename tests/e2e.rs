@@ -0,0 +1,577 @@
+// End-to-end tests
+// Run the glazewm-debug binary headlessly, against the fake glazewm executable
+// (tests/support/fake_glazewm.rs) and against demo scenarios
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use tempfile::NamedTempFile;
+
+/// The fake is a binary of this package, so cargo builds it for these tests
+fn fake_glazewm_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_fake_glazewm"))
+}
+
+fn write_json(value: Value) -> NamedTempFile {
+    let file = NamedTempFile::new().expect("create JSON file");
+    std::fs::write(file.path(), value.to_string()).expect("write JSON file");
+    file
+}
+
+/// `glazewm-debug dump` against the fake, optionally driven by a scenario
+fn glazewm_debug(scenario: Option<&NamedTempFile>) -> Command {
+    let mut cmd = Command::cargo_bin("glazewm-debug").unwrap();
+    cmd.arg("dump")
+        .arg("--glazewm-path")
+        .arg(fake_glazewm_path())
+        .env_remove("RUST_LOG");
+    match scenario {
+        Some(file) => cmd.env("FAKE_GLAZEWM_SCENARIO", file.path()),
+        None => cmd.env_remove("FAKE_GLAZEWM_SCENARIO"),
+    };
+    cmd
+}
+
+#[test]
+fn should_print_state_reported_by_glazewm() {
+    glazewm_debug(None)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Workspace Development"))
+        .stdout(predicate::str::contains("Firefox - Documentation"))
+        .stdout(predicate::str::contains("Inconsistencies").not());
+}
+
+#[test]
+fn should_reflect_scenario_commands() {
+    let scenario = write_json(json!({
+        "commands": ["shell-exec notepad Untitled - Notepad"]
+    }));
+
+    glazewm_debug(Some(&scenario))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Untitled - Notepad"));
+}
+
+#[test]
+fn should_fail_when_monitors_query_exits_non_zero() {
+    let scenario = write_json(json!({
+        "monitors": { "exit_code": 3, "stderr": "IPC server is not running" }
+    }));
+
+    glazewm_debug(Some(&scenario))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("non-zero exit code 3"))
+        .stderr(predicate::str::contains("IPC server is not running"));
+}
+
+#[test]
+fn should_fail_on_malformed_json() {
+    let scenario = write_json(json!({
+        "monitors": { "stdout": "{\"success\": true, \"data\": " }
+    }));
+
+    glazewm_debug(Some(&scenario))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to parse JSON response"));
+}
+
+#[test]
+fn should_fail_on_unsuccessful_response() {
+    let scenario = write_json(json!({
+        "monitors": { "json": { "success": false, "data": null, "error": "denied" } }
+    }));
+
+    glazewm_debug(Some(&scenario))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "glazewm reported an error: denied",
+        ));
+}
+
+#[test]
+fn should_time_out_slow_queries() {
+    let scenario = write_json(json!({
+        "monitors": { "delay_ms": 3000 }
+    }));
+
+    glazewm_debug(Some(&scenario))
+        .args(["--timeout", "200"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("timed out"));
+}
+
+#[test]
+fn should_keep_tree_when_windows_query_fails() {
+    let scenario = write_json(json!({
+        "windows": { "exit_code": 1, "stderr": "boom" }
+    }));
+
+    glazewm_debug(Some(&scenario))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Workspace Development"))
+        .stdout(predicate::str::contains("Inconsistencies").not());
+}
+
+#[test]
+fn should_report_windows_missing_from_window_list() {
+    let scenario = write_json(json!({
+        "windows": { "json": { "success": true, "data": { "windows": [] }, "error": null } }
+    }));
+
+    glazewm_debug(Some(&scenario))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Inconsistencies (5):"))
+        .stdout(predicate::str::contains("not in the window list"));
+}
+
+#[test]
+fn fake_should_apply_and_reject_commands() {
+    Command::new(fake_glazewm_path())
+        .env_remove("FAKE_GLAZEWM_SCENARIO")
+        .args(["command", "focus", "--direction", "left"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"success\":true"));
+
+    Command::new(fake_glazewm_path())
+        .env_remove("FAKE_GLAZEWM_SCENARIO")
+        .args(["command", "wm-exit"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("fake-glazewm:"));
+}
+
+#[test]
+fn should_play_shipped_demo_scenarios() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("docs/scenarios");
+    for entry in std::fs::read_dir(dir).expect("scenario directory exists") {
+        let path = entry.unwrap().path();
+        Command::cargo_bin("glazewm-debug")
+            .unwrap()
+            .arg("dump")
+            .arg("--demo")
+            .arg(&path)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Workspace 1"));
+    }
+}
+
+#[test]
+fn should_reject_invalid_demo_scenario() {
+    let scenario = write_json(json!({
+        "monitors": [{ "rect": { "x": 0, "y": 0, "width": 800, "height": 600 }, "workspace": "1" }],
+        "timeline": [{ "at_ms": 100, "remove_monitor": 0 }]
+    }));
+
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .arg("dump")
+        .arg("--demo")
+        .arg(scenario.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("step 0 (at 100 ms) failed"));
+}
+
+/// `glazewm-debug dump --demo` with extra arguments
+fn demo_dump(args: &[&str]) -> Command {
+    let mut cmd = Command::cargo_bin("glazewm-debug").unwrap();
+    cmd.args(["dump", "--demo"]).args(args);
+    cmd
+}
+
+#[test]
+fn should_inject_faults_from_the_command_line() {
+    demo_dump(&["--fault-rate", "1", "--faults", "exit"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("non-zero exit code 1"));
+
+    demo_dump(&["--fault-rate", "1", "--faults", "unsuccessful"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("glazewm reported an error"));
+
+    demo_dump(&["--fault-latency", "500", "--timeout", "100"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("timed out"));
+
+    demo_dump(&["--fault-rate", "0"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Workspace Development"));
+}
+
+#[test]
+fn should_reject_unknown_fault_kinds() {
+    demo_dump(&["--fault-rate", "1", "--faults", "exit,flaky"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown fault 'flaky'"));
+}
+
+#[test]
+fn should_read_captured_state_from_stdin() {
+    let capture = glazewm_debug::cli::Simulator::demo().monitors_json();
+
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["dump", "--input", "-"])
+        .write_stdin(format!("{}\n", capture))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Workspace Development"))
+        .stdout(predicate::str::contains("Inconsistencies").not());
+}
+
+#[test]
+fn should_read_captured_state_from_file() {
+    let capture = write_json(glazewm_debug::cli::Simulator::demo().monitors_json());
+
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .arg("dump")
+        .arg("--input")
+        .arg(capture.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Workspace Communication"));
+
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["dump", "--input", "-"])
+        .write_stdin("not json")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to parse JSON response"));
+}
+
+/// Start glazewm-debug in the background and wait for the address it prints
+/// after `prefix` on stderr
+fn spawn_server(args: &[&str], prefix: &str) -> (std::process::Child, String) {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_glazewm-debug"))
+        .args(args)
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("start server");
+
+    // Keep draining the log afterwards so the server never blocks on a full pipe
+    let mut lines = BufReader::new(child.stderr.take().unwrap())
+        .lines()
+        .map_while(Result::ok);
+    let address = lines
+        .find_map(|line| line.strip_prefix(prefix).map(str::to_string))
+        .expect("server prints its address");
+    std::thread::spawn(move || lines.for_each(drop));
+    (child, address)
+}
+
+#[test]
+fn should_view_state_served_by_an_agent() {
+    let (mut agent, address) = spawn_server(
+        &["agent", "--demo", "--refresh-rate", "100", "127.0.0.1:0"],
+        "Serving glazewm state on ",
+    );
+
+    let viewer = Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["dump", "--connect", &address])
+        .assert();
+    agent.kill().unwrap();
+    agent.wait().unwrap();
+
+    viewer
+        .success()
+        .stdout(predicate::str::contains("Workspace Development"))
+        .stdout(predicate::str::contains("Inconsistencies").not());
+}
+
+#[test]
+fn should_serve_metrics_headless() {
+    use std::io::{Read, Write};
+
+    let (mut server, url) = spawn_server(
+        &[
+            "--demo",
+            "--refresh-rate",
+            "100",
+            "--http",
+            "0",
+            "--headless",
+        ],
+        "Serving the HTTP API on ",
+    );
+
+    let address = url.trim_start_matches("http://");
+    let mut stream = std::net::TcpStream::connect(address).expect("connect to HTTP API");
+    stream
+        .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    server.kill().unwrap();
+    server.wait().unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("glazewm_monitors 2\n"));
+    assert!(response.contains("# TYPE glazewm_query_duration_seconds histogram"));
+}
+
+#[test]
+fn should_log_changes_in_watch_mode() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let scenario = write_json(json!({
+        "monitors": [{ "rect": { "x": 0, "y": 0, "width": 800, "height": 600 }, "workspace": "1" }],
+        "timeline": [{ "at_ms": 200, "commands": ["shell-exec notepad Untitled - Notepad"] }]
+    }));
+
+    let mut watch = std::process::Command::new(env!("CARGO_BIN_EXE_glazewm-debug"))
+        .args(["watch", "--demo"])
+        .arg(scenario.path())
+        .args(["--refresh-rate", "50", "--json"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("start watch");
+
+    let events: Vec<Value> = BufReader::new(watch.stdout.take().unwrap())
+        .lines()
+        .map(|line| serde_json::from_str(&line.unwrap()).expect("NDJSON line"))
+        .take(3)
+        .collect();
+    watch.kill().unwrap();
+    watch.wait().unwrap();
+
+    assert_eq!(events[0]["event"], "started");
+    assert_eq!(events[1]["event"], "window_opened");
+    assert_eq!(events[1]["workspace"], "1");
+    assert_eq!(events[2]["event"], "focus_changed");
+    assert!(events[2]["time"].as_str().unwrap().ends_with('Z'));
+}
+
+#[test]
+fn should_dump_a_recording_from_stdin() {
+    let recording = Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["record", "--demo", "--refresh-rate", "10", "--count", "2"])
+        .output()
+        .expect("run record");
+    assert!(recording.status.success());
+    let stdout = String::from_utf8(recording.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 2);
+
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["dump", "--input", "-"])
+        .write_stdin(stdout)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Workspace Development"));
+}
+
+#[test]
+fn should_diff_captured_documents() {
+    let mut simulator = glazewm_debug::cli::Simulator::demo();
+    let before = write_json(simulator.monitors_json());
+    simulator
+        .execute("shell-exec notepad Untitled - Notepad")
+        .unwrap();
+    let after = write_json(simulator.monitors_json()["data"].clone());
+
+    let diff = |a: &NamedTempFile, b: &NamedTempFile| {
+        let mut cmd = Command::cargo_bin("glazewm-debug").unwrap();
+        cmd.arg("diff").arg(a.path()).arg(b.path());
+        cmd
+    };
+    diff(&before, &after)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "+ notepad: Untitled - Notepad on workspace Development",
+        ));
+    diff(&before, &before)
+        .assert()
+        .success()
+        .stdout("No changes\n");
+
+    let output = diff(&before, &after)
+        .arg("--json")
+        .output()
+        .expect("run diff");
+    let result: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let added = &result["windows"].as_array().unwrap().last().unwrap();
+    assert_eq!(added["kind"], "added");
+    assert_eq!(added["window"], "notepad: Untitled - Notepad");
+}
+
+#[test]
+fn should_exit_non_zero_when_validation_finds_problems() {
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["validate", "--demo"])
+        .assert()
+        .success()
+        .stdout("No problems found\n");
+
+    let scenario = write_json(json!({
+        "windows": { "json": { "success": true, "data": { "windows": [] }, "error": null } }
+    }));
+    let output = Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["validate", "--json", "--glazewm-path"])
+        .arg(fake_glazewm_path())
+        .env("FAKE_GLAZEWM_SCENARIO", scenario.path())
+        .env_remove("RUST_LOG")
+        .output()
+        .expect("run validate");
+    assert_eq!(output.status.code(), Some(1));
+    let validation: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(validation["valid"], false);
+    assert_eq!(validation["inconsistencies"].as_array().unwrap().len(), 5);
+}
+
+#[test]
+fn should_report_layout_deviations_for_configured_gaps() {
+    // The simulator tiles without gaps, so a 10px inner gap moves every
+    // window on the Development workspace by half of it
+    let output = Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["validate", "--json", "--inner-gap", "10", "--demo"])
+        .output()
+        .expect("run validate");
+    assert!(output.status.success());
+    let validation: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(validation["valid"], true);
+    let deviations = validation["layout_deviations"].as_array().unwrap();
+    assert_eq!(deviations.len(), 2);
+    assert_eq!(deviations[1]["window_id"], "sim-window-6");
+    assert_eq!(deviations[1]["expected"]["position"]["x"], 965);
+
+    // Within the tolerance nothing is flagged
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["validate", "--inner-gap", "10", "--layout-tolerance", "5"])
+        .arg("--demo")
+        .assert()
+        .success()
+        .stdout("No problems found\n");
+}
+
+#[test]
+fn should_check_layout_assertions() {
+    let spec = write_json(json!({
+        "name": "Demo layout",
+        "assertions": [
+            { "expect": "workspace_on_monitor", "workspace": "Development", "monitor": { "hardware_id": "DEMO0001" } },
+            { "expect": "no_offscreen_windows" }
+        ]
+    }));
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .arg("assert")
+        .arg(spec.path())
+        .arg("--demo")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "Demo layout\nPASS workspace Development",
+        ))
+        .stdout(predicate::str::ends_with("2 passed, 0 failed\n"));
+
+    let spec = write_json(json!({
+        "assertions": [{ "expect": "workspace_exists", "workspace": "42" }]
+    }));
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .arg("assert")
+        .arg(spec.path())
+        .args(["--json", "--demo"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("\"workspace 42 not found\""));
+}
+
+#[test]
+fn should_write_a_redacted_bug_report() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("report.tar");
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["report", "--demo", "--redact", "process", "--output"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Wrote bug report to"));
+
+    let archive = String::from_utf8_lossy(&std::fs::read(&path).unwrap()).into_owned();
+    assert!(archive.starts_with("glazewm-report/summary.md\0"));
+    assert!(archive.contains("glazewm-report/queries/app-metadata.json\0"));
+    assert!(archive.contains("- glazewm: simulator\n"));
+    assert!(archive.contains("INFO Running in demo mode"));
+    assert!(!archive.contains("Firefox - Documentation"));
+}
+
+#[test]
+fn should_redact_window_titles() {
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["dump", "--demo", "--redact", "process"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("firefox: firefox #1"))
+        .stdout(predicate::str::contains("Firefox - Documentation").not());
+
+    let rules = write_json(json!({ "rules": [{ "pattern": " - .*$", "replace": "" }] }));
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["dump", "--demo", "--redact-rules"])
+        .arg(rules.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("firefox: Firefox ("));
+}
+
+#[test]
+fn should_render_a_frame_without_a_terminal() {
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["frame", "--size", "100x30", "--mode", "compact", "--demo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Mode: Compact"))
+        .stdout(predicate::function(|out: &str| out.lines().count() == 30));
+
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["frame", "--format", "svg", "--demo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("<svg "))
+        .stdout(predicate::str::ends_with("</svg>\n"));
+}
+
+#[test]
+fn should_reject_connection_options_before_a_command() {
+    Command::cargo_bin("glazewm-debug")
+        .unwrap()
+        .args(["--timeout", "100", "dump"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
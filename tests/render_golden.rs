@@ -0,0 +1,212 @@
+// Golden render tests
+// Draw `Renderer::render` into an off-screen buffer at several terminal sizes
+// for every display mode and compare the text with tests/golden/render/.
+// After an intended layout change, regenerate the files and review the diff:
+//
+//     UPDATE_GOLDEN=1 cargo test --test render_golden
+
+use glazewm_debug::cli::Simulator;
+use glazewm_debug::domain::{GeometryAnalyzer, TilingModel};
+use glazewm_debug::tui::export::{self, FrameFormat, FrameSnapshot};
+use glazewm_debug::tui::{DisplayMode, SortOrder};
+use glazewm_debug::GlazewmParser;
+use serde_json::Value;
+use std::path::PathBuf;
+
+const SIZES: [(u16, u16); 3] = [(60, 20), (80, 24), (120, 40)];
+
+const MODES: [(DisplayMode, &str); 3] = [
+    (DisplayMode::Detailed, "detailed"),
+    (DisplayMode::Compact, "compact"),
+    (DisplayMode::FocusStack, "focus-stack"),
+];
+
+fn snapshot(simulator: &Simulator, mode: DisplayMode) -> FrameSnapshot {
+    snapshot_of(&simulator.monitors_json(), mode)
+}
+
+fn snapshot_of(json: &Value, mode: DisplayMode) -> FrameSnapshot {
+    let parsed = GlazewmParser::parse_monitors(json).unwrap();
+    FrameSnapshot {
+        deviations: TilingModel::default().deviations(&parsed.monitors),
+        geometry: GeometryAnalyzer::analyze(&parsed.monitors),
+        monitors: parsed.monitors,
+        placeholders: parsed.placeholders,
+        warnings: parsed.warnings,
+        inconsistencies: Vec::new(),
+        mode,
+        sort_order: SortOrder::Natural,
+        last_error: None,
+        perf: None,
+        notice: None,
+    }
+}
+
+/// Give the window of `process` a different width, as an app enforcing a
+/// minimum size would
+fn resize_window(json: &mut Value, process: &str, width: u64) -> bool {
+    if json["processName"] == process {
+        json["width"] = width.into();
+        return true;
+    }
+    match json {
+        Value::Object(map) => map
+            .values_mut()
+            .any(|value| resize_window(value, process, width)),
+        Value::Array(values) => values
+            .iter_mut()
+            .any(|value| resize_window(value, process, width)),
+        _ => false,
+    }
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join("render")
+        .join(format!("{}.txt", name))
+}
+
+/// Compare with the golden file, or rewrite it with `UPDATE_GOLDEN=1`;
+/// returns a description of the mismatch
+fn check(name: &str, snapshot: &FrameSnapshot, (width, height): (u16, u16)) -> Option<String> {
+    let buffer = export::render_frame(&snapshot.view(), width, height);
+    let actual = export::export(&buffer, FrameFormat::Text);
+    let path = golden_path(name);
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, &actual).unwrap();
+        return None;
+    }
+
+    let Ok(expected) = std::fs::read_to_string(&path) else {
+        return Some(format!("{}: no golden file at {}", name, path.display()));
+    };
+    if expected == actual {
+        return None;
+    }
+    let (line, (want, got)) = expected
+        .lines()
+        .chain(std::iter::repeat(""))
+        .zip(actual.lines().chain(std::iter::repeat("")))
+        .enumerate()
+        .find(|(_, (want, got))| want != got)
+        .unwrap_or((0, ("", "")));
+    Some(format!(
+        "{}: line {} differs\n  expected: {:?}\n  actual:   {:?}\n\n{}",
+        name,
+        line + 1,
+        want,
+        got,
+        actual
+    ))
+}
+
+fn assert_all(mismatches: Vec<String>) {
+    assert!(
+        mismatches.is_empty(),
+        "{} frame(s) differ from tests/golden/render \
+         (rerun with UPDATE_GOLDEN=1 if the change is intended):\n\n{}",
+        mismatches.len(),
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn should_match_golden_frames_for_every_mode_and_size() {
+    let simulator = Simulator::demo();
+    let mut mismatches = Vec::new();
+
+    for (mode, mode_name) in MODES {
+        let snapshot = snapshot(&simulator, mode);
+        for size in SIZES {
+            let name = format!("demo-{}-{}x{}", mode_name, size.0, size.1);
+            mismatches.extend(check(&name, &snapshot, size));
+        }
+    }
+    assert_all(mismatches);
+}
+
+#[test]
+fn should_match_golden_frames_for_wide_titles_and_nesting() {
+    let mut simulator = Simulator::demo();
+    for command in [
+        "shell-exec notepad 開発メモ - 日本語のタイトル",
+        // Wraps the other windows of the workspace in a split container
+        "move --direction up",
+    ] {
+        simulator.execute(command).unwrap();
+    }
+    let snapshot = snapshot(&simulator, DisplayMode::Detailed);
+
+    assert_all(
+        [(80, 24), (120, 40)]
+            .into_iter()
+            .filter_map(|size| {
+                let name = format!("nested-detailed-{}x{}", size.0, size.1);
+                check(&name, &snapshot, size)
+            })
+            .collect(),
+    );
+}
+
+#[test]
+fn should_match_golden_frames_for_layout_deviations() {
+    let mut json = Simulator::demo().monitors_json();
+    assert!(resize_window(&mut json, "Code", 900));
+
+    assert_all(
+        [
+            (DisplayMode::Detailed, "detailed", (120, 40)),
+            (DisplayMode::Compact, "compact", (80, 24)),
+        ]
+        .into_iter()
+        .filter_map(|(mode, mode_name, size)| {
+            let name = format!("deviation-{}-{}x{}", mode_name, size.0, size.1);
+            check(&name, &snapshot_of(&json, mode), size)
+        })
+        .collect(),
+    );
+}
+
+#[test]
+fn should_match_golden_frames_for_invalid_monitors() {
+    let mut json = Simulator::demo().monitors_json();
+    let mut unplugged = json["data"]["monitors"][0].clone();
+    unplugged["id"] = "sim-monitor-unplugged".into();
+    unplugged["width"] = 0.into();
+    unplugged["hasFocus"] = false.into();
+    unplugged["children"] = Value::Array(Vec::new());
+    json["data"]["monitors"]
+        .as_array_mut()
+        .unwrap()
+        .push(unplugged);
+
+    assert_all(
+        [
+            (DisplayMode::Detailed, "detailed", (120, 40)),
+            (DisplayMode::Compact, "compact", (120, 40)),
+        ]
+        .into_iter()
+        .filter_map(|(mode, mode_name, size)| {
+            let name = format!("invalid-monitor-{}-{}x{}", mode_name, size.0, size.1);
+            check(&name, &snapshot_of(&json, mode), size)
+        })
+        .collect(),
+    );
+}
+
+#[test]
+fn should_match_golden_frames_without_data() {
+    let mut snapshot = snapshot(&Simulator::new(), DisplayMode::Detailed);
+    snapshot.monitors.clear();
+    snapshot.last_error = Some("glazewm exited with status 1".to_string());
+
+    assert_all(
+        check("no-data-80x24", &snapshot, (80, 24))
+            .into_iter()
+            .collect(),
+    );
+}
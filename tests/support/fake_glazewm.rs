@@ -0,0 +1,109 @@
+// Fake glazewm executable for end-to-end tests
+//
+// Mimics `glazewm query monitors|windows` and `glazewm command ...`.
+// Answers come from the built-in simulator unless the scenario file named by
+// FAKE_GLAZEWM_SCENARIO overrides them:
+//
+// {
+//   "commands": ["shell-exec notepad"],
+//   "monitors": { "delay_ms": 500, "exit_code": 3, "stderr": "ipc error" },
+//   "windows":  { "stdout": "{ not json" },
+//   "command":  { "json": { "success": false, "data": null, "error": "denied" } }
+// }
+//
+// `commands` are applied to the simulator before answering. A response may set
+// `stdout` (raw text) or `json` (a value to print); otherwise the simulator
+// answers.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::process::exit;
+use std::thread::sleep;
+use std::time::Duration;
+
+use glazewm_debug::cli::Simulator;
+
+const SCENARIO_ENV: &str = "FAKE_GLAZEWM_SCENARIO";
+
+#[derive(Debug, Default, Deserialize)]
+struct Scenario {
+    #[serde(default)]
+    commands: Vec<String>,
+    #[serde(flatten)]
+    responses: HashMap<String, Response>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Response {
+    #[serde(default)]
+    delay_ms: u64,
+    #[serde(default)]
+    exit_code: i32,
+    stderr: Option<String>,
+    stdout: Option<String>,
+    json: Option<Value>,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut scenario = load_scenario();
+
+    let mut simulator = Simulator::demo();
+    for command in &scenario.commands {
+        if let Err(e) = simulator.execute(command) {
+            fail(2, &format!("scenario command '{}' failed: {}", command, e));
+        }
+    }
+
+    // `query monitors` answers with the "monitors" response, `command ...`
+    // with the "command" response
+    let (key, default_output) = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["query", "monitors"] => ("monitors", Ok(simulator.monitors_json())),
+        ["query", "windows"] => ("windows", Ok(simulator.windows_json())),
+        ["command", ref rest @ ..] if !rest.is_empty() => (
+            "command",
+            simulator
+                .execute(&rest.join(" "))
+                .map(|()| json!({ "success": true, "data": null, "error": null }))
+                .map_err(|e| e.to_string()),
+        ),
+        _ => fail(
+            2,
+            "usage: glazewm query <monitors|windows> | command <args...>",
+        ),
+    };
+
+    let response = scenario.responses.remove(key).unwrap_or_default();
+
+    sleep(Duration::from_millis(response.delay_ms));
+
+    if let Some(stderr) = &response.stderr {
+        eprint!("{}", stderr);
+    }
+
+    match (response.stdout, response.json, default_output) {
+        (Some(stdout), _, _) => print!("{}", stdout),
+        (None, Some(value), _) => println!("{}", value),
+        (None, None, Ok(value)) => println!("{}", value),
+        (None, None, Err(reason)) => fail(1, &reason),
+    }
+
+    exit(response.exit_code);
+}
+
+fn load_scenario() -> Scenario {
+    let Ok(path) = std::env::var(SCENARIO_ENV) else {
+        return Scenario::default();
+    };
+
+    let text = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| fail(2, &format!("cannot read scenario {}: {}", path, e)));
+    serde_json::from_str(&text)
+        .unwrap_or_else(|e| fail(2, &format!("invalid scenario {}: {}", path, e)))
+}
+
+fn fail(code: i32, message: &str) -> ! {
+    eprintln!("fake-glazewm: {}", message);
+    exit(code);
+}